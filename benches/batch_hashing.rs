@@ -0,0 +1,34 @@
+//! Hashing 100k synthetic transactions one `hash_event` call at a time
+//! against one `hash_events` pass, for each codec.
+
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use gitdigital_ledger_core::core::event::LedgerEvent;
+use gitdigital_ledger_core::storage::codec::{CborCodec, JsonCodec, RecordCodec};
+
+const EVENTS: usize = 100_000;
+
+fn batch_hashing(c: &mut Criterion) {
+    let events = common::transactions(EVENTS, 100);
+    let refs: Vec<&LedgerEvent> = events.iter().collect();
+    let codecs: [(&str, Box<dyn RecordCodec>); 2] = [("json", Box::new(JsonCodec)), ("cbor", Box::new(CborCodec))];
+
+    let mut group = c.benchmark_group("hash_100k_transactions");
+    group.sample_size(10);
+    for (name, codec) in &codecs {
+        group.bench_function(format!("{}/single_calls", name), |b| {
+            b.iter(|| {
+                refs.iter()
+                    .map(|event| codec.hash_event(event))
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            })
+        });
+        group.bench_function(format!("{}/batch", name), |b| b.iter(|| codec.hash_events(&refs).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, batch_hashing);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+//! Encoded size and hashing throughput of each `RecordCodec` over a batch
+//! of financial transactions.
+
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use gitdigital_ledger_core::core::event::LedgerEvent;
+use gitdigital_ledger_core::storage::codec::{CborCodec, JsonCodec, RecordCodec};
+
+const EVENTS: usize = 10_000;
+
+fn codecs() -> [(&'static str, Box<dyn RecordCodec>); 2] {
+    [("json", Box::new(JsonCodec)), ("cbor", Box::new(CborCodec))]
+}
+
+fn encode(c: &mut Criterion) {
+    let events = common::transactions(EVENTS, 100);
+
+    let mut group = c.benchmark_group("encode_10k_transactions");
+    for (name, codec) in &codecs() {
+        let size: usize = events.iter().map(|event| codec.encode_event(event).unwrap().len()).sum();
+        println!("{}: {} bytes, {} per transaction", name, size, size / EVENTS);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                events
+                    .iter()
+                    .map(|event| codec.encode_event(event).unwrap().len())
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn hash(c: &mut Criterion) {
+    let events = common::transactions(EVENTS, 100);
+    let refs: Vec<&LedgerEvent> = events.iter().collect();
+
+    let mut group = c.benchmark_group("hash_10k_transactions");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    for (name, codec) in &codecs() {
+        group.bench_function(*name, |b| b.iter(|| codec.hash_events(&refs).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, hash);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! Synthetic events shared by the benchmarks
+#![allow(dead_code)]
+
+use gitdigital_ledger_core::core::event::{FinancialTransaction, LedgerEvent, Money};
+
+pub fn usd(amount: i64) -> Money {
+    Money::with_currency_defaults(rust_decimal::Decimal::new(amount, 2), "USD")
+}
+
+/// `count` transfers among `accounts` accounts, each with its own id and
+/// amount
+pub fn transactions(count: usize, accounts: usize) -> Vec<LedgerEvent> {
+    (0..count)
+        .map(|i| {
+            let transaction = FinancialTransaction::builder()
+                .transaction_id(format!("tx-{}", i))
+                .from_account(format!("acct-{}", i % accounts))
+                .to_account(format!("acct-{}", (i + 1) % accounts))
+                .amount(usd(i as i64 + 1))
+                .description("synthetic transfer")
+                .build()
+                .expect("valid transfer");
+            LedgerEvent::FinancialTransaction(transaction)
+        })
+        .collect()
+}
@@ -0,0 +1,169 @@
+//! Storage fetches and time of `StorageContextProvider` over a batch of
+//! 1000 transfers among four accounts, with and without a `ContextCache`.
+//! The storage is an in-memory stand-in that counts the queries made.
+
+mod common;
+
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, Criterion};
+use gitdigital_ledger_core::compliance::context::{ContextCache, StorageContextProvider};
+use gitdigital_ledger_core::compliance::validator::{ContextProvider, ValidationContext};
+use gitdigital_ledger_core::core::event::LedgerEvent;
+use gitdigital_ledger_core::core::schema::CURRENT_SCHEMA_VERSION;
+use gitdigital_ledger_core::core::LedgerRecord;
+use gitdigital_ledger_core::storage::append_only::{
+    AppendOnlyStorage, ChainSummary, IndexEntry, StorageError, TagFilter,
+};
+use gitdigital_ledger_core::storage::codec::JSON_CODEC_ID;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const CHAIN_ID: &str = "bench";
+const EVENTS: usize = 1_000;
+const ACCOUNTS: usize = 4;
+
+/// Serves a fixed history to `query_records`, counting the calls; nothing
+/// else is used by the provider
+struct CountingStorage {
+    records: Vec<LedgerRecord>,
+    queries: AtomicUsize,
+}
+
+impl CountingStorage {
+    fn new(history: Vec<LedgerEvent>) -> Self {
+        let records = history
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| LedgerRecord {
+                event_id: format!("event-{}", i),
+                event,
+                metadata: serde_json::Value::Null,
+                timestamp: chrono::Utc::now(),
+                previous_hash: None,
+                chain_id: CHAIN_ID.to_string(),
+                signature: None,
+                violations: Vec::new(),
+                codec: JSON_CODEC_ID.to_string(),
+                schema_version: CURRENT_SCHEMA_VERSION,
+                legacy_event_bytes: None,
+                sequence: Some(i as u64),
+                nonce: None,
+                merkle_root_at_append: None,
+                visibility: Vec::new(),
+            })
+            .collect();
+        Self { records, queries: AtomicUsize::new(0) }
+    }
+}
+
+#[async_trait]
+impl AppendOnlyStorage for CountingStorage {
+    async fn append(&self, _record: LedgerRecord) -> Result<(), StorageError> {
+        unimplemented!("read-only stand-in")
+    }
+
+    async fn append_atomic(&self, _record: LedgerRecord, _index_entries: &[IndexEntry]) -> Result<(), StorageError> {
+        unimplemented!("read-only stand-in")
+    }
+
+    async fn records_by_index(&self, _chain_id: &str, _index: &str, _key: &str) -> Result<Vec<LedgerRecord>, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+
+    async fn get(&self, _chain_id: &str, _event_id: &str) -> Result<Option<LedgerRecord>, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+
+    async fn get_by_idempotency_key(&self, _chain_id: &str, _key: &str) -> Result<Option<LedgerRecord>, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+
+    async fn query_records(
+        &self,
+        _chain_id: &str,
+        _entity_id: Option<&str>,
+        start_time: Option<chrono::DateTime<chrono::Utc>>,
+        _end_time: Option<chrono::DateTime<chrono::Utc>>,
+        event_types: Option<&[String]>,
+        _tags: Option<&TagFilter>,
+        _caller_scopes: Option<&[String]>,
+    ) -> Result<Vec<LedgerRecord>, StorageError> {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .records
+            .iter()
+            .filter(|record| start_time.map_or(true, |start| record.timestamp >= start))
+            .filter(|record| {
+                event_types.map_or(true, |types| types.iter().any(|t| t == record.event.event_type_name()))
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn verify_chain(&self, _chain_id: &str) -> Result<bool, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+
+    async fn get_latest_hash(&self, _chain_id: &str) -> Result<Option<String>, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+
+    async fn get_merkle_root(&self, _chain_id: &str) -> Result<String, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+
+    async fn rebuild_merkle_tree(&self, _chain_id: &str) -> Result<String, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+
+    async fn replace_with_tombstone(&self, _event_ids: &[String], _tombstone: LedgerRecord) -> Result<(), StorageError> {
+        unimplemented!("read-only stand-in")
+    }
+
+    async fn list_chains(&self) -> Result<Vec<ChainSummary>, StorageError> {
+        unimplemented!("not used by the provider")
+    }
+}
+
+fn context_cache(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let storage = Arc::new(CountingStorage::new(common::transactions(500, ACCOUNTS)));
+    let batch = common::transactions(EVENTS, ACCOUNTS);
+
+    let uncached = StorageContextProvider::new(storage.clone(), CHAIN_ID, chrono::Duration::hours(1));
+    let cache = Arc::new(ContextCache::new(Duration::from_secs(60), 1_000));
+    let cached = StorageContextProvider::new(storage.clone(), CHAIN_ID, chrono::Duration::hours(1))
+        .with_cache(cache.clone());
+    let providers: [(&str, &dyn ContextProvider); 2] = [("uncached", &uncached), ("cached", &cached)];
+
+    let populate_batch = |provider: &dyn ContextProvider| {
+        runtime.block_on(async {
+            for event in &batch {
+                provider.populate(event, &mut ValidationContext::new()).await.unwrap();
+            }
+        })
+    };
+
+    for (name, provider) in providers {
+        let before = storage.queries.load(Ordering::Relaxed);
+        populate_batch(provider);
+        println!(
+            "{}: {} storage queries for {} events",
+            name,
+            storage.queries.load(Ordering::Relaxed) - before,
+            EVENTS
+        );
+    }
+    println!("cache: {:?}", cache.stats());
+
+    let mut group = c.benchmark_group("populate_1000_events_4_accounts");
+    group.sample_size(10);
+    for (name, provider) in providers {
+        group.bench_function(name, |b| b.iter(|| populate_batch(provider)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, context_cache);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Allocations and time of the built-in rules over a stream of compliant
+//! transactions, the path every append takes. Allocations are counted by a
+//! wrapping global allocator; `async_trait` boxes each `evaluate` future,
+//! so one allocation per rule and event is the floor.
+
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use gitdigital_ledger_core::compliance::validator::{
+    AmountLimitRule, ComplianceValidator, RoundAmountRule, Rule, SanctionedCountriesRule, ValidationContext,
+};
+use rust_decimal::Decimal;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const EVENTS: usize = 1_000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(AmountLimitRule::new(Decimal::new(10_000, 0), "USD")),
+        Box::new(SanctionedCountriesRule::new(vec!["KP", "IR"])),
+        Box::new(RoundAmountRule::new(Decimal::new(1_000, 0), Decimal::new(100, 0))),
+    ]
+}
+
+fn rule_allocations(c: &mut Criterion) {
+    // Amounts of a few cents to ten dollars: under every limit, never round
+    let events = common::transactions(EVENTS, 50);
+    let context = ValidationContext::new();
+
+    for rule in rules() {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        for event in &events {
+            let violations = futures::executor::block_on(rule.evaluate(event, &context)).unwrap();
+            assert!(violations.is_empty(), "{} flagged a compliant transaction", rule.get_rule_id());
+        }
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        println!(
+            "{}: {:.2} allocations per compliant transaction",
+            rule.get_rule_id(),
+            allocations as f64 / EVENTS as f64
+        );
+    }
+
+    let mut validator = ComplianceValidator::new();
+    for rule in rules() {
+        validator.add_rule(rule);
+    }
+    c.bench_function("validate_1000_compliant_transactions", |b| {
+        b.iter(|| {
+            for event in &events {
+                futures::executor::block_on(validator.validate(event)).unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(benches, rule_allocations);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+//! Validating the same 1000 transactions through several overlapping rule
+//! sets, with and without the pure-rule cache. The cache is kept across
+//! iterations, as during batch pre-screening that revisits events.
+
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use gitdigital_ledger_core::compliance::validator::{
+    AmountLimitRule, ComplianceValidator, RoundAmountRule, SanctionedCountriesRule,
+};
+use rust_decimal::Decimal;
+
+const EVENTS: usize = 1_000;
+const RULE_SETS: [&str; 3] = ["limits", "screening", "full"];
+
+fn validator(cache_capacity: Option<usize>) -> ComplianceValidator {
+    let mut validator = ComplianceValidator::new();
+    if let Some(capacity) = cache_capacity {
+        validator = validator.with_rule_cache(capacity);
+    }
+    validator.add_rule(Box::new(AmountLimitRule::new(Decimal::new(10_000, 0), "USD")));
+    validator.add_rule(Box::new(SanctionedCountriesRule::new(vec!["KP", "IR"])));
+    validator.add_rule(Box::new(RoundAmountRule::new(Decimal::new(1_000, 0), Decimal::new(100, 0))));
+    validator.create_rule_set("limits", vec!["AMOUNT_LIMIT", "ROUND_AMOUNT"]);
+    validator.create_rule_set("screening", vec!["SANCTIONED_COUNTRIES", "AMOUNT_LIMIT"]);
+    validator.create_rule_set("full", vec!["AMOUNT_LIMIT", "SANCTIONED_COUNTRIES", "ROUND_AMOUNT"]);
+    validator
+}
+
+fn rule_cache(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let events = common::transactions(EVENTS, 50);
+
+    let mut group = c.benchmark_group("validate_1000_events_across_rule_sets");
+    for (name, capacity) in [("uncached", None), ("cached", Some(4 * EVENTS))] {
+        let validator = validator(capacity);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    for event in &events {
+                        for rule_set in RULE_SETS {
+                            validator.validate_with_rule_set(event, rule_set).await.unwrap();
+                        }
+                    }
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, rule_cache);
+criterion_main!(benches);
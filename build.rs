@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Only the grpc feature needs generated code, so plain builds don't
+    // require protoc
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/ledger.proto");
+        tonic_build::compile_protos("proto/ledger.proto")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,235 @@
+//! gRPC service over `DigitalLedger`, generated from `proto/ledger.proto`.
+//! Only compiled with the `grpc` feature.
+#![cfg(feature = "grpc")]
+
+use crate::compliance::validator::Violation;
+use crate::core::event::LedgerEvent;
+use crate::core::{DigitalLedger, LedgerError, LedgerRecord};
+use crate::storage::append_only::TagFilter;
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Code, Request, Response, Status};
+
+pub mod proto {
+    tonic::include_proto!("ledger.v1");
+}
+
+use proto::ledger_server::{Ledger, LedgerServer};
+
+/// Records buffered ahead of a slow audit-trail client
+const AUDIT_TRAIL_STREAM_BUFFER: usize = 64;
+
+#[derive(Clone)]
+pub struct LedgerService {
+    ledger: Arc<DigitalLedger>,
+}
+
+impl LedgerService {
+    pub fn new(ledger: Arc<DigitalLedger>) -> Self {
+        Self { ledger }
+    }
+
+    pub fn into_server(self) -> LedgerServer<Self> {
+        LedgerServer::new(self)
+    }
+}
+
+/// Compliance rejections are `PERMISSION_DENIED` with the violations as JSON
+/// in the status details; field validation failures are `INVALID_ARGUMENT`
+/// with the field errors, mirroring the HTTP error body.
+impl From<LedgerError> for Status {
+    fn from(err: LedgerError) -> Self {
+        let message = err.to_string();
+        match &err {
+            LedgerError::ComplianceViolation { violations, .. } => {
+                with_json_details(Code::PermissionDenied, message, violations)
+            }
+            LedgerError::ValidationError { field_errors } => {
+                with_json_details(Code::InvalidArgument, message, field_errors)
+            }
+            LedgerError::LedgerSealed | LedgerError::NotSealed | LedgerError::ReplicationGap { .. } => {
+                Status::failed_precondition(message)
+            }
+            LedgerError::ReplicationFork { .. } => Status::aborted(message),
+            LedgerError::IdempotencyConflict { .. } | LedgerError::DuplicateEvent { .. } => {
+                Status::already_exists(message)
+            }
+            LedgerError::ImportRejected(_)
+            | LedgerError::TimestampOutOfBounds { .. }
+            | LedgerError::EventTypeNotAllowed { .. } => Status::invalid_argument(message),
+            LedgerError::NotQuarantined { .. } | LedgerError::ChainNotFound { .. } => {
+                Status::not_found(message)
+            }
+            LedgerError::StorageError(_) | LedgerError::EnrichmentFailed(_) => Status::internal(message),
+            LedgerError::MoneyError(_) => Status::out_of_range(message),
+            LedgerError::AppendQueueClosed | LedgerError::TemporarilyReadOnly => Status::unavailable(message),
+        }
+    }
+}
+
+fn with_json_details<T: serde::Serialize>(code: Code, message: String, details: &T) -> Status {
+    match serde_json::to_vec(details) {
+        Ok(bytes) => Status::with_details(code, message, bytes.into()),
+        Err(_) => Status::new(code, message),
+    }
+}
+
+fn parse_json(field: &str, json: &str) -> Result<serde_json::Value, Status> {
+    serde_json::from_str(json).map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", field, e)))
+}
+
+fn to_timestamp(ts: chrono::DateTime<chrono::Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: ts.timestamp(),
+        nanos: ts.timestamp_subsec_nanos() as i32,
+    }
+}
+
+fn from_timestamp(ts: prost_types::Timestamp) -> Result<chrono::DateTime<chrono::Utc>, Status> {
+    chrono::DateTime::from_timestamp(ts.seconds, ts.nanos.max(0) as u32)
+        .ok_or_else(|| Status::invalid_argument("Timestamp out of range"))
+}
+
+impl TryFrom<proto::LedgerEvent> for LedgerEvent {
+    type Error = Status;
+
+    fn try_from(event: proto::LedgerEvent) -> Result<Self, Status> {
+        let event: LedgerEvent = serde_json::from_str(&event.event_json)
+            .map_err(|e| Status::invalid_argument(format!("Invalid event_json: {}", e)))?;
+        Ok(event)
+    }
+}
+
+impl From<&LedgerEvent> for proto::LedgerEvent {
+    fn from(event: &LedgerEvent) -> Self {
+        proto::LedgerEvent {
+            event_type: event.event_type_name().to_string(),
+            event_json: serde_json::to_string(event).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&Violation> for proto::Violation {
+    fn from(violation: &Violation) -> Self {
+        proto::Violation {
+            rule_id: violation.rule_id.clone(),
+            severity: format!("{:?}", violation.severity),
+            message: violation.message.clone(),
+            evidence_json: violation.evidence.to_string(),
+        }
+    }
+}
+
+impl From<&LedgerRecord> for proto::LedgerRecord {
+    fn from(record: &LedgerRecord) -> Self {
+        proto::LedgerRecord {
+            event_id: record.event_id.clone(),
+            event: Some((&record.event).into()),
+            metadata_json: record.metadata.to_string(),
+            timestamp: Some(to_timestamp(record.timestamp)),
+            previous_hash: record.previous_hash.clone(),
+            chain_id: record.chain_id.clone(),
+            signature: record.signature.clone(),
+            violations: record.violations.iter().map(Into::into).collect(),
+            codec: record.codec.clone(),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Ledger for LedgerService {
+    async fn append_event(
+        &self,
+        request: Request<proto::AppendEventRequest>,
+    ) -> Result<Response<proto::AppendEventResponse>, Status> {
+        let request = request.into_inner();
+        let event: LedgerEvent = request
+            .event
+            .ok_or_else(|| Status::invalid_argument("event is required"))?
+            .try_into()?;
+        let metadata = request
+            .metadata_json
+            .as_deref()
+            .map(|json| parse_json("metadata_json", json))
+            .transpose()?;
+
+        let event_id = self.ledger.append_event(event, metadata).await?;
+        Ok(Response::new(proto::AppendEventResponse { event_id }))
+    }
+
+    type GetAuditTrailStream = ReceiverStream<Result<proto::LedgerRecord, Status>>;
+
+    async fn get_audit_trail(
+        &self,
+        request: Request<proto::GetAuditTrailRequest>,
+    ) -> Result<Response<Self::GetAuditTrailStream>, Status> {
+        let request = request.into_inner();
+        let start_time = request.start_time.map(from_timestamp).transpose()?;
+        let end_time = request.end_time.map(from_timestamp).transpose()?;
+        let event_types = (!request.event_types.is_empty()).then_some(request.event_types);
+        let tags = (!request.tags.is_empty()).then(|| match request.match_all_tags {
+            true => TagFilter::all(request.tags),
+            false => TagFilter::any(request.tags),
+        });
+
+        let records = self
+            .ledger
+            .get_audit_trail(
+                request.entity_id.as_deref(),
+                start_time,
+                end_time,
+                event_types.as_deref(),
+                tags.as_ref(),
+                None,
+            )
+            .await?;
+
+        // Each record is converted only when the client is ready for it
+        let (tx, rx) = tokio::sync::mpsc::channel(AUDIT_TRAIL_STREAM_BUFFER);
+        tokio::spawn(async move {
+            for record in &records {
+                if tx.send(Ok(record.into())).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn verify_integrity(
+        &self,
+        _request: Request<proto::VerifyIntegrityRequest>,
+    ) -> Result<Response<proto::VerifyIntegrityResponse>, Status> {
+        let is_valid = self.ledger.verify_integrity().await?;
+        Ok(Response::new(proto::VerifyIntegrityResponse { is_valid }))
+    }
+
+    async fn get_merkle_root(
+        &self,
+        _request: Request<proto::GetMerkleRootRequest>,
+    ) -> Result<Response<proto::GetMerkleRootResponse>, Status> {
+        let merkle_root = self.ledger.get_merkle_root().await?;
+        Ok(Response::new(proto::GetMerkleRootResponse { merkle_root }))
+    }
+
+    async fn get_merkle_proof(
+        &self,
+        request: Request<proto::GetMerkleProofRequest>,
+    ) -> Result<Response<proto::GetMerkleProofResponse>, Status> {
+        let event_id = request.into_inner().event_id;
+        let proof = self
+            .ledger
+            .get_merkle_proof(&event_id)
+            .await?
+            .ok_or_else(|| Status::not_found(format!("No record {}", event_id)))?;
+
+        Ok(Response::new(proto::GetMerkleProofResponse {
+            event_id: proof.event_id,
+            leaf_index: proof.leaf_index as u64,
+            tree_size: proof.tree_size as u64,
+            path: proof.path,
+            root: proof.root,
+        }))
+    }
+}
@@ -1,6 +1,8 @@
 use axum::{
     routing::{get, post},
     Router, Json, extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
 };
 use crate::core::{DigitalLedger, LedgerError};
 use crate::core::event::LedgerEvent;
@@ -19,6 +21,7 @@ pub fn create_router(state: ApiState) -> Router {
         .route("/audit", get(get_audit_trail))
         .route("/integrity", get(verify_integrity))
         .route("/merkle-root", get(get_merkle_root))
+        .route("/stats", get(get_stats))
         .with_state(state)
 }
 
@@ -58,7 +61,7 @@ async fn append_event(
 async fn get_audit_trail(
     State(state): State<ApiState>,
 ) -> Result<Json<Vec<crate::core::LedgerRecord>>, LedgerError> {
-    let records = state.ledger.get_audit_trail(None, None, None).await?;
+    let records = state.ledger.get_audit_trail(None, None, None, None, None, None).await?;
     Ok(Json(records))
 }
 
@@ -89,6 +92,67 @@ async fn get_merkle_root(
     }))
 }
 
+async fn get_stats(
+    State(state): State<ApiState>,
+) -> Result<Json<crate::core::LedgerStats>, LedgerError> {
+    Ok(Json(state.ledger.stats().await?))
+}
+
+#[derive(Serialize)]
+struct ErrorResponse<'a> {
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    violations: Option<&'a [crate::compliance::validator::Violation]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field_errors: Option<&'a [crate::core::event::FieldError]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alert_event_id: Option<&'a str>,
+}
+
+impl IntoResponse for LedgerError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            LedgerError::ComplianceViolation { .. }
+            | LedgerError::ValidationError { .. }
+            | LedgerError::TimestampOutOfBounds { .. }
+            | LedgerError::EventTypeNotAllowed { .. }
+            | LedgerError::MoneyError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            LedgerError::LedgerSealed
+            | LedgerError::NotSealed
+            | LedgerError::IdempotencyConflict { .. }
+            | LedgerError::DuplicateEvent { .. }
+            | LedgerError::ReplicationGap { .. }
+            | LedgerError::ReplicationFork { .. } => StatusCode::CONFLICT,
+            LedgerError::ImportRejected(_) => StatusCode::BAD_REQUEST,
+            LedgerError::NotQuarantined { .. } | LedgerError::ChainNotFound { .. } => StatusCode::NOT_FOUND,
+            LedgerError::StorageError(_) | LedgerError::EnrichmentFailed(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            LedgerError::AppendQueueClosed | LedgerError::TemporarilyReadOnly => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+        };
+        
+        let body = ErrorResponse {
+            error: self.to_string(),
+            violations: match &self {
+                LedgerError::ComplianceViolation { violations, .. } => Some(violations),
+                _ => None,
+            },
+            field_errors: match &self {
+                LedgerError::ValidationError { field_errors } => Some(field_errors),
+                _ => None,
+            },
+            alert_event_id: match &self {
+                LedgerError::ComplianceViolation { alert_event_id, .. } => alert_event_id.as_deref(),
+                _ => None,
+            },
+        };
+        
+        (status, Json(body)).into_response()
+    }
+}
+
 #[derive(Serialize)]
 struct HealthResponse {
     status: String,
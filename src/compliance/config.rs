@@ -0,0 +1,260 @@
+use crate::compliance::validator::{
+    AmountLimitRule, BusinessHoursRule, ComplianceValidator, DuplicateTransactionRule,
+    ExemptionPredicate, ExemptionRule, RoundAmountRule, Rule, RuleSeverity,
+    SanctionedCountriesRule, StructuringRule, SufficientFundsRule,
+};
+use crate::core::event::AccountType;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Operator-supplied description of the rules a validator should run.
+///
+/// Rules are keyed by rule id, which also selects the built-in rule type:
+///
+/// ```yaml
+/// rules:
+///   AMOUNT_LIMIT:
+///     parameters:
+///       limits: {USD: 1000000, EUR: 850000}
+///       default_limit: 500000
+///   SANCTIONED_COUNTRIES:
+///     parameters:
+///       countries: [CU, IR, KP, SY]
+/// rule_sets:
+///   default: [AMOUNT_LIMIT, SANCTIONED_COUNTRIES]
+///   onboarding:
+///     rules: [SANCTIONED_COUNTRIES]
+///     blocking_severity: Warning
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ValidatorConfig {
+    #[serde(default)]
+    pub rules: HashMap<String, RuleConfig>,
+    #[serde(default)]
+    pub rule_sets: HashMap<String, RuleSetEntry>,
+    /// Lowest severity that blocks outside rule sets, `Critical` if unset
+    #[serde(default)]
+    pub blocking_severity: Option<RuleSeverity>,
+    /// See `ComplianceValidator::with_dedup`
+    #[serde(default)]
+    pub dedup: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub parameters: Value,
+    /// Events exempt from this rule, e.g. `[{account: treasury}]`
+    #[serde(default)]
+    pub exemptions: Vec<ExemptionPredicate>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A rule set given either as a bare list of rule ids, blocking on
+/// `Critical`, or with its own blocking severity
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RuleSetEntry {
+    Rules(Vec<String>),
+    Detailed {
+        rules: Vec<String>,
+        blocking_severity: RuleSeverity,
+    },
+}
+
+impl RuleSetEntry {
+    pub fn rule_ids(&self) -> &[String] {
+        match self {
+            RuleSetEntry::Rules(rules) | RuleSetEntry::Detailed { rules, .. } => rules,
+        }
+    }
+
+    pub fn blocking_severity(&self) -> RuleSeverity {
+        match self {
+            RuleSetEntry::Rules(_) => RuleSeverity::Critical,
+            RuleSetEntry::Detailed { blocking_severity, .. } => blocking_severity.clone(),
+        }
+    }
+}
+
+impl ValidatorConfig {
+    pub fn from_json_str(s: &str) -> Result<Self> {
+        serde_json::from_str(s).context("Invalid validator configuration JSON")
+    }
+
+    pub fn from_yaml_str(s: &str) -> Result<Self> {
+        serde_yaml::from_str(s).context("Invalid validator configuration YAML")
+    }
+}
+
+/// Either a single `limit` and `currency`, or per-currency `limits`, each
+/// with an optional `default_limit`
+#[derive(Deserialize)]
+struct AmountLimitParams {
+    #[serde(default)]
+    limit: Option<rust_decimal::Decimal>,
+    #[serde(default)]
+    currency: Option<String>,
+    #[serde(default)]
+    limits: HashMap<String, rust_decimal::Decimal>,
+    #[serde(default)]
+    default_limit: Option<rust_decimal::Decimal>,
+}
+
+#[derive(Deserialize)]
+struct SanctionedCountriesParams {
+    countries: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RoundAmountParams {
+    threshold: rust_decimal::Decimal,
+    multiple: rust_decimal::Decimal,
+}
+
+#[derive(Deserialize)]
+struct DuplicateTransactionParams {
+    #[serde(default = "default_duplicate_window_seconds")]
+    window_seconds: i64,
+}
+
+fn default_duplicate_window_seconds() -> i64 {
+    60
+}
+
+#[derive(Deserialize)]
+struct StructuringParams {
+    report_threshold: rust_decimal::Decimal,
+    tolerance: rust_decimal::Decimal,
+    window_seconds: i64,
+    min_occurrences: usize,
+}
+
+#[derive(Deserialize)]
+struct BusinessHoursParams {
+    timezone: String,
+    open: chrono::NaiveTime,
+    close: chrono::NaiveTime,
+    days: Vec<chrono::Weekday>,
+}
+
+#[derive(Deserialize)]
+struct SufficientFundsParams {
+    #[serde(default)]
+    protected_types: Option<Vec<AccountType>>,
+}
+
+fn parameters<T: serde::de::DeserializeOwned>(rule_id: &str, value: &Value) -> Result<T> {
+    // Omitted parameters read as an empty object so all-default params work
+    let value = match value {
+        Value::Null => Value::Object(Default::default()),
+        other => other.clone(),
+    };
+    serde_json::from_value(value)
+        .with_context(|| format!("Invalid parameters for rule {}", rule_id))
+}
+
+fn build_rule(rule_id: &str, config: &RuleConfig) -> Result<Box<dyn Rule>> {
+    let params = &config.parameters;
+    let mut rule: Box<dyn Rule> = match rule_id {
+        "AMOUNT_LIMIT" => {
+            let mut p: AmountLimitParams = parameters(rule_id, params)?;
+            match (p.limit, p.currency) {
+                (Some(limit), Some(currency)) => {
+                    p.limits.insert(currency, limit);
+                }
+                (None, None) => {}
+                _ => bail!("Rule {} needs both limit and currency, or neither", rule_id),
+            }
+            if p.limits.is_empty() && p.default_limit.is_none() {
+                bail!("Rule {} has no limits", rule_id);
+            }
+            let rule = AmountLimitRule::per_currency(p.limits);
+            Box::new(match p.default_limit {
+                Some(limit) => rule.with_default_limit(limit),
+                None => rule,
+            })
+        }
+        "SANCTIONED_COUNTRIES" => {
+            let p: SanctionedCountriesParams = parameters(rule_id, params)?;
+            Box::new(SanctionedCountriesRule::new(
+                p.countries.iter().map(String::as_str).collect(),
+            ))
+        }
+        "ROUND_AMOUNT" => {
+            let p: RoundAmountParams = parameters(rule_id, params)?;
+            Box::new(RoundAmountRule::new(p.threshold, p.multiple))
+        }
+        "DUPLICATE_TRANSACTION" => {
+            let p: DuplicateTransactionParams = parameters(rule_id, params)?;
+            Box::new(DuplicateTransactionRule::new(chrono::Duration::seconds(p.window_seconds)))
+        }
+        "STRUCTURING" => {
+            let p: StructuringParams = parameters(rule_id, params)?;
+            Box::new(StructuringRule::new(
+                p.report_threshold,
+                p.tolerance,
+                chrono::Duration::seconds(p.window_seconds),
+                p.min_occurrences,
+            ))
+        }
+        "BUSINESS_HOURS" => {
+            let p: BusinessHoursParams = parameters(rule_id, params)?;
+            let timezone: chrono_tz::Tz = p
+                .timezone
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid timezone for rule {}: {}", rule_id, e))?;
+            Box::new(BusinessHoursRule::new(timezone, p.open, p.close, p.days))
+        }
+        "SUFFICIENT_FUNDS" => {
+            let p: SufficientFundsParams = parameters(rule_id, params)?;
+            match p.protected_types {
+                Some(types) => Box::new(SufficientFundsRule::with_protected_types(types)),
+                None => Box::new(SufficientFundsRule::new()),
+            }
+        }
+        other => bail!("Unknown rule type: {}", other),
+    };
+    for exemption in &config.exemptions {
+        rule = Box::new(ExemptionRule::new(rule, exemption.clone()));
+    }
+    Ok(rule)
+}
+
+impl ComplianceValidator {
+    /// Builds a validator from configuration. Disabled rules are skipped, and
+    /// rule sets may still name them; naming a rule absent from the
+    /// configuration is an error.
+    pub fn from_config(config: ValidatorConfig) -> Result<Self> {
+        let mut validator = ComplianceValidator::new()
+            .with_dedup(config.dedup)
+            .with_blocking_severity(config.blocking_severity.clone().unwrap_or(RuleSeverity::Critical));
+
+        for (rule_id, rule_config) in &config.rules {
+            if rule_config.enabled {
+                validator.add_rule(build_rule(rule_id, rule_config)?);
+            }
+        }
+
+        for (name, rule_set) in &config.rule_sets {
+            let rule_ids = rule_set.rule_ids();
+            if let Some(missing) = rule_ids.iter().find(|id| !config.rules.contains_key(*id)) {
+                bail!("Rule set {} references unknown rule {}", name, missing);
+            }
+            validator.create_rule_set_with_blocking(
+                name,
+                rule_ids.iter().map(String::as_str).collect(),
+                rule_set.blocking_severity(),
+            );
+        }
+
+        Ok(validator)
+    }
+}
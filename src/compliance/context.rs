@@ -0,0 +1,310 @@
+use crate::compliance::validator::{
+    escalation_entity, ContextProvider, EscalationStore, ValidationContext, ACCOUNT_TYPES_KEY, BALANCES_KEY,
+    RECENT_TRANSACTIONS_KEY,
+};
+use crate::core::balance::{self, fold_balances, BALANCE_EVENT_TYPES};
+use crate::core::event::LedgerEvent;
+use crate::core::{LedgerObserver, LedgerRecord};
+use crate::storage::append_only::AppendOnlyStorage;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// `ContextProvider` that reads history from a ledger's own chain.
+///
+/// For a financial transaction it supplies, under `RECENT_TRANSACTIONS_KEY`,
+/// the transactions appended within `lookback` of it that involve either of
+/// its accounts, and under `ACCOUNT_TYPES_KEY` the types of both accounts as
+/// recorded by their `AccountCreation` events, and under `BALANCES_KEY` both
+/// accounts' current balances. Other events get no data.
+///
+/// Account types and balances are folded from the whole balance history, so
+/// with a `ContextCache` they are only fetched for accounts it doesn't hold.
+pub struct StorageContextProvider {
+    storage: Arc<dyn AppendOnlyStorage>,
+    chain_id: String,
+    lookback: chrono::Duration,
+    cache: Option<Arc<ContextCache>>,
+}
+
+impl StorageContextProvider {
+    pub fn new(
+        storage: Arc<dyn AppendOnlyStorage>,
+        chain_id: impl Into<String>,
+        lookback: chrono::Duration,
+    ) -> Self {
+        Self {
+            storage,
+            chain_id: chain_id.into(),
+            lookback,
+            cache: None,
+        }
+    }
+
+    /// Reuses account types and balances held by `cache`. Subscribe the
+    /// same cache to the ledger so appends invalidate what they change.
+    pub fn with_cache(mut self, cache: Arc<ContextCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+}
+
+/// What `StorageContextProvider` supplies about one account
+#[derive(Debug, Clone)]
+struct AccountContext {
+    account_type: Option<serde_json::Value>,
+    balances: serde_json::Value,
+}
+
+impl AccountContext {
+    fn fold(account_id: &str, history: &[LedgerRecord]) -> Result<Self> {
+        let events = || history.iter().map(|r| &r.event);
+        let account_type = balance::account_type(account_id, events())
+            .map(serde_json::to_value)
+            .transpose()?;
+        // Same fold as DigitalLedger::balance_at, over the whole history
+        let balances = serde_json::to_value(fold_balances(account_id, events())?)?;
+        Ok(Self { account_type, balances })
+    }
+}
+
+/// Account context cached by `StorageContextProvider` across events, so a
+/// batch touching few accounts folds each one's history once.
+///
+/// Entries expire `ttl` after they were fetched. Past `max_entries`, expired
+/// entries are dropped first and then the oldest. As a `LedgerObserver` the
+/// cache drops an account's entry when a record moving its balance is
+/// appended; observers are notified asynchronously and may miss
+/// notifications under load, so `ttl` bounds how stale an entry can get.
+pub struct ContextCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (AccountContext, Instant)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Lookups served by a `ContextCache` since it was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl ContextCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> ContextCacheStats {
+        ContextCacheStats {
+            entries: self.entries.lock().unwrap().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drops the cached context of `account_id`
+    pub fn invalidate(&self, account_id: &str) {
+        self.entries.lock().unwrap().remove(account_id);
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn get(&self, account_id: &str) -> Option<AccountContext> {
+        let mut entries = self.entries.lock().unwrap();
+        let fresh = match entries.get(account_id) {
+            Some((context, fetched_at)) if fetched_at.elapsed() < self.ttl => Some(context.clone()),
+            Some(_) => {
+                entries.remove(account_id);
+                None
+            }
+            None => None,
+        };
+        let counter = if fresh.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh
+    }
+
+    fn insert(&self, account_id: &str, context: AccountContext) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(account_id.to_string(), (context, Instant::now()));
+        if entries.len() > self.max_entries {
+            entries.retain(|_, (_, fetched_at)| fetched_at.elapsed() < self.ttl);
+        }
+        while entries.len() > self.max_entries {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (_, fetched_at))| *fetched_at)
+                .map(|(account, _)| account.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+    }
+}
+
+impl LedgerObserver for ContextCache {
+    fn on_appended(&self, record: &LedgerRecord) -> anyhow::Result<()> {
+        match &record.event {
+            LedgerEvent::AccountCreation(acct) => self.invalidate(&acct.account_id),
+            LedgerEvent::FinancialTransaction(tx) => {
+                self.invalidate(&tx.from_account);
+                self.invalidate(&tx.to_account);
+            }
+            LedgerEvent::BalanceAdjustment(adj) => self.invalidate(&adj.account_id),
+            LedgerEvent::JournalEntry(entry) => {
+                for line in &entry.lines {
+                    self.invalidate(&line.account);
+                }
+            }
+            // The accounts a reversal moves are those of its original
+            // transfer, which the record doesn't name
+            LedgerEvent::TransactionReversal(_) => self.clear(),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ContextProvider for StorageContextProvider {
+    async fn populate(&self, event: &LedgerEvent, context: &mut ValidationContext) -> Result<()> {
+        let LedgerEvent::FinancialTransaction(tx) = event else {
+            return Ok(());
+        };
+        let involves = |account: &str| account == tx.from_account || account == tx.to_account;
+
+        let recent: Vec<_> = self
+            .storage
+            .query_records(
+                &self.chain_id,
+                None,
+                Some(tx.timestamp - self.lookback),
+                None,
+                Some(&["financial_transaction".to_string()]),
+                None,
+                None,
+            )
+            .await?
+            .into_iter()
+            .filter_map(|record| match record.event {
+                LedgerEvent::FinancialTransaction(prior)
+                    if involves(&prior.from_account) || involves(&prior.to_account) =>
+                {
+                    Some(prior)
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut accounts = HashMap::new();
+        let mut missing = Vec::new();
+        for account in [&tx.from_account, &tx.to_account] {
+            match self.cache.as_ref().and_then(|cache| cache.get(account)) {
+                Some(cached) => {
+                    accounts.insert(account.clone(), cached);
+                }
+                None => missing.push(account),
+            }
+        }
+        if !missing.is_empty() {
+            let balance_types: Vec<String> = BALANCE_EVENT_TYPES.iter().map(|t| t.to_string()).collect();
+            let history = self
+                .storage
+                .query_records(&self.chain_id, None, None, None, Some(&balance_types), None, None)
+                .await?;
+            for account in missing {
+                let fetched = AccountContext::fold(account, &history)?;
+                if let Some(cache) = &self.cache {
+                    cache.insert(account, fetched.clone());
+                }
+                accounts.insert(account.clone(), fetched);
+            }
+        }
+
+        let mut account_types = serde_json::Map::new();
+        let mut balances = serde_json::Map::new();
+        for (account, fetched) in accounts {
+            if let Some(account_type) = fetched.account_type {
+                account_types.insert(account.clone(), account_type);
+            }
+            balances.insert(account, fetched.balances);
+        }
+
+        context
+            .additional_data
+            .insert(RECENT_TRANSACTIONS_KEY.to_string(), serde_json::to_value(recent)?);
+        context
+            .additional_data
+            .insert(ACCOUNT_TYPES_KEY.to_string(), serde_json::Value::Object(account_types));
+        context
+            .additional_data
+            .insert(BALANCES_KEY.to_string(), serde_json::Value::Object(balances));
+        Ok(())
+    }
+}
+
+/// `EscalationStore` that counts occurrences from a ledger's own chain, so
+/// counts survive restarts and are shared by every process on the chain.
+///
+/// An occurrence is a stored record that kept a violation of the rule and
+/// whose entity matches; recording one writes nothing, since the record the
+/// violation ends up on is the occurrence. Only violations kept with
+/// appended records count: an event rejected outright, for instance once
+/// its violations were escalated to a blocking severity, is not counted
+/// again.
+pub struct StorageEscalationStore {
+    storage: Arc<dyn AppendOnlyStorage>,
+    chain_id: String,
+}
+
+impl StorageEscalationStore {
+    pub fn new(storage: Arc<dyn AppendOnlyStorage>, chain_id: impl Into<String>) -> Self {
+        Self {
+            storage,
+            chain_id: chain_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl EscalationStore for StorageEscalationStore {
+    async fn record_occurrence(
+        &self,
+        rule_id: &str,
+        entity: &str,
+        at: chrono::DateTime<chrono::Utc>,
+        window: chrono::Duration,
+    ) -> Result<usize> {
+        let prior = self
+            .storage
+            .query_records(&self.chain_id, None, Some(at - window), Some(at), None, None, None)
+            .await?
+            .into_iter()
+            .filter(|record| {
+                record.timestamp > at - window
+                    && record.violations.iter().any(|v| v.rule_id == rule_id)
+                    && escalation_entity(&record.event) == entity
+            })
+            .count();
+        // The current occurrence is not stored yet
+        Ok(prior + 1)
+    }
+}
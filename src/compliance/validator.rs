@@ -1,159 +1,1754 @@
-use crate::core::event::LedgerEvent;
+use crate::core::event::{AccountType, FinancialTransaction, HasMetadata, LedgerEvent, Money, MoneyError};
+use crate::utils::metrics;
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use tracing::Instrument;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 #[async_trait]
 pub trait Rule: Send + Sync {
     async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>>;
     fn get_rule_id(&self) -> &str;
     fn get_severity(&self) -> RuleSeverity;
+    
+    /// Evaluation order within `ComplianceValidator::validate`: higher
+    /// priorities run first, ties run in rule id order. `depends_on` takes
+    /// precedence.
+    fn priority(&self) -> i32 {
+        0
+    }
+    
+    /// Whether the result depends only on the event, so the validator's rule
+    /// cache may reuse it. Rules that read the `ValidationContext`, the
+    /// clock or any other state must leave this false.
+    fn is_pure(&self) -> bool {
+        false
+    }
+    
+    /// Points this rule adds to `ComplianceValidator::score` for the
+    /// violations it raised on one event. Defaults to a fixed amount per
+    /// violation by severity; rules that can tell how far over a threshold
+    /// an event is override it.
+    fn risk_contribution(&self, violations: &[Violation]) -> u32 {
+        violations.iter().map(|v| v.severity.default_risk_points()).sum()
+    }
+    
+    /// Ids of rules that must run before this one because it reads context
+    /// data they contribute. Dependencies on rules not being evaluated, for
+    /// instance ones outside the rule set, are ignored.
+    fn depends_on(&self) -> Vec<String> {
+        Vec::new()
+    }
+    
+    /// Adds data to the context for this rule and the rules that depend on
+    /// it, such as a resolved counterparty for a sanctions rule. Runs just
+    /// before `evaluate`; an error is reported like an evaluation error and
+    /// skips `evaluate`.
+    async fn contribute(&self, _event: &LedgerEvent, _context: &mut ValidationContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Fills a `ValidationContext` before rules run, so history-dependent rules
+/// see the data they need on the normal `validate` path.
+#[async_trait]
+pub trait ContextProvider: Send + Sync {
+    async fn populate(&self, event: &LedgerEvent, context: &mut ValidationContext) -> Result<()>;
+}
+
+pub struct ComplianceValidator {
+    rules: HashMap<String, Box<dyn Rule>>,
+    rule_sets: HashMap<String, RuleSet>,
+    /// Frameworks each rule belongs to, by rule id
+    rule_tags: HashMap<String, Vec<String>>,
+    blocking_severity: RuleSeverity,
+    dedup: bool,
+    context_provider: Option<Arc<dyn ContextProvider>>,
+    batch_concurrency: usize,
+    redaction: Option<RedactionPolicy>,
+    rule_cache: Option<Mutex<RuleCache>>,
+}
+
+/// Events `validate_many` evaluates at once unless configured otherwise
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+impl ComplianceValidator {
+    pub fn new() -> Self {
+        Self {
+            rules: HashMap::new(),
+            rule_sets: HashMap::new(),
+            rule_tags: HashMap::new(),
+            blocking_severity: RuleSeverity::Critical,
+            dedup: false,
+            context_provider: None,
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            redaction: None,
+            rule_cache: None,
+        }
+    }
+    
+    /// Caches the results of pure rules by rule id and event hash, keeping
+    /// the `capacity` most recently used. Off by default.
+    pub fn with_rule_cache(mut self, capacity: usize) -> Self {
+        self.rule_cache = Some(Mutex::new(RuleCache::new(capacity)));
+        self
+    }
+    
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.rule_cache {
+            cache.lock().unwrap().clear();
+        }
+    }
+    
+    /// Policy applied by `redact_for_export`. Violations returned by
+    /// `validate` and stored on records are never redacted.
+    pub fn with_redaction(mut self, policy: RedactionPolicy) -> Self {
+        self.redaction = Some(policy);
+        self
+    }
+    
+    /// Violations as they may be shown to external consumers
+    pub fn redact_for_export(&self, violations: &[Violation]) -> Vec<Violation> {
+        match &self.redaction {
+            Some(policy) => violations.iter().map(|v| v.redact(policy)).collect(),
+            None => violations.to_vec(),
+        }
+    }
+    
+    /// Maximum number of events `validate_many` evaluates concurrently
+    pub fn with_batch_concurrency(mut self, limit: usize) -> Self {
+        self.batch_concurrency = limit.max(1);
+        self
+    }
+    
+    pub fn set_context_provider(&mut self, provider: Arc<dyn ContextProvider>) {
+        self.context_provider = Some(provider);
+    }
+    
+    /// Collapse violations sharing a rule id and structurally equal evidence
+    /// into one, keeping the highest severity. Off by default.
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+    
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
+        self.add_rule_with_tags(rule, Vec::new());
+    }
+    
+    /// Adds a rule tagged with the frameworks it belongs to, such as "aml"
+    /// or "sanctions". A rule may carry any number of tags, and replacing a
+    /// rule replaces its tags.
+    pub fn add_rule_with_tags(&mut self, rule: Box<dyn Rule>, tags: Vec<&str>) {
+        let rule_id = rule.get_rule_id().to_string();
+        if tags.is_empty() {
+            self.rule_tags.remove(&rule_id);
+        } else {
+            self.rule_tags.insert(rule_id.clone(), tags.iter().map(|t| t.to_string()).collect());
+        }
+        self.rules.insert(rule_id, rule);
+    }
+    
+    pub fn rule_tags(&self, rule_id: &str) -> &[String] {
+        self.rule_tags.get(rule_id).map_or(&[], Vec::as_slice)
+    }
+    
+    /// Lowest severity that blocks an event in `check`. Defaults to `Critical`.
+    pub fn with_blocking_severity(mut self, severity: RuleSeverity) -> Self {
+        self.blocking_severity = severity;
+        self
+    }
+    
+    /// Creates a rule set with the default `RuleSetConfig`, which blocks on
+    /// `Critical` violations and uses the validator's context provider
+    pub fn create_rule_set(&mut self, name: &str, rule_ids: Vec<&str>) {
+        self.create_rule_set_with_config(name, rule_ids, RuleSetConfig::default());
+    }
+    
+    /// Creates a rule set whose verdict blocks on violations at or above
+    /// `blocking_severity`
+    pub fn create_rule_set_with_blocking(
+        &mut self,
+        name: &str,
+        rule_ids: Vec<&str>,
+        blocking_severity: RuleSeverity,
+    ) {
+        let config = RuleSetConfig::default().with_blocking_severity(blocking_severity);
+        self.create_rule_set_with_config(name, rule_ids, config);
+    }
+    
+    /// Creates a rule set of `rule_ids` plus the rules tagged with any of
+    /// `config.tags`, judged and given context as `config` says. Replaces
+    /// any set of the same name.
+    pub fn create_rule_set_with_config(&mut self, name: &str, rule_ids: Vec<&str>, config: RuleSetConfig) {
+        self.rule_sets.insert(
+            name.to_string(),
+            RuleSet {
+                rule_ids: rule_ids.iter().map(|s| s.to_string()).collect(),
+                config,
+            },
+        );
+    }
+    
+    /// Runs all rules like `validate` and judges the violations against the
+    /// validator's blocking severity
+    pub async fn check(&self, event: &LedgerEvent) -> Result<ComplianceOutcome> {
+        let violations = self.validate(event).await?;
+        Ok(ComplianceOutcome::from_violations(violations, &self.blocking_severity))
+    }
+    
+    pub async fn validate(&self, event: &LedgerEvent) -> Result<Vec<Violation>> {
+        let violations = self
+            .evaluate_rules(event)
+            .await?
+            .into_iter()
+            .flat_map(|(_, rule_violations)| rule_violations)
+            .collect();
+        
+        let violations = self.finish(violations);
+        violations.iter().for_each(metrics::record_violation);
+        Ok(violations)
+    }
+    
+    /// Runs only the rules tagged with any of `tags`, in the same order
+    /// `validate` would run them. Untagged rules never run here.
+    pub async fn validate_with_tags(&self, event: &LedgerEvent, tags: &[&str]) -> Result<Vec<Violation>> {
+        let rules = self
+            .ordered_rules()
+            .into_iter()
+            .filter(|rule| self.rule_tags(rule.get_rule_id()).iter().any(|t| tags.contains(&t.as_str())))
+            .collect();
+        let violations = self
+            .evaluate_rule_list(event, rules, self.context_provider.as_deref())
+            .await?
+            .into_iter()
+            .flat_map(|(_, rule_violations)| rule_violations)
+            .collect();
+        
+        let violations = self.finish(violations);
+        violations.iter().for_each(metrics::record_violation);
+        Ok(violations)
+    }
+    
+    /// Groups violations by the tags of the rules that raised them, for
+    /// per-framework reports. A violation appears under every tag of its
+    /// rule; violations from untagged rules are left out.
+    pub fn violations_by_tag(&self, violations: &[Violation]) -> BTreeMap<String, Vec<Violation>> {
+        let mut grouped: BTreeMap<String, Vec<Violation>> = BTreeMap::new();
+        for violation in violations {
+            for tag in self.rule_tags(&violation.rule_id) {
+                grouped.entry(tag.clone()).or_default().push(violation.clone());
+            }
+        }
+        grouped
+    }
+    
+    /// Runs all rules like `validate` and sums the risk contributions of the
+    /// rules that fired into a 0-100 score. Violations are reported exactly
+    /// as `validate` would; the score is for prioritizing review, not for
+    /// deciding whether an event is blocked.
+    pub async fn score(&self, event: &LedgerEvent) -> Result<RiskScore> {
+        let mut breakdown = Vec::new();
+        for (rule, violations) in self.evaluate_rules(event).await? {
+            let violations = self.finish(violations);
+            if violations.is_empty() {
+                continue;
+            }
+            breakdown.push(RiskContribution {
+                rule_id: rule.get_rule_id().to_string(),
+                points: rule.risk_contribution(&violations),
+                violations,
+            });
+        }
+        
+        let total: u32 = breakdown.iter().map(|c| c.points).sum();
+        Ok(RiskScore {
+            score: total.min(MAX_RISK_SCORE),
+            breakdown,
+        })
+    }
+    
+    /// Runs all rules like `validate`, also collecting what each rule checked
+    /// and decided, passes included, for explaining a decision to auditors.
+    /// Rules add their own lines through `ValidationContext::trace`, and
+    /// each rule's outcome is added after them. Pure rules are evaluated
+    /// afresh rather than answered from the cache, and no metrics are
+    /// recorded.
+    pub async fn validate_explained(&self, event: &LedgerEvent) -> Result<Explanation> {
+        let (results, trace) = self
+            .run_rules(event, self.ordered_rules(), self.context_provider.as_deref(), true)
+            .await?;
+        let violations = results
+            .into_iter()
+            .flat_map(|(_, rule_violations)| rule_violations)
+            .collect();
+        Ok(Explanation {
+            violations: self.finish(violations),
+            trace,
+        })
+    }
+    
+    /// Every rule in evaluation order with the violations it raised. A rule
+    /// that fails to evaluate is reported as a single critical violation.
+    async fn evaluate_rules(&self, event: &LedgerEvent) -> Result<Vec<(&dyn Rule, Vec<Violation>)>> {
+        // Apply all rules by default
+        self.evaluate_rule_list(event, self.ordered_rules(), self.context_provider.as_deref())
+            .await
+    }
+    
+    /// Runs `rules` in dependency order, layer by layer (see
+    /// `dependency_layers`), against a context populated by `provider`.
+    /// Within a layer contributions are made in order, then the rules are
+    /// evaluated concurrently against the shared context. A dependency
+    /// cycle fails the whole validation.
+    async fn evaluate_rule_list<'a>(
+        &'a self,
+        event: &LedgerEvent,
+        rules: Vec<&'a dyn Rule>,
+        provider: Option<&dyn ContextProvider>,
+    ) -> Result<Vec<(&'a dyn Rule, Vec<Violation>)>> {
+        let (results, _) = self.run_rules(event, rules, provider, false).await?;
+        Ok(results)
+    }
+    
+    /// `evaluate_rule_list`, with the trace of each rule, grouped in
+    /// evaluation order, when `explain` is set
+    async fn run_rules<'a>(
+        &'a self,
+        event: &LedgerEvent,
+        rules: Vec<&'a dyn Rule>,
+        provider: Option<&dyn ContextProvider>,
+        explain: bool,
+    ) -> Result<(Vec<(&'a dyn Rule, Vec<Violation>)>, Vec<TraceEntry>)> {
+        let layers = dependency_layers(rules)?;
+        let mut context = build_context(event, provider).await?;
+        if explain {
+            context.trace = Some(RuleTrace::default());
+        }
+        // Cached results would skip the rules' own trace lines
+        let event_key = if explain { None } else { self.cache_key(event)? };
+        let mut results = Vec::new();
+        
+        for layer in layers {
+            let mut contributions = Vec::with_capacity(layer.len());
+            for rule in &layer {
+                contributions.push(rule.contribute(event, &mut context).await);
+            }
+            
+            let context = &context;
+            let event_key = event_key.as_deref();
+            let evaluated = futures::future::join_all(layer.into_iter().zip(contributions).map(
+                |(rule, contribution)| async move {
+                    let result = match contribution {
+                        Ok(()) => self.evaluate_cached(rule, event, event_key, context).await,
+                        Err(e) => Err(e),
+                    };
+                    let violations = result.unwrap_or_else(|e| {
+                        context.trace(rule.get_rule_id(), || format!("evaluation error: {}", e));
+                        vec![Violation {
+                            rule_id: rule.get_rule_id().to_string(),
+                            severity: RuleSeverity::Critical,
+                            message: format!("Rule evaluation error: {}", e),
+                            evidence: serde_json::json!({"error": e.to_string()}),
+                        }]
+                    });
+                    (rule, violations)
+                },
+            ))
+            .await;
+            for (rule, violations) in &evaluated {
+                context.trace(rule.get_rule_id(), || match violations.len() {
+                    0 => "no violations".to_string(),
+                    n => format!("{} violation(s)", n),
+                });
+            }
+            results.extend(evaluated);
+        }
+        
+        // Rules in a layer trace concurrently; regroup by rule
+        let Some(trace) = context.trace.take() else {
+            return Ok((results, Vec::new()));
+        };
+        let mut trace = trace.into_entries();
+        let position: HashMap<&str, usize> = results
+            .iter()
+            .enumerate()
+            .map(|(i, (rule, _))| (rule.get_rule_id(), i))
+            .collect();
+        trace.sort_by_key(|entry| {
+            position.get(entry.rule_id.as_str()).copied().unwrap_or(usize::MAX)
+        });
+        
+        Ok((results, trace))
+    }
+    
+    /// Validates every event, up to `batch_concurrency` at a time, each with
+    /// its own provider-populated context. Returns the violations of each
+    /// event that has any, keyed by its index in `events` and in index
+    /// order. An event whose validation fails outright is reported as a
+    /// single critical violation rather than aborting the batch.
+    pub async fn validate_many(&self, events: &[LedgerEvent]) -> Vec<(usize, Vec<Violation>)> {
+        let mut results: Vec<(usize, Vec<Violation>)> = stream::iter(events.iter().enumerate())
+            .map(|(index, event)| async move {
+                let violations = self.validate(event).await.unwrap_or_else(|e| {
+                    vec![Violation {
+                        rule_id: "COMPLIANCE_VALIDATOR".to_string(),
+                        severity: RuleSeverity::Critical,
+                        message: format!("Compliance check failed: {}", e),
+                        evidence: serde_json::json!({"error": e.to_string()}),
+                    }]
+                });
+                (index, violations)
+            })
+            .buffer_unordered(self.batch_concurrency)
+            .filter(|(_, violations)| futures::future::ready(!violations.is_empty()))
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
+        results
+    }
+    
+    /// Runs a rule set's rules against a context from the set's own
+    /// provider, falling back to the validator's, and judges the violations
+    /// against the set's blocking severity
+    pub async fn validate_with_rule_set(
+        &self,
+        event: &LedgerEvent,
+        rule_set_name: &str,
+    ) -> Result<ComplianceOutcome> {
+        if let Some(rule_set) = self.rule_sets.get(rule_set_name) {
+            let provider = rule_set
+                .config
+                .context_provider
+                .as_deref()
+                .or(self.context_provider.as_deref());
+            let violations = self
+                .evaluate_rule_list(event, self.rule_set_rules(rule_set), provider)
+                .await?
+                .into_iter()
+                .flat_map(|(_, rule_violations)| rule_violations)
+                .collect();
+            
+            let violations = self.finish(violations);
+            violations.iter().for_each(metrics::record_violation);
+            Ok(ComplianceOutcome::from_violations(violations, &rule_set.config.blocking_severity))
+        } else {
+            Err(anyhow::anyhow!("Rule set not found: {}", rule_set_name))
+        }
+    }
+    
+    /// The rules named by `rule_set` in the order declared, then those
+    /// tagged with any of its tags in `ordered_rules` order
+    fn rule_set_rules(&self, rule_set: &RuleSet) -> Vec<&dyn Rule> {
+        let mut rules: Vec<&dyn Rule> = rule_set
+            .rule_ids
+            .iter()
+            .filter_map(|rule_id| self.rules.get(rule_id))
+            .map(|rule| rule.as_ref())
+            .collect();
+        if rule_set.config.tags.is_empty() {
+            return rules;
+        }
+        let tagged: Vec<&dyn Rule> = self
+            .ordered_rules()
+            .into_iter()
+            .filter(|rule| !rule_set.rule_ids.iter().any(|id| id == rule.get_rule_id()))
+            .filter(|rule| {
+                self.rule_tags(rule.get_rule_id())
+                    .iter()
+                    .any(|tag| rule_set.config.tags.contains(tag))
+            })
+            .collect();
+        rules.extend(tagged);
+        rules
+    }
+    
+    /// All rules by descending priority, then rule id, so evaluation and
+    /// violation order are stable across runs. Rule sets keep the order
+    /// they were declared in.
+    fn ordered_rules(&self) -> Vec<&dyn Rule> {
+        let mut rules: Vec<&dyn Rule> = self.rules.values().map(|r| r.as_ref()).collect();
+        rules.sort_by(|a, b| {
+            b.priority()
+                .cmp(&a.priority())
+                .then_with(|| a.get_rule_id().cmp(b.get_rule_id()))
+        });
+        rules
+    }
+    
+    /// Hash identifying the event in the rule cache, when caching is on
+    fn cache_key(&self, event: &LedgerEvent) -> Result<Option<String>> {
+        if self.rule_cache.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(hex::encode(Sha256::digest(serde_json::to_vec(event)?))))
+    }
+    
+    /// Evaluates a rule, answering pure rules from the cache when possible.
+    /// Evaluation errors are never cached.
+    async fn evaluate_cached(
+        &self,
+        rule: &dyn Rule,
+        event: &LedgerEvent,
+        event_key: Option<&str>,
+        context: &ValidationContext,
+    ) -> Result<Vec<Violation>> {
+        let (Some(cache), Some(event_key), true) = (&self.rule_cache, event_key, rule.is_pure()) else {
+            return evaluate_traced(rule, event, context).await;
+        };
+        let key = (rule.get_rule_id().to_string(), event_key.to_string());
+        
+        if let Some(violations) = cache.lock().unwrap().get(&key) {
+            return Ok(violations);
+        }
+        let violations = evaluate_traced(rule, event, context).await?;
+        cache.lock().unwrap().insert(key, violations.clone());
+        Ok(violations)
+    }
+    
+    fn finish(&self, violations: Vec<Violation>) -> Vec<Violation> {
+        if self.dedup {
+            dedup_violations(violations)
+        } else {
+            violations
+        }
+    }
+}
+
+async fn build_context(event: &LedgerEvent, provider: Option<&dyn ContextProvider>) -> Result<ValidationContext> {
+    let mut context = ValidationContext::new();
+    if let Some(provider) = provider {
+        provider.populate(event, &mut context).await?;
+    }
+    Ok(context)
+}
+
+/// Splits `rules` into layers that run one after another, every rule in a
+/// layer after all the rules it depends on. Rules keep their relative order
+/// within a layer, so with no dependencies there is a single layer in the
+/// original order. Fails if some rules depend on each other in a cycle.
+fn dependency_layers(rules: Vec<&dyn Rule>) -> Result<Vec<Vec<&dyn Rule>>> {
+    let ids: HashSet<String> = rules.iter().map(|rule| rule.get_rule_id().to_string()).collect();
+    let mut done: HashSet<String> = HashSet::new();
+    let mut pending = rules;
+    let mut layers = Vec::new();
+    
+    while !pending.is_empty() {
+        let (ready, blocked): (Vec<&dyn Rule>, Vec<&dyn Rule>) = pending.into_iter().partition(|rule| {
+            rule.depends_on()
+                .iter()
+                .all(|dep| done.contains(dep) || !ids.contains(dep))
+        });
+        if ready.is_empty() {
+            let stuck: Vec<&str> = blocked.iter().map(|rule| rule.get_rule_id()).collect();
+            return Err(anyhow::anyhow!("Rule dependency cycle among: {}", stuck.join(", ")));
+        }
+        done.extend(ready.iter().map(|rule| rule.get_rule_id().to_string()));
+        layers.push(ready);
+        pending = blocked;
+    }
+    
+    Ok(layers)
+}
+
+/// Keeps the first of each group of violations with the same rule id and
+/// equal evidence, raised to the group's highest severity. `Value` equality
+/// is structural, so object key order does not matter.
+fn dedup_violations(violations: Vec<Violation>) -> Vec<Violation> {
+    let mut kept: Vec<Violation> = Vec::with_capacity(violations.len());
+    for violation in violations {
+        match kept
+            .iter_mut()
+            .find(|k| k.rule_id == violation.rule_id && k.evidence == violation.evidence)
+        {
+            Some(existing) => {
+                if violation.severity > existing.severity {
+                    existing.severity = violation.severity;
+                }
+            }
+            None => kept.push(violation),
+        }
+    }
+    kept
+}
+
+struct RuleSet {
+    rule_ids: Vec<String>,
+    config: RuleSetConfig,
+}
+
+/// How a rule set picks its rules, fills its context and reaches its
+/// verdict, so sets with different purposes (onboarding checks needing KYC
+/// data, monitoring needing transaction history) don't share one setup
+#[derive(Clone)]
+pub struct RuleSetConfig {
+    /// Lowest severity that blocks an event. Defaults to `Critical`.
+    pub blocking_severity: RuleSeverity,
+    /// Populates the context of this set's rules; `None` uses the
+    /// validator's provider
+    pub context_provider: Option<Arc<dyn ContextProvider>>,
+    /// Rules tagged with any of these run in the set besides its listed ones
+    pub tags: Vec<String>,
+}
+
+impl Default for RuleSetConfig {
+    fn default() -> Self {
+        Self {
+            blocking_severity: RuleSeverity::Critical,
+            context_provider: None,
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl RuleSetConfig {
+    pub fn with_blocking_severity(mut self, severity: RuleSeverity) -> Self {
+        self.blocking_severity = severity;
+        self
+    }
+    
+    pub fn with_context_provider(mut self, provider: Arc<dyn ContextProvider>) -> Self {
+        self.context_provider = Some(provider);
+        self
+    }
+    
+    pub fn with_tags(mut self, tags: Vec<&str>) -> Self {
+        self.tags = tags.iter().map(|t| t.to_string()).collect();
+        self
+    }
+}
+
+/// Least-recently-used map of `(rule_id, event_hash)` to that rule's
+/// violations. Each entry carries the tick of its last use; `by_use` orders
+/// keys by that tick so the oldest is evicted first.
+struct RuleCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<(String, String), (Vec<Violation>, u64)>,
+    by_use: BTreeMap<u64, (String, String)>,
+}
+
+impl RuleCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            by_use: BTreeMap::new(),
+        }
+    }
+    
+    fn get(&mut self, key: &(String, String)) -> Option<Vec<Violation>> {
+        self.tick += 1;
+        let (violations, last_used) = self.entries.get_mut(key)?;
+        self.by_use.remove(last_used);
+        *last_used = self.tick;
+        self.by_use.insert(self.tick, key.clone());
+        Some(violations.clone())
+    }
+    
+    fn insert(&mut self, key: (String, String), violations: Vec<Violation>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.insert(key.clone(), (violations, self.tick)) {
+            self.by_use.remove(&last_used);
+        }
+        self.by_use.insert(self.tick, key);
+        
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.by_use.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+    
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.by_use.clear();
+    }
+}
+
+/// Verdict of a compliance check
+#[derive(Debug, Clone)]
+pub enum ComplianceOutcome {
+    /// No rule fired
+    Passed,
+    /// Rules fired, but none at or above the blocking severity
+    PassedWithViolations(Vec<Violation>),
+    /// At least one violation is at or above the blocking severity; all
+    /// violations are included
+    Blocked(Vec<Violation>),
+}
+
+impl ComplianceOutcome {
+    pub fn from_violations(violations: Vec<Violation>, blocking_severity: &RuleSeverity) -> Self {
+        if violations.iter().any(|v| v.severity >= *blocking_severity) {
+            ComplianceOutcome::Blocked(violations)
+        } else if violations.is_empty() {
+            ComplianceOutcome::Passed
+        } else {
+            ComplianceOutcome::PassedWithViolations(violations)
+        }
+    }
+    
+    pub fn is_blocked(&self) -> bool {
+        matches!(self, ComplianceOutcome::Blocked(_))
+    }
+    
+    pub fn violations(&self) -> &[Violation] {
+        match self {
+            ComplianceOutcome::Passed => &[],
+            ComplianceOutcome::PassedWithViolations(v) | ComplianceOutcome::Blocked(v) => v,
+        }
+    }
+    
+    pub fn into_violations(self) -> Vec<Violation> {
+        match self {
+            ComplianceOutcome::Passed => Vec::new(),
+            ComplianceOutcome::PassedWithViolations(v) | ComplianceOutcome::Blocked(v) => v,
+        }
+    }
+}
+
+/// Highest value of `RiskScore::score`
+pub const MAX_RISK_SCORE: u32 = 100;
+
+/// Risk of an event from the rules that fired on it, for ordering a manual
+/// review queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskScore {
+    /// Sum of the contributions, capped at `MAX_RISK_SCORE`
+    pub score: u32,
+    /// One entry per rule that fired, in evaluation order
+    pub breakdown: Vec<RiskContribution>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskContribution {
+    pub rule_id: String,
+    pub points: u32,
+    pub violations: Vec<Violation>,
+}
+
+/// `ValidationContext` key holding recent `FinancialTransaction`s as a JSON array
+pub const RECENT_TRANSACTIONS_KEY: &str = "recent_transactions";
+
+/// `ValidationContext` key holding a JSON object of account id to `AccountType`
+pub const ACCOUNT_TYPES_KEY: &str = "account_types";
+
+/// `ValidationContext` key holding a JSON object of account id to its
+/// per-currency balances, each a `Money` keyed by currency code
+pub const BALANCES_KEY: &str = "balances";
+
+/// Runs a rule inside a span carrying its rule id
+async fn evaluate_traced(
+    rule: &dyn Rule,
+    event: &LedgerEvent,
+    context: &ValidationContext,
+) -> Result<Vec<Violation>> {
+    let span = tracing::info_span!("evaluate_rule", rule_id = rule.get_rule_id());
+    rule.evaluate(event, context).instrument(span).await
+}
+
+/// Outcome of `ComplianceValidator::validate_explained`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Explanation {
+    pub violations: Vec<Violation>,
+    /// What each rule checked and decided, in evaluation order
+    pub trace: Vec<TraceEntry>,
+}
+
+/// One line of a rule's explanation, e.g. "amount 500 USD <= limit 10000
+/// USD: PASS"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub rule_id: String,
+    pub message: String,
+}
+
+/// Accumulates trace lines while the validator is explaining. Rules in a
+/// dependency layer run concurrently, so it takes lines through `&self`.
+#[derive(Debug, Default)]
+pub struct RuleTrace {
+    entries: Mutex<Vec<TraceEntry>>,
+}
+
+impl RuleTrace {
+    pub fn record(&self, rule_id: &str, message: String) {
+        self.entries.lock().unwrap().push(TraceEntry {
+            rule_id: rule_id.to_string(),
+            message,
+        });
+    }
+    
+    pub fn into_entries(self) -> Vec<TraceEntry> {
+        self.entries.into_inner().unwrap()
+    }
+}
+
+pub struct ValidationContext {
+    pub additional_data: HashMap<String, Value>,
+    /// Set only by `ComplianceValidator::validate_explained`
+    pub trace: Option<RuleTrace>,
+}
+
+impl ValidationContext {
+    pub fn new() -> Self {
+        Self {
+            additional_data: HashMap::new(),
+            trace: None,
+        }
+    }
+    
+    /// Adds a line to the explanation when the validator is explaining;
+    /// `message` is only built then
+    pub fn trace(&self, rule_id: &str, message: impl FnOnce() -> String) {
+        if let Some(trace) = &self.trace {
+            trace.record(rule_id, message());
+        }
+    }
+    
+    pub fn with_data(mut self, key: &str, value: Value) -> Self {
+        self.additional_data.insert(key.to_string(), value);
+        self
+    }
+    
+    // The accessors below deserialize straight from the borrowed JSON
+    // rather than a clone of it, since rules call them on every event
+    
+    /// Transaction history supplied under `RECENT_TRANSACTIONS_KEY`, empty if absent
+    pub fn recent_transactions(&self) -> Result<Vec<FinancialTransaction>> {
+        match self.additional_data.get(RECENT_TRANSACTIONS_KEY) {
+            Some(value) => Ok(Vec::<FinancialTransaction>::deserialize(value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    /// Balance of an account in one currency as supplied under
+    /// `BALANCES_KEY`, `None` if the account has no balances in the context
+    pub fn balance(&self, account_id: &str, currency_code: &str) -> Result<Option<Money>> {
+        let Some(balances) = self.additional_data.get(BALANCES_KEY).and_then(|b| b.get(account_id)) else {
+            return Ok(None);
+        };
+        match balances.get(currency_code) {
+            Some(value) => Ok(Some(Money::deserialize(value)?)),
+            None => Ok(Some(Money::with_currency_defaults(rust_decimal::Decimal::ZERO, currency_code))),
+        }
+    }
+    
+    /// Type of an account as supplied under `ACCOUNT_TYPES_KEY`
+    pub fn account_type(&self, account_id: &str) -> Result<Option<AccountType>> {
+        match self.additional_data.get(ACCOUNT_TYPES_KEY).and_then(|types| types.get(account_id)) {
+            Some(value) => Ok(Some(AccountType::deserialize(value)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    pub rule_id: String,
+    pub severity: RuleSeverity,
+    pub message: String,
+    pub evidence: Value,
+}
+
+impl Violation {
+    /// Copy with `evidence` redacted by `policy`; rule id, severity and
+    /// message are kept as is. The original is left untouched for the
+    /// internal audit path.
+    pub fn redact(&self, policy: &RedactionPolicy) -> Violation {
+        let mut redacted = self.clone();
+        for rule in &policy.rules {
+            let path: Vec<&str> = rule.path.split('.').collect();
+            redact_path(&mut redacted.evidence, &path, &rule.action);
+        }
+        redacted
+    }
+}
+
+/// Evidence fields to strip before violations leave the system.
+///
+/// Paths are dot-separated object keys into `evidence`; `*` matches every
+/// element of an array or every value of an object, e.g.
+/// `transaction_ids.*`. Paths that don't resolve are ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionPolicy {
+    pub rules: Vec<RedactionRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub path: String,
+    pub action: RedactionAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionAction {
+    /// Drop the field entirely
+    Remove,
+    /// Replace all but the last `n` characters of the value with `*`
+    MaskKeepLast(usize),
+}
+
+impl RedactionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn remove(mut self, path: &str) -> Self {
+        self.rules.push(RedactionRule {
+            path: path.to_string(),
+            action: RedactionAction::Remove,
+        });
+        self
+    }
+    
+    pub fn mask_keep_last(mut self, path: &str, visible: usize) -> Self {
+        self.rules.push(RedactionRule {
+            path: path.to_string(),
+            action: RedactionAction::MaskKeepLast(visible),
+        });
+        self
+    }
+}
+
+fn redact_path(value: &mut Value, path: &[&str], action: &RedactionAction) {
+    let Some((&segment, rest)) = path.split_first() else {
+        return;
+    };
+    
+    if rest.is_empty() {
+        match (value, segment, action) {
+            (Value::Object(map), "*", RedactionAction::Remove) => map.clear(),
+            (Value::Array(items), "*", RedactionAction::Remove) => items.clear(),
+            (Value::Object(map), key, RedactionAction::Remove) => {
+                map.remove(key);
+            }
+            (Value::Object(map), "*", RedactionAction::MaskKeepLast(n)) => {
+                map.values_mut().for_each(|v| *v = mask_value(v, *n));
+            }
+            (Value::Array(items), "*", RedactionAction::MaskKeepLast(n)) => {
+                items.iter_mut().for_each(|v| *v = mask_value(v, *n));
+            }
+            (Value::Object(map), key, RedactionAction::MaskKeepLast(n)) => {
+                if let Some(v) = map.get_mut(key) {
+                    *v = mask_value(v, *n);
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+    
+    match (value, segment) {
+        (Value::Object(map), "*") => map.values_mut().for_each(|v| redact_path(v, rest, action)),
+        (Value::Array(items), "*") => items.iter_mut().for_each(|v| redact_path(v, rest, action)),
+        (Value::Object(map), key) => {
+            if let Some(v) = map.get_mut(key) {
+                redact_path(v, rest, action);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn mask_value(value: &Value, visible: usize) -> Value {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Null => return Value::Null,
+        other => other.to_string(),
+    };
+    let len = text.chars().count();
+    let masked: String = text
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if i + visible < len { '*' } else { c })
+        .collect();
+    Value::String(masked)
+}
+
+/// Ordered from least to most severe
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RuleSeverity {
+    Warning,
+    Error,
+    Critical,
+}
+
+impl RuleSeverity {
+    /// Risk points a violation of this severity scores unless its rule
+    /// overrides `Rule::risk_contribution`
+    pub fn default_risk_points(&self) -> u32 {
+        match self {
+            RuleSeverity::Warning => 10,
+            RuleSeverity::Error => 25,
+            RuleSeverity::Critical => 50,
+        }
+    }
+}
+
+// Example compliance rules
+/// Flags transactions above the limit for their currency. Currencies
+/// without a limit of their own fall back to the default limit if one is
+/// set, and are otherwise not checked.
+pub struct AmountLimitRule {
+    limits: HashMap<String, rust_decimal::Decimal>,
+    default_limit: Option<rust_decimal::Decimal>,
+}
+
+impl AmountLimitRule {
+    /// A limit for a single currency
+    pub fn new(limit: rust_decimal::Decimal, currency: &str) -> Self {
+        Self::per_currency(HashMap::from([(currency.to_string(), limit)]))
+    }
+    
+    pub fn per_currency(limits: HashMap<String, rust_decimal::Decimal>) -> Self {
+        Self {
+            limits,
+            default_limit: None,
+        }
+    }
+    
+    /// Limit for currencies not given one of their own
+    pub fn with_default_limit(mut self, limit: rust_decimal::Decimal) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+    
+    /// The limit for `currency` and whether it is the default
+    fn limit_for(&self, currency: &str) -> Option<(rust_decimal::Decimal, bool)> {
+        match self.limits.get(currency) {
+            Some(limit) => Some((*limit, false)),
+            None => self.default_limit.map(|limit| (limit, true)),
+        }
+    }
+}
+
+#[async_trait]
+impl Rule for AmountLimitRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        
+        if let LedgerEvent::FinancialTransaction(tx) = event {
+            let Some((limit, is_default)) = self.limit_for(&tx.currency) else {
+                context.trace(self.get_rule_id(), || format!("no limit for {}: SKIP", tx.currency));
+                return Ok(violations);
+            };
+            let exceeded = tx.amount.amount > limit;
+            context.trace(self.get_rule_id(), || {
+                format!(
+                    "amount {} {} {} {}limit {} {}: {}",
+                    tx.amount.amount,
+                    tx.currency,
+                    if exceeded { ">" } else { "<=" },
+                    if is_default { "default " } else { "" },
+                    limit,
+                    tx.currency,
+                    if exceeded { "FAIL" } else { "PASS" }
+                )
+            });
+            if exceeded {
+                violations.push(Violation {
+                    rule_id: self.get_rule_id().to_string(),
+                    severity: self.get_severity(),
+                    message: format!(
+                        "Transaction amount {} exceeds {}limit of {} {}",
+                        tx.amount,
+                        if is_default { "default " } else { "" },
+                        limit,
+                        tx.currency
+                    ),
+                    evidence: serde_json::json!({
+                        "transaction_amount": tx.amount.amount,
+                        "currency": tx.currency,
+                        "limit": limit,
+                        // Currency whose configured limit was hit, or
+                        // "default" for the fallback limit
+                        "limit_currency": if is_default { "default" } else { tx.currency.as_str() },
+                    }),
+                });
+            }
+        }
+        
+        Ok(violations)
+    }
+    
+    fn get_rule_id(&self) -> &str {
+        "AMOUNT_LIMIT"
+    }
+    
+    fn is_pure(&self) -> bool {
+        true
+    }
+    
+    /// 20 points for any breach, rising in proportion to the overage up to
+    /// 100 at twice the limit
+    fn risk_contribution(&self, violations: &[Violation]) -> u32 {
+        use rust_decimal::prelude::ToPrimitive;
+        use rust_decimal::Decimal;
+        
+        violations
+            .iter()
+            .map(|v| {
+                let field = |name: &str| {
+                    serde_json::from_value::<Decimal>(v.evidence.get(name).cloned().unwrap_or_default())
+                };
+                let (Ok(amount), Ok(limit)) = (field("transaction_amount"), field("limit")) else {
+                    return v.severity.default_risk_points();
+                };
+                let overage = if limit > Decimal::ZERO {
+                    ((amount - limit) / limit).clamp(Decimal::ZERO, Decimal::ONE)
+                } else {
+                    Decimal::ONE
+                };
+                20 + (overage * Decimal::from(80)).round().to_u32().unwrap_or(80)
+            })
+            .sum()
+    }
+    
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Error
+    }
+}
+
+/// Transaction metadata key holding the originating party's ISO country code
+pub const FROM_COUNTRY_FIELD: &str = "from_country";
+
+/// Transaction metadata key holding the receiving party's ISO country code
+pub const TO_COUNTRY_FIELD: &str = "to_country";
+
+/// Flags transactions whose `FROM_COUNTRY_FIELD` or `TO_COUNTRY_FIELD`
+/// metadata names a sanctioned country. A country value that is not a
+/// string is an evaluation error rather than a pass.
+pub struct SanctionedCountriesRule {
+    sanctioned_countries: Vec<String>,
+}
+
+impl SanctionedCountriesRule {
+    pub fn new(countries: Vec<&str>) -> Self {
+        Self {
+            sanctioned_countries: countries.iter().map(|s| s.to_ascii_uppercase()).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl Rule for SanctionedCountriesRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        
+        // Check if any party in the transaction is from a sanctioned country
+        if let LedgerEvent::FinancialTransaction(tx) = event {
+            for key in [FROM_COUNTRY_FIELD, TO_COUNTRY_FIELD] {
+                let Some(country) = tx.metadata_str(key)? else {
+                    context.trace(self.get_rule_id(), || format!("{} not set: SKIP", key));
+                    continue;
+                };
+                // Compared in place; only a hit pays for the uppercase copy
+                let sanctioned = self
+                    .sanctioned_countries
+                    .iter()
+                    .any(|sanctioned| sanctioned.eq_ignore_ascii_case(country));
+                context.trace(self.get_rule_id(), || {
+                    format!(
+                        "{} {} {}: {}",
+                        key,
+                        country.to_ascii_uppercase(),
+                        if sanctioned { "is sanctioned" } else { "is not sanctioned" },
+                        if sanctioned { "FAIL" } else { "PASS" }
+                    )
+                });
+                if sanctioned {
+                    let country = country.to_ascii_uppercase();
+                    violations.push(Violation {
+                        rule_id: self.get_rule_id().to_string(),
+                        severity: self.get_severity(),
+                        message: format!(
+                            "Transaction {} involves sanctioned country {}",
+                            tx.transaction_id, country
+                        ),
+                        evidence: serde_json::json!({
+                            "transaction_id": tx.transaction_id,
+                            "field": key,
+                            "country": country,
+                        }),
+                    });
+                }
+            }
+        }
+        
+        Ok(violations)
+    }
+    
+    fn get_rule_id(&self) -> &str {
+        "SANCTIONED_COUNTRIES"
+    }
+    
+    fn is_pure(&self) -> bool {
+        true
+    }
+    
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Critical
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StructuringThreshold {
+    pub report_threshold: rust_decimal::Decimal,
+    pub tolerance: rust_decimal::Decimal,
+    pub window: chrono::Duration,
+    pub min_occurrences: usize,
+}
+
+/// Flags accounts that repeatedly transact just under a reporting threshold.
+///
+/// A transaction counts when its amount falls in
+/// `[report_threshold - tolerance, report_threshold)`. Transaction history is
+/// read from `ValidationContext::recent_transactions`.
+pub struct StructuringRule {
+    default: StructuringThreshold,
+    per_currency: HashMap<String, StructuringThreshold>,
+}
+
+impl StructuringRule {
+    pub fn new(
+        report_threshold: rust_decimal::Decimal,
+        tolerance: rust_decimal::Decimal,
+        window: chrono::Duration,
+        min_occurrences: usize,
+    ) -> Self {
+        Self {
+            default: StructuringThreshold {
+                report_threshold,
+                tolerance,
+                window,
+                min_occurrences,
+            },
+            per_currency: HashMap::new(),
+        }
+    }
+    
+    /// Overrides the thresholds for a single currency
+    pub fn with_currency(mut self, currency: &str, threshold: StructuringThreshold) -> Self {
+        self.per_currency.insert(currency.to_string(), threshold);
+        self
+    }
+    
+    fn threshold_for(&self, currency: &str) -> &StructuringThreshold {
+        self.per_currency.get(currency).unwrap_or(&self.default)
+    }
+}
+
+#[async_trait]
+impl Rule for StructuringRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        
+        if let LedgerEvent::FinancialTransaction(tx) = event {
+            let threshold = self.threshold_for(&tx.currency);
+            let in_band = |amount: rust_decimal::Decimal| {
+                amount < threshold.report_threshold
+                    && amount >= threshold.report_threshold - threshold.tolerance
+            };
+            
+            if !in_band(tx.amount.amount) {
+                context.trace(self.get_rule_id(), || {
+                    format!(
+                        "amount {} {} outside [{}, {}): PASS",
+                        tx.amount.amount,
+                        tx.currency,
+                        threshold.report_threshold - threshold.tolerance,
+                        threshold.report_threshold
+                    )
+                });
+                return Ok(violations);
+            }
+            
+            let window_start = tx.timestamp - threshold.window;
+            let mut contributing: Vec<FinancialTransaction> = context
+                .recent_transactions()?
+                .into_iter()
+                .filter(|prior| {
+                    prior.transaction_id != tx.transaction_id
+                        && prior.from_account == tx.from_account
+                        && prior.currency == tx.currency
+                        && prior.timestamp >= window_start
+                        && prior.timestamp <= tx.timestamp
+                        && in_band(prior.amount.amount)
+                })
+                .collect();
+            contributing.push(tx.clone());
+            
+            let structured = contributing.len() >= threshold.min_occurrences;
+            context.trace(self.get_rule_id(), || {
+                format!(
+                    "{} in-band transaction(s) from {} within {}s, {} needed: {}",
+                    contributing.len(),
+                    tx.from_account,
+                    threshold.window.num_seconds(),
+                    threshold.min_occurrences,
+                    if structured { "FAIL" } else { "PASS" }
+                )
+            });
+            if structured {
+                let total = contributing
+                    .iter()
+                    .try_fold(rust_decimal::Decimal::ZERO, |total, t| total.checked_add(t.amount.amount))
+                    .ok_or_else(|| MoneyError::overflow(&tx.currency))?;
+                violations.push(Violation {
+                    rule_id: self.get_rule_id().to_string(),
+                    severity: self.get_severity(),
+                    message: format!(
+                        "Account {} made {} transactions just below the {} {} reporting threshold",
+                        tx.from_account, contributing.len(), threshold.report_threshold, tx.currency
+                    ),
+                    evidence: serde_json::json!({
+                        "account": tx.from_account,
+                        "transaction_ids": contributing.iter().map(|t| &t.transaction_id).collect::<Vec<_>>(),
+                        "total": total,
+                        "currency": tx.currency,
+                        "report_threshold": threshold.report_threshold,
+                        "window_seconds": threshold.window.num_seconds(),
+                    }),
+                });
+            }
+        }
+        
+        Ok(violations)
+    }
+    
+    fn get_rule_id(&self) -> &str {
+        "STRUCTURING"
+    }
+    
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Critical
+    }
+}
+
+/// Metadata key clients may set to identify retries of the same transaction
+pub const IDEMPOTENCY_KEY_FIELD: &str = "idempotency_key";
+
+/// Flags a transaction that repeats a recent one between the same accounts
+/// for the same amount and currency.
+///
+/// When the transaction carries an `idempotency_key` in its metadata, a prior
+/// transaction with the same key is a duplicate regardless of the window.
+pub struct DuplicateTransactionRule {
+    window: chrono::Duration,
+}
+
+impl DuplicateTransactionRule {
+    pub fn new(window: chrono::Duration) -> Self {
+        Self { window }
+    }
 }
 
-pub struct ComplianceValidator {
-    rules: HashMap<String, Box<dyn Rule>>,
-    rule_sets: HashMap<String, Vec<String>>,
+impl Default for DuplicateTransactionRule {
+    fn default() -> Self {
+        Self::new(chrono::Duration::seconds(60))
+    }
 }
 
-impl ComplianceValidator {
-    pub fn new() -> Self {
-        Self {
-            rules: HashMap::new(),
-            rule_sets: HashMap::new(),
+#[async_trait]
+impl Rule for DuplicateTransactionRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        
+        if let LedgerEvent::FinancialTransaction(tx) = event {
+            let idempotency_key = tx.metadata_str(IDEMPOTENCY_KEY_FIELD)?;
+            let window_start = tx.timestamp - self.window;
+            
+            let matched = context.recent_transactions()?.into_iter().find(|prior| {
+                if prior.transaction_id == tx.transaction_id {
+                    return false;
+                }
+                match idempotency_key {
+                    Some(key) => prior.metadata_str(IDEMPOTENCY_KEY_FIELD) == Ok(Some(key)),
+                    None => {
+                        prior.from_account == tx.from_account
+                            && prior.to_account == tx.to_account
+                            && prior.amount.amount == tx.amount.amount
+                            && prior.currency == tx.currency
+                            && prior.timestamp >= window_start
+                            && prior.timestamp <= tx.timestamp
+                    }
+                }
+            });
+            
+            context.trace(self.get_rule_id(), || match (&matched, idempotency_key) {
+                (Some(prior), Some(key)) => {
+                    format!("idempotency key {} used by {}: FAIL", key, prior.transaction_id)
+                }
+                (Some(prior), None) => format!("matches {}: FAIL", prior.transaction_id),
+                (None, _) => "no matching prior transaction: PASS".to_string(),
+            });
+            if let Some(prior) = matched {
+                violations.push(Violation {
+                    rule_id: self.get_rule_id().to_string(),
+                    severity: self.get_severity(),
+                    message: format!(
+                        "Transaction {} duplicates prior transaction {}",
+                        tx.transaction_id, prior.transaction_id
+                    ),
+                    evidence: serde_json::json!({
+                        "transaction_id": tx.transaction_id,
+                        "matched_transaction_id": prior.transaction_id,
+                        "idempotency_key": idempotency_key,
+                        "window_seconds": self.window.num_seconds(),
+                    }),
+                });
+            }
         }
+        
+        Ok(violations)
     }
     
-    pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
-        self.rules.insert(rule.get_rule_id().to_string(), rule);
+    fn get_rule_id(&self) -> &str {
+        "DUPLICATE_TRANSACTION"
     }
     
-    pub fn create_rule_set(&mut self, name: &str, rule_ids: Vec<&str>) {
-        self.rule_sets.insert(
-            name.to_string(),
-            rule_ids.iter().map(|s| s.to_string()).collect(),
-        );
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Error
     }
-    
-    pub async fn validate(&self, event: &LedgerEvent) -> Result<Vec<Violation>> {
-        let context = ValidationContext::new();
+}
+
+/// Flags high-value transactions whose amount is an exact multiple of a round
+/// number. Low severity by design: it informs review rather than blocking.
+pub struct RoundAmountRule {
+    threshold: rust_decimal::Decimal,
+    multiple: rust_decimal::Decimal,
+}
+
+impl RoundAmountRule {
+    pub fn new(threshold: rust_decimal::Decimal, multiple: rust_decimal::Decimal) -> Self {
+        Self { threshold, multiple }
+    }
+}
+
+#[async_trait]
+impl Rule for RoundAmountRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
         
-        // Apply all rules by default
-        for rule in self.rules.values() {
-            match rule.evaluate(event, &context).await {
-                Ok(mut rule_violations) => violations.append(&mut rule_violations),
-                Err(e) => {
-                    violations.push(Violation {
-                        rule_id: rule.get_rule_id().to_string(),
-                        severity: RuleSeverity::Critical,
-                        message: format!("Rule evaluation error: {}", e),
-                        evidence: serde_json::json!({"error": e.to_string()}),
-                    });
+        if let LedgerEvent::FinancialTransaction(tx) = event {
+            let amount = tx.amount.amount;
+            // Decimal remainder is exact, so 9999.99 never counts as a multiple of 1000
+            let is_round = !self.multiple.is_zero() && (amount % self.multiple).is_zero();
+            context.trace(self.get_rule_id(), || {
+                if amount < self.threshold {
+                    format!("amount {} below threshold {}: PASS", amount, self.threshold)
+                } else if is_round {
+                    format!("amount {} is a multiple of {}: FAIL", amount, self.multiple)
+                } else {
+                    format!("amount {} is not a multiple of {}: PASS", amount, self.multiple)
                 }
+            });
+            if amount >= self.threshold && is_round {
+                violations.push(Violation {
+                    rule_id: self.get_rule_id().to_string(),
+                    severity: self.get_severity(),
+                    message: format!(
+                        "Transaction amount {} is a round multiple of {}",
+                        tx.amount, self.multiple
+                    ),
+                    evidence: serde_json::json!({
+                        "transaction_amount": amount,
+                        "currency": tx.currency,
+                        "multiple": self.multiple,
+                    }),
+                });
             }
         }
         
         Ok(violations)
     }
     
-    pub async fn validate_with_rule_set(
-        &self,
-        event: &LedgerEvent,
-        rule_set_name: &str,
-    ) -> Result<Vec<Violation>> {
-        if let Some(rule_ids) = self.rule_sets.get(rule_set_name) {
-            let context = ValidationContext::new();
-            let mut violations = Vec::new();
-            
-            for rule_id in rule_ids {
-                if let Some(rule) = self.rules.get(rule_id) {
-                    match rule.evaluate(event, &context).await {
-                        Ok(mut rule_violations) => violations.append(&mut rule_violations),
-                        Err(e) => {
-                            violations.push(Violation {
-                                rule_id: rule_id.clone(),
-                                severity: RuleSeverity::Critical,
-                                message: format!("Rule evaluation error: {}", e),
-                                evidence: serde_json::json!({"error": e.to_string()}),
-                            });
-                        }
-                    }
-                }
-            }
-            
-            Ok(violations)
-        } else {
-            Err(anyhow::anyhow!("Rule set not found: {}", rule_set_name))
-        }
+    fn get_rule_id(&self) -> &str {
+        "ROUND_AMOUNT"
+    }
+    
+    fn is_pure(&self) -> bool {
+        true
+    }
+    
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Warning
     }
 }
 
-pub struct ValidationContext {
-    pub additional_data: HashMap<String, Value>,
+/// Restricts transactions to a daily window on allowed weekdays, evaluated in
+/// a fixed timezone.
+///
+/// The window includes `open` and excludes `close`. When `close` is not after
+/// `open` the window crosses midnight, and the early-morning part belongs to
+/// the weekday on which the window opened.
+pub struct BusinessHoursRule {
+    timezone: chrono_tz::Tz,
+    open: chrono::NaiveTime,
+    close: chrono::NaiveTime,
+    days: Vec<chrono::Weekday>,
 }
 
-impl ValidationContext {
-    pub fn new() -> Self {
+impl BusinessHoursRule {
+    pub fn new(
+        timezone: chrono_tz::Tz,
+        open: chrono::NaiveTime,
+        close: chrono::NaiveTime,
+        days: Vec<chrono::Weekday>,
+    ) -> Self {
         Self {
-            additional_data: HashMap::new(),
+            timezone,
+            open,
+            close,
+            days,
         }
     }
     
-    pub fn with_data(mut self, key: &str, value: Value) -> Self {
-        self.additional_data.insert(key.to_string(), value);
-        self
+    fn is_allowed(&self, local: &chrono::DateTime<chrono_tz::Tz>) -> bool {
+        use chrono::Datelike;
+        
+        let time = local.time();
+        let weekday = local.weekday();
+        
+        if self.open < self.close {
+            time >= self.open && time < self.close && self.days.contains(&weekday)
+        } else if time >= self.open {
+            self.days.contains(&weekday)
+        } else if time < self.close {
+            self.days.contains(&weekday.pred())
+        } else {
+            false
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Violation {
-    pub rule_id: String,
-    pub severity: RuleSeverity,
-    pub message: String,
-    pub evidence: Value,
+#[async_trait]
+impl Rule for BusinessHoursRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        
+        if let LedgerEvent::FinancialTransaction(tx) = event {
+            let local = tx.timestamp.with_timezone(&self.timezone);
+            let allowed = self.is_allowed(&local);
+            context.trace(self.get_rule_id(), || {
+                format!(
+                    "local time {} {} hours {}-{} ({}): {}",
+                    local.format("%a %H:%M:%S"),
+                    if allowed { "within" } else { "outside" },
+                    self.open,
+                    self.close,
+                    self.timezone,
+                    if allowed { "PASS" } else { "FAIL" }
+                )
+            });
+            if !allowed {
+                violations.push(Violation {
+                    rule_id: self.get_rule_id().to_string(),
+                    severity: self.get_severity(),
+                    message: format!(
+                        "Transaction at {} is outside business hours {}-{} ({})",
+                        local.format("%a %H:%M:%S"), self.open, self.close, self.timezone
+                    ),
+                    evidence: serde_json::json!({
+                        "transaction_id": tx.transaction_id,
+                        "local_time": local.to_rfc3339(),
+                        "timezone": self.timezone.name(),
+                        "open": self.open.to_string(),
+                        "close": self.close.to_string(),
+                    }),
+                });
+            }
+        }
+        
+        Ok(violations)
+    }
+    
+    fn get_rule_id(&self) -> &str {
+        "BUSINESS_HOURS"
+    }
+    
+    fn is_pure(&self) -> bool {
+        true
+    }
+    
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Error
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum RuleSeverity {
-    Warning,
-    Error,
-    Critical,
+/// Enforces which account types may transfer directly to which.
+///
+/// Events only carry account ids, so account types are resolved from the
+/// `ACCOUNT_TYPES_KEY` entry of the `ValidationContext`. Transfers involving
+/// an account whose type is not in the context are not evaluated.
+pub struct AccountTypePolicyRule {
+    allowed: Vec<(AccountType, AccountType)>,
 }
 
-// Example compliance rules
-pub struct AmountLimitRule {
-    limit: rust_decimal::Decimal,
-    currency: String,
+impl AccountTypePolicyRule {
+    /// `allowed` lists the permitted `(from, to)` account type pairs
+    pub fn new(allowed: Vec<(AccountType, AccountType)>) -> Self {
+        Self { allowed }
+    }
+    
+    fn is_allowed(&self, from: &AccountType, to: &AccountType) -> bool {
+        self.allowed.iter().any(|(f, t)| f == from && t == to)
+    }
 }
 
-impl AmountLimitRule {
-    pub fn new(limit: rust_decimal::Decimal, currency: &str) -> Self {
+#[async_trait]
+impl Rule for AccountTypePolicyRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        
+        if let LedgerEvent::FinancialTransaction(tx) = event {
+            let (Some(from_type), Some(to_type)) = (
+                context.account_type(&tx.from_account)?,
+                context.account_type(&tx.to_account)?,
+            ) else {
+                context.trace(self.get_rule_id(), || "account type unknown: SKIP".to_string());
+                return Ok(violations);
+            };
+            
+            let allowed = self.is_allowed(&from_type, &to_type);
+            context.trace(self.get_rule_id(), || {
+                format!(
+                    "{:?} -> {:?} {}: {}",
+                    from_type,
+                    to_type,
+                    if allowed { "allowed" } else { "not allowed" },
+                    if allowed { "PASS" } else { "FAIL" }
+                )
+            });
+            if !allowed {
+                violations.push(Violation {
+                    rule_id: self.get_rule_id().to_string(),
+                    severity: self.get_severity(),
+                    message: format!(
+                        "Transfer from {:?} account {} to {:?} account {} is not allowed",
+                        from_type, tx.from_account, to_type, tx.to_account
+                    ),
+                    evidence: serde_json::json!({
+                        "from_account": tx.from_account,
+                        "from_account_type": from_type,
+                        "to_account": tx.to_account,
+                        "to_account_type": to_type,
+                    }),
+                });
+            }
+        }
+        
+        Ok(violations)
+    }
+    
+    fn get_rule_id(&self) -> &str {
+        "ACCOUNT_TYPE_POLICY"
+    }
+    
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Error
+    }
+}
+
+/// Blocks transfers that would overdraw the sending account.
+///
+/// The sender's balance in the transaction currency comes from the
+/// `BALANCES_KEY` context entry and its type from `ACCOUNT_TYPES_KEY`. Only
+/// account types that hold funds, asset and liability accounts by default,
+/// are protected; equity, revenue and expense accounts routinely carry
+/// negative running balances under the ledger's debit-from/credit-to folding.
+/// Transfers from accounts whose type is not in the context are not
+/// evaluated; a typed account with no balances counts as empty.
+pub struct SufficientFundsRule {
+    protected_types: Vec<AccountType>,
+}
+
+impl SufficientFundsRule {
+    pub fn new() -> Self {
         Self {
-            limit,
-            currency: currency.to_string(),
+            protected_types: vec![AccountType::Asset, AccountType::Liability],
         }
     }
+    
+    pub fn with_protected_types(protected_types: Vec<AccountType>) -> Self {
+        Self { protected_types }
+    }
+}
+
+impl Default for SufficientFundsRule {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[async_trait]
-impl Rule for AmountLimitRule {
-    async fn evaluate(&self, event: &LedgerEvent, _context: &ValidationContext) -> Result<Vec<Violation>> {
+impl Rule for SufficientFundsRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
         
         if let LedgerEvent::FinancialTransaction(tx) = event {
-            if tx.currency == self.currency && tx.amount.amount > self.limit {
+            let Some(account_type) = context.account_type(&tx.from_account)? else {
+                context.trace(self.get_rule_id(), || {
+                    format!("type of {} unknown: SKIP", tx.from_account)
+                });
+                return Ok(violations);
+            };
+            if !self.protected_types.contains(&account_type) {
+                context.trace(self.get_rule_id(), || {
+                    format!("{:?} accounts not protected: SKIP", account_type)
+                });
+                return Ok(violations);
+            }
+            
+            let prior = context
+                .balance(&tx.from_account, &tx.amount.currency_code)?
+                .map_or(rust_decimal::Decimal::ZERO, |m| m.amount);
+            let resulting = prior
+                .checked_sub(tx.amount.amount)
+                .ok_or_else(|| MoneyError::overflow(&tx.amount.currency_code))?;
+            context.trace(self.get_rule_id(), || {
+                format!(
+                    "balance {} - amount {} = {} {}: {}",
+                    prior,
+                    tx.amount.amount,
+                    resulting,
+                    tx.amount.currency_code,
+                    if resulting < rust_decimal::Decimal::ZERO { "FAIL" } else { "PASS" }
+                )
+            });
+            
+            if resulting < rust_decimal::Decimal::ZERO {
+                let as_money = |amount| Money {
+                    amount,
+                    currency_code: tx.amount.currency_code.clone(),
+                    precision: tx.amount.precision,
+                };
                 violations.push(Violation {
                     rule_id: self.get_rule_id().to_string(),
                     severity: self.get_severity(),
                     message: format!(
-                        "Transaction amount {} {} exceeds limit of {} {}",
-                        tx.amount.amount, tx.currency, self.limit, self.currency
+                        "Transaction {} of {} would overdraw {:?} account {} (balance {})",
+                        tx.transaction_id, tx.amount, account_type, tx.from_account, as_money(prior)
                     ),
                     evidence: serde_json::json!({
+                        "account": tx.from_account,
+                        "account_type": account_type,
+                        "prior_balance": prior,
                         "transaction_amount": tx.amount.amount,
-                        "currency": tx.currency,
-                        "limit": self.limit,
+                        "resulting_balance": resulting,
+                        "currency": tx.amount.currency_code,
                     }),
                 });
             }
@@ -163,42 +1758,267 @@ impl Rule for AmountLimitRule {
     }
     
     fn get_rule_id(&self) -> &str {
-        "AMOUNT_LIMIT"
+        "SUFFICIENT_FUNDS"
     }
     
     fn get_severity(&self) -> RuleSeverity {
-        RuleSeverity::Error
+        RuleSeverity::Critical
     }
 }
 
-pub struct SanctionedCountriesRule {
-    sanctioned_countries: Vec<String>,
+/// Which events an `ExemptionRule` exempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExemptionPredicate {
+    /// Transactions from or to this account
+    Account(String),
+    /// Transactions carrying this tag
+    Tag(String),
+    /// Transactions whose metadata has this key set to `true`
+    MetadataFlag(String),
 }
 
-impl SanctionedCountriesRule {
-    pub fn new(countries: Vec<&str>) -> Self {
+impl ExemptionPredicate {
+    pub fn matches(&self, event: &LedgerEvent) -> bool {
+        let LedgerEvent::FinancialTransaction(tx) = event else {
+            return false;
+        };
+        match self {
+            ExemptionPredicate::Account(account) => {
+                tx.from_account == *account || tx.to_account == *account
+            }
+            ExemptionPredicate::Tag(tag) => tx.tags.contains(tag),
+            ExemptionPredicate::MetadataFlag(flag) => {
+                tx.metadata_flag(flag).unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// Suppresses an inner rule's violations for pre-cleared events.
+///
+/// The wrapper takes the inner rule's id and priority. The inner rule still
+/// runs for exempt events; if it flags anything, its violations are replaced
+/// by a single `Warning` noting the exemption, with the suppressed violations
+/// kept in the evidence for audit.
+pub struct ExemptionRule {
+    inner: Box<dyn Rule>,
+    predicate: ExemptionPredicate,
+}
+
+impl ExemptionRule {
+    pub fn new(inner: Box<dyn Rule>, predicate: ExemptionPredicate) -> Self {
+        Self { inner, predicate }
+    }
+}
+
+#[async_trait]
+impl Rule for ExemptionRule {
+    async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
+        let violations = self.inner.evaluate(event, context).await?;
+        if violations.is_empty() || !self.predicate.matches(event) {
+            return Ok(violations);
+        }
+        context.trace(self.get_rule_id(), || {
+            format!("exemption {:?} applied: {} violation(s) suppressed", self.predicate, violations.len())
+        });
+        
+        Ok(vec![Violation {
+            rule_id: self.get_rule_id().to_string(),
+            severity: RuleSeverity::Warning,
+            message: format!(
+                "Exemption applied to {}: {} violation(s) suppressed",
+                self.get_rule_id(),
+                violations.len()
+            ),
+            evidence: serde_json::json!({
+                "exemption": self.predicate,
+                "suppressed": violations,
+            }),
+        }])
+    }
+    
+    fn get_rule_id(&self) -> &str {
+        self.inner.get_rule_id()
+    }
+    
+    fn get_severity(&self) -> RuleSeverity {
+        self.inner.get_severity()
+    }
+    
+    fn priority(&self) -> i32 {
+        self.inner.priority()
+    }
+    
+    // The predicate only reads the event
+    fn is_pure(&self) -> bool {
+        self.inner.is_pure()
+    }
+    
+    /// An applied exemption scores as the warning it was reduced to
+    fn risk_contribution(&self, violations: &[Violation]) -> u32 {
+        if violations.iter().any(|v| v.evidence.get("exemption").is_some()) {
+            violations.iter().map(|v| v.severity.default_risk_points()).sum()
+        } else {
+            self.inner.risk_contribution(violations)
+        }
+    }
+}
+
+/// Entity an `EscalationRule` counts occurrences against: the sending
+/// account of a transaction, the entity id of any other event
+pub fn escalation_entity(event: &LedgerEvent) -> String {
+    match event {
+        LedgerEvent::FinancialTransaction(tx) => tx.from_account.clone(),
+        other => other.get_entity_id(),
+    }
+}
+
+/// Where an `EscalationRule` keeps how often its inner rule has fired
+#[async_trait]
+pub trait EscalationStore: Send + Sync {
+    /// Notes that `rule_id` fired for `entity` at `at`, and returns how many
+    /// times it has within `window` up to and including `at`
+    async fn record_occurrence(
+        &self,
+        rule_id: &str,
+        entity: &str,
+        at: chrono::DateTime<chrono::Utc>,
+        window: chrono::Duration,
+    ) -> Result<usize>;
+}
+
+/// Occurrences held in process memory, so counts restart from zero with the
+/// process. Every firing counts, including ones whose event was rejected.
+#[derive(Default)]
+pub struct InMemoryEscalationStore {
+    occurrences: Mutex<HashMap<(String, String), Vec<chrono::DateTime<chrono::Utc>>>>,
+}
+
+#[async_trait]
+impl EscalationStore for InMemoryEscalationStore {
+    async fn record_occurrence(
+        &self,
+        rule_id: &str,
+        entity: &str,
+        at: chrono::DateTime<chrono::Utc>,
+        window: chrono::Duration,
+    ) -> Result<usize> {
+        let mut occurrences = self.occurrences.lock().unwrap();
+        let times = occurrences
+            .entry((rule_id.to_string(), entity.to_string()))
+            .or_default();
+        // Older occurrences can never count again
+        times.retain(|t| *t > at - window);
+        times.push(at);
+        Ok(times.iter().filter(|t| **t <= at).count())
+    }
+}
+
+/// Raises an inner rule's violations to `escalated_severity` once it has
+/// fired `threshold` times for the same entity (see `escalation_entity`)
+/// within `window`, counting the current event. Three `Warning`s for one
+/// account in a day can so become an `Error`.
+///
+/// The wrapper takes the inner rule's id and priority. Violations already
+/// at or above `escalated_severity` are left as they are. Each violation
+/// raised once the threshold is reached carries the occurrence count, the
+/// window and the severity it was escalated from in its evidence.
+pub struct EscalationRule {
+    inner: Box<dyn Rule>,
+    store: Arc<dyn EscalationStore>,
+    threshold: usize,
+    window: chrono::Duration,
+    escalated_severity: RuleSeverity,
+}
+
+impl EscalationRule {
+    pub fn new(
+        inner: Box<dyn Rule>,
+        store: Arc<dyn EscalationStore>,
+        threshold: usize,
+        window: chrono::Duration,
+        escalated_severity: RuleSeverity,
+    ) -> Self {
         Self {
-            sanctioned_countries: countries.iter().map(|s| s.to_string()).collect(),
+            inner,
+            store,
+            threshold,
+            window,
+            escalated_severity,
         }
     }
 }
 
 #[async_trait]
-impl Rule for SanctionedCountriesRule {
+impl Rule for EscalationRule {
     async fn evaluate(&self, event: &LedgerEvent, context: &ValidationContext) -> Result<Vec<Violation>> {
-        let mut violations = Vec::new();
+        let mut violations = self.inner.evaluate(event, context).await?;
+        if violations.is_empty() {
+            return Ok(violations);
+        }
         
-        // Check if any party in the transaction is from a sanctioned country
-        // This would typically check against metadata or additional data sources
+        let entity = escalation_entity(event);
+        let occurrences = self
+            .store
+            .record_occurrence(self.get_rule_id(), &entity, event.get_timestamp(), self.window)
+            .await?;
+        let escalated = occurrences >= self.threshold;
+        context.trace(self.get_rule_id(), || {
+            format!(
+                "{} occurrence(s) for {} within {}s, {} escalate: {}",
+                occurrences,
+                entity,
+                self.window.num_seconds(),
+                self.threshold,
+                if escalated { "ESCALATE" } else { "KEEP" }
+            )
+        });
+        if !escalated {
+            return Ok(violations);
+        }
         
+        for violation in &mut violations {
+            let original = violation.severity.clone();
+            if original >= self.escalated_severity {
+                continue;
+            }
+            violation.severity = self.escalated_severity.clone();
+            let escalation = serde_json::json!({
+                "occurrences": occurrences,
+                "window_seconds": self.window.num_seconds(),
+                "threshold": self.threshold,
+                "escalated_from": original,
+            });
+            match &mut violation.evidence {
+                Value::Object(fields) => {
+                    fields.insert("escalation".to_string(), escalation);
+                }
+                other => {
+                    *other = serde_json::json!({"original": other.take(), "escalation": escalation});
+                }
+            }
+        }
         Ok(violations)
     }
     
     fn get_rule_id(&self) -> &str {
-        "SANCTIONED_COUNTRIES"
+        self.inner.get_rule_id()
     }
     
     fn get_severity(&self) -> RuleSeverity {
-        RuleSeverity::Critical
+        self.inner.get_severity().max(self.escalated_severity.clone())
+    }
+    
+    fn priority(&self) -> i32 {
+        self.inner.priority()
+    }
+    
+    fn depends_on(&self) -> Vec<String> {
+        self.inner.depends_on()
+    }
+    
+    async fn contribute(&self, event: &LedgerEvent, context: &mut ValidationContext) -> Result<()> {
+        self.inner.contribute(event, context).await
     }
 }
@@ -0,0 +1,66 @@
+use crate::core::event::AdjustmentReason;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Rule id of violations raised when an adjustment's authorizer is denied
+pub const ADJUSTMENT_AUTHORIZATION_RULE_ID: &str = "ADJUSTMENT_AUTHORIZATION";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthorizationDecision {
+    Allow,
+    Deny(String),
+}
+
+/// Decides whether an actor may post a `BalanceAdjustment` for a reason.
+/// Consulted by `DigitalLedger` on every adjustment it appends.
+#[async_trait]
+pub trait AuthorizationPolicy: Send + Sync {
+    async fn authorize_adjustment(
+        &self,
+        reason: &AdjustmentReason,
+        authorized_by: &str,
+    ) -> anyhow::Result<AuthorizationDecision>;
+}
+
+/// Static list of the actors allowed to authorize each adjustment reason.
+/// Reasons with no entry are denied to everyone.
+#[derive(Debug, Clone, Default)]
+pub struct AdjustmentAllowList {
+    allowed: HashMap<AdjustmentReason, Vec<String>>,
+}
+
+impl AdjustmentAllowList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow(mut self, reason: AdjustmentReason, actors: Vec<&str>) -> Self {
+        self.allowed
+            .entry(reason)
+            .or_default()
+            .extend(actors.into_iter().map(str::to_string));
+        self
+    }
+}
+
+#[async_trait]
+impl AuthorizationPolicy for AdjustmentAllowList {
+    async fn authorize_adjustment(
+        &self,
+        reason: &AdjustmentReason,
+        authorized_by: &str,
+    ) -> anyhow::Result<AuthorizationDecision> {
+        let permitted = self
+            .allowed
+            .get(reason)
+            .is_some_and(|actors| actors.iter().any(|a| a == authorized_by));
+        Ok(if permitted {
+            AuthorizationDecision::Allow
+        } else {
+            AuthorizationDecision::Deny(format!(
+                "{} is not permitted to authorize {:?} adjustments",
+                authorized_by, reason
+            ))
+        })
+    }
+}
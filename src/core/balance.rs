@@ -0,0 +1,172 @@
+use crate::core::event::{
+    AccountType, AdjustmentReason, FinancialTransaction, LedgerEvent, Money, MoneyError, RoundingMode,
+};
+use crate::core::LedgerError;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Event types that can move an account balance
+pub const BALANCE_EVENT_TYPES: &[&str] = &[
+    "account_creation",
+    "financial_transaction",
+    "balance_adjustment",
+    "transaction_reversal",
+    "journal_entry",
+];
+
+/// Sign convention for journal lines posting to an account with no
+/// `AccountCreation` in the events: a debit lowers the balance and a credit
+/// raises it, the same way transfers move balances
+pub const UNTYPED_ACCOUNT_CONVENTION: AccountType = AccountType::Liability;
+
+/// Type of `account_id` as recorded by its `AccountCreation`, if any
+pub fn account_type<'a>(
+    account_id: &str,
+    events: impl IntoIterator<Item = &'a LedgerEvent>,
+) -> Option<AccountType> {
+    events.into_iter().find_map(|event| match event {
+        LedgerEvent::AccountCreation(acct) if acct.account_id == account_id => Some(acct.account_type.clone()),
+        _ => None,
+    })
+}
+
+/// One change to an account's balance: the index of the event that caused
+/// it and the signed amount it moved, in that leg's currency
+#[derive(Debug, Clone)]
+pub struct BalanceMovement {
+    pub index: usize,
+    pub delta: Money,
+}
+
+/// Folds events into per-currency balances for one account.
+///
+/// Events must be given in the order they should be applied; see
+/// `balance_movements` for how each event type moves a balance. The
+/// resulting precision is the largest precision of any contributing amount.
+/// A balance outside `Decimal`'s range is a `LedgerError::MoneyError`.
+pub fn fold_balances<'a>(
+    account_id: &str,
+    events: impl IntoIterator<Item = &'a LedgerEvent>,
+) -> Result<HashMap<String, Money>, LedgerError> {
+    let mut balances: HashMap<String, Money> = HashMap::new();
+    for movement in balance_movements(account_id, events)? {
+        apply(&mut balances, &movement.delta)?;
+    }
+    Ok(balances)
+}
+
+/// Lists every movement of one account's balance, in event order.
+///
+/// Transfers debit `from_account` in the transfer currency and credit
+/// `to_account` in the settlement currency (the same one unless the transfer
+/// is FX); a write-off reduces the balance and every other adjustment reason
+/// adds its (signed) amount; a reversal undoes the original transfer, which
+/// must appear earlier in `events`. Each line of a journal entry posting to
+/// the account moves it on its own, as `AccountType::apply` does for the
+/// type its `AccountCreation` recorded, or `UNTYPED_ACCOUNT_CONVENTION`
+/// before or without one.
+pub fn balance_movements<'a>(
+    account_id: &str,
+    events: impl IntoIterator<Item = &'a LedgerEvent>,
+) -> Result<Vec<BalanceMovement>, LedgerError> {
+    let mut movements = Vec::new();
+    // transaction_id -> transfer, for resolving reversals
+    let mut transfers: HashMap<&str, &FinancialTransaction> = HashMap::new();
+    let mut convention = UNTYPED_ACCOUNT_CONVENTION;
+    let mut push = |index: usize, money: &Money, amount: Decimal| {
+        movements.push(BalanceMovement {
+            index,
+            delta: Money { amount, ..money.clone() },
+        })
+    };
+
+    for (index, event) in events.into_iter().enumerate() {
+        match event {
+            LedgerEvent::AccountCreation(acct) if acct.account_id == account_id => {
+                convention = acct.account_type.clone();
+                push(index, &acct.initial_balance, acct.initial_balance.amount);
+            }
+            LedgerEvent::FinancialTransaction(tx) => {
+                transfers.insert(&tx.transaction_id, tx);
+                if tx.from_account != account_id && tx.to_account != account_id {
+                    continue;
+                }
+                if tx.currency != tx.amount.currency_code {
+                    return Err(LedgerError::validation(
+                        "currency",
+                        format!(
+                            "Transaction {} mixes currencies {} and {}",
+                            tx.transaction_id, tx.currency, tx.amount.currency_code
+                        ),
+                    ));
+                }
+                if tx.from_account == account_id {
+                    push(index, &tx.amount, -tx.amount.amount);
+                }
+                if tx.to_account == account_id {
+                    let credited = tx.credited_amount();
+                    push(index, credited, credited.amount);
+                }
+            }
+            LedgerEvent::BalanceAdjustment(adj) if adj.account_id == account_id => {
+                let delta = match adj.reason {
+                    AdjustmentReason::WriteOff => -adj.amount.amount.abs(),
+                    _ => adj.amount.amount,
+                };
+                push(index, &adj.amount, delta);
+            }
+            LedgerEvent::TransactionReversal(rev) => {
+                let Some(original) = transfers.get(rev.original_transaction_id.as_str()) else {
+                    continue;
+                };
+                if original.from_account == account_id {
+                    push(index, &rev.reversed_amount, rev.reversed_amount.amount);
+                }
+                if original.to_account == account_id {
+                    let reversed = settled_reversal(original, &rev.reversed_amount)?;
+                    push(index, &reversed, -reversed.amount);
+                }
+            }
+            LedgerEvent::JournalEntry(entry) => {
+                for line in entry.lines.iter().filter(|line| line.account == account_id) {
+                    push(index, &line.amount, convention.apply(Decimal::ZERO, line)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(movements)
+}
+
+/// The part of an FX transfer's settlement leg undone by reversing
+/// `reversed` of its source amount, at the original rate
+fn settled_reversal(original: &FinancialTransaction, reversed: &Money) -> Result<Money, MoneyError> {
+    match (&original.settlement_amount, original.exchange_rate) {
+        (Some(settlement), Some(rate)) if settlement.currency_code != reversed.currency_code => {
+            let converted = reversed.convert(rate, &settlement.currency_code, RoundingMode::default())?;
+            Ok(Money {
+                precision: settlement.precision.max(converted.precision),
+                ..converted
+            })
+        }
+        _ => Ok(reversed.clone()),
+    }
+}
+
+fn apply(balances: &mut HashMap<String, Money>, delta: &Money) -> Result<(), MoneyError> {
+    let entry = balances
+        .entry(delta.currency_code.clone())
+        .or_insert_with(|| Money {
+            amount: Decimal::ZERO,
+            currency_code: delta.currency_code.clone(),
+            precision: delta.precision,
+        });
+
+    entry.amount = entry
+        .amount
+        .checked_add(delta.amount)
+        .ok_or_else(|| MoneyError::overflow(&delta.currency_code))?;
+    entry.precision = entry.precision.max(delta.precision);
+    Ok(())
+}
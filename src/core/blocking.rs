@@ -0,0 +1,235 @@
+//! Synchronous facade over `DigitalLedger` for callers that aren't async,
+//! such as a CLI or an FFI boundary.
+
+use crate::compliance::validator::ComplianceValidator;
+use crate::core::checkpoint::SealAttestation;
+use crate::core::event::LedgerEvent;
+use crate::core::ledger::{
+    AppendOutcome, BatchResult, ChainVerification, DigitalLedger, LedgerConfig, LedgerError,
+    LedgerRecord, LedgerStats, ObserverLag,
+};
+use crate::core::reconciliation::LedgerDiff;
+use crate::core::integrity::SignatureVerification;
+use crate::storage::append_only::{AppendOnlyStorage, TagFilter};
+use crate::storage::merkle_tree::{ConsistencyProof, MerkleProof};
+use ring::signature::Ed25519KeyPair;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Runs `DigitalLedger` calls to completion on a runtime it owns.
+///
+/// The runtime is multi-threaded and created once, so background work such
+/// as the append queue worker keeps running between calls, and every call
+/// reuses it instead of starting a runtime of its own. `BlockingLedger` is
+/// `Send + Sync`: several threads may call it at once, and their calls run
+/// concurrently on the runtime just as concurrent async calls would.
+///
+/// Each call blocks the calling thread, and panics if made from inside an
+/// async context. This is meant for the edges of a system; async code
+/// should use the `DigitalLedger` from `ledger()` directly.
+pub struct BlockingLedger {
+    runtime: Runtime,
+    ledger: Arc<DigitalLedger>,
+}
+
+impl BlockingLedger {
+    /// Starts a runtime and opens the ledger on it
+    pub fn open(
+        storage: Arc<dyn AppendOnlyStorage>,
+        validator: Arc<ComplianceValidator>,
+        config: LedgerConfig,
+    ) -> anyhow::Result<Self> {
+        let runtime = new_runtime()?;
+        let ledger = runtime.block_on(DigitalLedger::new(storage, validator, config))?;
+        Ok(Self {
+            runtime,
+            ledger: Arc::new(ledger),
+        })
+    }
+
+    /// Starts a runtime and attaches to an existing chain on it; see
+    /// `DigitalLedger::open_existing`
+    pub fn open_existing(
+        storage: Arc<dyn AppendOnlyStorage>,
+        validator: Arc<ComplianceValidator>,
+        config: LedgerConfig,
+    ) -> anyhow::Result<Self> {
+        let runtime = new_runtime()?;
+        let ledger = runtime.block_on(DigitalLedger::open_existing(storage, validator, config))?;
+        Ok(Self {
+            runtime,
+            ledger: Arc::new(ledger),
+        })
+    }
+
+    /// Starts a runtime and opens the ledger on it with an append queue of
+    /// `capacity`; see `DigitalLedger::with_append_queue`
+    pub fn open_with_append_queue(
+        storage: Arc<dyn AppendOnlyStorage>,
+        validator: Arc<ComplianceValidator>,
+        config: LedgerConfig,
+        capacity: usize,
+    ) -> anyhow::Result<Self> {
+        let runtime = new_runtime()?;
+        let ledger = runtime.block_on(async {
+            DigitalLedger::new(storage, validator, config)
+                .await
+                .map(|ledger| ledger.with_append_queue(capacity))
+        })?;
+        Ok(Self { runtime, ledger })
+    }
+
+    /// The underlying ledger, for sharing with async code
+    pub fn ledger(&self) -> &Arc<DigitalLedger> {
+        &self.ledger
+    }
+
+    /// The runtime calls run on, for ledger operations not wrapped here
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    pub fn append_event(
+        &self,
+        event: LedgerEvent,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<String, LedgerError> {
+        self.runtime.block_on(self.ledger.append_event(event, metadata))
+    }
+
+    pub fn append_event_with_visibility(
+        &self,
+        event: LedgerEvent,
+        metadata: Option<serde_json::Value>,
+        visibility: Vec<String>,
+    ) -> Result<String, LedgerError> {
+        self.runtime
+            .block_on(self.ledger.append_event_with_visibility(event, metadata, visibility))
+    }
+
+    pub fn append_batch(
+        &self,
+        events: Vec<(LedgerEvent, Option<serde_json::Value>)>,
+    ) -> Result<Vec<String>, LedgerError> {
+        self.runtime.block_on(self.ledger.append_batch(events))
+    }
+
+    pub fn append_batch_lenient(
+        &self,
+        events: Vec<(LedgerEvent, Option<serde_json::Value>)>,
+    ) -> Result<BatchResult, LedgerError> {
+        self.runtime.block_on(self.ledger.append_batch_lenient(events))
+    }
+
+    pub fn append_or_quarantine(
+        &self,
+        event: LedgerEvent,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<AppendOutcome, LedgerError> {
+        self.runtime.block_on(self.ledger.append_or_quarantine(event, metadata))
+    }
+
+    pub fn append_replicated(&self, record: LedgerRecord) -> Result<String, LedgerError> {
+        self.runtime.block_on(self.ledger.append_replicated(record))
+    }
+
+    pub fn release_from_quarantine(&self, quarantine_event_id: &str, approver: &str) -> Result<String, LedgerError> {
+        self.runtime
+            .block_on(self.ledger.release_from_quarantine(quarantine_event_id, approver))
+    }
+
+    pub fn get_audit_trail(
+        &self,
+        entity_id: Option<&str>,
+        start_time: Option<chrono::DateTime<chrono::Utc>>,
+        end_time: Option<chrono::DateTime<chrono::Utc>>,
+        event_types: Option<&[String]>,
+        tags: Option<&TagFilter>,
+        caller_scopes: Option<&[String]>,
+    ) -> Result<Vec<LedgerRecord>, LedgerError> {
+        self.runtime.block_on(
+            self.ledger
+                .get_audit_trail(entity_id, start_time, end_time, event_types, tags, caller_scopes),
+        )
+    }
+
+    pub fn get_record(&self, event_id: &str) -> Result<Option<LedgerRecord>, LedgerError> {
+        self.runtime.block_on(self.ledger.get_record(event_id))
+    }
+
+    pub fn verify_integrity(&self) -> Result<bool, LedgerError> {
+        self.runtime.block_on(self.ledger.verify_integrity())
+    }
+
+    pub fn verify_integrity_parallel(&self, segment_size: usize) -> Result<ChainVerification, LedgerError> {
+        self.runtime.block_on(self.ledger.verify_integrity_parallel(segment_size))
+    }
+
+    pub fn verify_since_checkpoint(&self) -> Result<bool, LedgerError> {
+        self.runtime.block_on(self.ledger.verify_since_checkpoint())
+    }
+
+    pub fn verify_signatures(&self) -> Result<SignatureVerification, LedgerError> {
+        self.runtime.block_on(self.ledger.verify_signatures())
+    }
+
+    pub fn diff(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<LedgerDiff, LedgerError> {
+        self.runtime.block_on(self.ledger.diff(from, to))
+    }
+
+    pub fn get_merkle_root(&self) -> Result<String, LedgerError> {
+        self.runtime.block_on(self.ledger.get_merkle_root())
+    }
+
+    pub fn get_merkle_proof(&self, event_id: &str) -> Result<Option<MerkleProof>, LedgerError> {
+        self.runtime.block_on(self.ledger.get_merkle_proof(event_id))
+    }
+
+    pub fn get_consistency_proof(
+        &self,
+        from_size: usize,
+        to_size: usize,
+    ) -> Result<Option<ConsistencyProof>, LedgerError> {
+        self.runtime.block_on(self.ledger.get_consistency_proof(from_size, to_size))
+    }
+
+    pub fn stats(&self) -> Result<LedgerStats, LedgerError> {
+        self.runtime.block_on(self.ledger.stats())
+    }
+
+    pub fn export_ndjson<W: Write>(&self, writer: W) -> Result<usize, LedgerError> {
+        self.runtime.block_on(self.ledger.export_ndjson(writer))
+    }
+
+    pub fn import_ndjson<R: Read>(&self, reader: R) -> Result<usize, LedgerError> {
+        self.runtime.block_on(self.ledger.import_ndjson(reader))
+    }
+
+    pub fn seal_ledger(&self) -> Result<(), LedgerError> {
+        self.runtime.block_on(self.ledger.seal_ledger())
+    }
+
+    pub fn seal_and_attest(&self, signer: &Ed25519KeyPair) -> Result<SealAttestation, LedgerError> {
+        self.runtime.block_on(self.ledger.seal_and_attest(signer))
+    }
+
+    pub fn set_maintenance_mode(&self, enabled: bool) {
+        self.runtime.block_on(self.ledger.set_maintenance_mode(enabled))
+    }
+
+    pub fn observer_lag(&self) -> Vec<ObserverLag> {
+        self.ledger.observer_lag()
+    }
+}
+
+fn new_runtime() -> std::io::Result<Runtime> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("ledger-blocking")
+        .build()
+}
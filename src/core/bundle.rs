@@ -0,0 +1,125 @@
+use crate::core::integrity::{genesis_seed, root_signing_message, verify_ed25519_tag};
+use crate::core::ledger::{id_break, link_target, links_to_genesis, Erasures, LedgerRecord};
+use crate::storage::merkle_tree;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Portable, self-verifying copy of a sealed chain: every record, the Merkle
+/// root over them, and a signature over that root when the ledger signs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealedBundle {
+    /// `MERKLE_TREE_VERSION` the root was computed under; bundles from
+    /// before the field existed used version 1
+    #[serde(default = "legacy_tree_version")]
+    pub merkle_tree_version: u8,
+    pub chain_id: String,
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub records: Vec<LedgerRecord>,
+    pub merkle_root: String,
+    /// `<scheme>:<hex>` tag over `root_signing_message`
+    pub root_signature: Option<String>,
+}
+
+fn legacy_tree_version() -> u8 {
+    1
+}
+
+#[derive(Debug, Error)]
+pub enum BundleError {
+    #[error("Merkle tree version {0} is not supported by this verifier")]
+    UnsupportedTreeVersion(u8),
+    #[error("Record {event_id} does not hash to its event id: {reason}")]
+    IdMismatch { event_id: String, reason: String },
+    #[error("Record {event_id} links to {actual:?}, expected {expected:?}")]
+    BrokenLink {
+        event_id: String,
+        expected: Option<String>,
+        actual: Option<String>,
+    },
+    #[error("Record {event_id} belongs to chain {actual}, not {expected}")]
+    WrongChain {
+        event_id: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Merkle root {actual} does not match bundled root {expected}")]
+    RootMismatch { expected: String, actual: String },
+    #[error("Bundle has no root signature")]
+    MissingSignature,
+    #[error("Root signature is invalid: {0}")]
+    InvalidSignature(String),
+}
+
+/// Verifies a bundle without a running ledger: re-derives every event id,
+/// re-walks the chain links, recomputes the Merkle root, and, when
+/// `public_key` is given, checks the Ed25519 root signature against it.
+pub fn verify_sealed_bundle(bundle: &SealedBundle, public_key: Option<&[u8]>) -> Result<(), BundleError> {
+    if bundle.merkle_tree_version != merkle_tree::MERKLE_TREE_VERSION {
+        return Err(BundleError::UnsupportedTreeVersion(bundle.merkle_tree_version));
+    }
+
+    let erasures = Erasures::from_records(&bundle.records);
+    let mut expected_previous: Option<String> = None;
+    for (i, record) in bundle.records.iter().enumerate() {
+        if record.chain_id != bundle.chain_id {
+            return Err(BundleError::WrongChain {
+                event_id: record.event_id.clone(),
+                expected: bundle.chain_id.clone(),
+                actual: record.chain_id.clone(),
+            });
+        }
+
+        // Older records are checked against the bytes they were stored as,
+        // erased ones against their erasure
+        match id_break(record, &erasures) {
+            Ok(None) => {}
+            Ok(Some(reason)) => {
+                return Err(BundleError::IdMismatch {
+                    event_id: record.event_id.clone(),
+                    reason,
+                })
+            }
+            Err(e) => {
+                return Err(BundleError::IdMismatch {
+                    event_id: record.event_id.clone(),
+                    reason: e.to_string(),
+                })
+            }
+        }
+
+        let linked = if i == 0 {
+            links_to_genesis(record, &bundle.chain_id)
+        } else {
+            record.previous_hash == expected_previous
+        };
+        if !linked {
+            return Err(BundleError::BrokenLink {
+                event_id: record.event_id.clone(),
+                expected: expected_previous.or_else(|| Some(genesis_seed(&bundle.chain_id))),
+                actual: record.previous_hash.clone(),
+            });
+        }
+        expected_previous = Some(link_target(record));
+    }
+
+    let leaves: Vec<&str> = bundle.records.iter().map(|r| r.event_id.as_str()).collect();
+    let root = hex::encode(merkle_tree::compute_root(&leaves));
+    if root != bundle.merkle_root {
+        return Err(BundleError::RootMismatch {
+            expected: bundle.merkle_root.clone(),
+            actual: root,
+        });
+    }
+
+    if let Some(public_key) = public_key {
+        let signature = bundle.root_signature.as_deref().ok_or(BundleError::MissingSignature)?;
+        let message = root_signing_message(&bundle.chain_id, &bundle.merkle_root, bundle.records.len());
+        match verify_ed25519_tag(public_key, &message, signature) {
+            Ok(true) => {}
+            Ok(false) => return Err(BundleError::InvalidSignature("signature does not match".to_string())),
+            Err(reason) => return Err(BundleError::InvalidSignature(reason)),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::core::event::LedgerEvent;
+use crate::core::integrity::{cursor_signing_message, seal_signing_message, verify_ed25519_tag};
+use crate::core::ledger::LedgerRecord;
+use crate::storage::append_only::StorageError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// `AuditLog` action of the record noting where a checkpoint was anchored
+pub const CHECKPOINT_ACTION: &str = "checkpoint_anchored";
+
+/// The chain's Merkle root at a point in time, for anchoring in an external
+/// system such as a public blockchain or timestamping service. The root
+/// covers the first `record_count` records, ending at `head_event_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// `MERKLE_TREE_VERSION` the root was computed under
+    pub merkle_tree_version: u8,
+    pub chain_id: String,
+    pub merkle_root: String,
+    pub record_count: usize,
+    /// `None` only for a checkpoint of an empty chain
+    pub head_event_id: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// `<scheme>:<hex>` tag over `root_signing_message`, when the ledger signs
+    pub signature: Option<String>,
+}
+
+impl Checkpoint {
+    /// The checkpoint and external anchor noted by a record written with
+    /// `DigitalLedger::append_checkpoint`, or `None` for any other record
+    pub fn from_record(record: &LedgerRecord) -> Option<(Checkpoint, String)> {
+        let LedgerEvent::AuditLog(log) = &record.event else {
+            return None;
+        };
+        if log.action != CHECKPOINT_ACTION {
+            return None;
+        }
+        let checkpoint = serde_json::from_value(log.changes.get("checkpoint")?.clone()).ok()?;
+        let anchor = log.changes.get("external_anchor")?.as_str()?.to_string();
+        Some((checkpoint, anchor))
+    }
+}
+
+/// Signed statement of a ledger's final state, produced by
+/// `DigitalLedger::seal_and_attest` in the same step as sealing, so the root
+/// and count are exactly what the sealed ledger holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealAttestation {
+    /// `MERKLE_TREE_VERSION` the root was computed under
+    pub merkle_tree_version: u8,
+    pub chain_id: String,
+    pub merkle_root: String,
+    pub record_count: usize,
+    /// `None` only when an empty ledger was sealed
+    pub head_event_id: Option<String>,
+    pub sealed_at: chrono::DateTime<chrono::Utc>,
+    /// `ed25519:<hex>` tag over `seal_signing_message`
+    pub signature: String,
+}
+
+impl SealAttestation {
+    pub fn signing_message(&self) -> Vec<u8> {
+        seal_signing_message(
+            &self.chain_id,
+            &self.merkle_root,
+            self.record_count,
+            self.head_event_id.as_deref(),
+            self.sealed_at,
+        )
+    }
+}
+
+/// Checks an attestation's signature against the signer's Ed25519 public
+/// key. This vouches for the attested root and count; comparing them with
+/// an archived copy of the chain, for instance through
+/// `verify_sealed_bundle`, is up to the caller.
+pub fn verify_seal_attestation(attestation: &SealAttestation, public_key: &[u8]) -> Result<bool, String> {
+    verify_ed25519_tag(public_key, &attestation.signing_message(), &attestation.signature)
+}
+
+/// The last record `DigitalLedger::verify_since_checkpoint` verified the
+/// chain up to. Records up to and including it are trusted on the next run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationCursor {
+    pub chain_id: String,
+    pub event_id: String,
+    /// What the next record must link to: the record's `link_target`
+    pub link_hash: String,
+    pub sequence: Option<u64>,
+    /// The record's timestamp, where the next run starts reading
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub verified_at: chrono::DateTime<chrono::Utc>,
+    /// `<scheme>:<hex>` tag over `signing_message` with the ledger's
+    /// integrity strategy. `verified_at` is informational and not covered.
+    pub signature: String,
+}
+
+impl VerificationCursor {
+    pub fn signing_message(&self) -> Vec<u8> {
+        cursor_signing_message(
+            &self.chain_id,
+            &self.event_id,
+            &self.link_hash,
+            self.sequence,
+            self.timestamp,
+        )
+    }
+}
+
+/// Where the verification cursor is kept between runs
+pub trait VerificationCursorStore: Send + Sync {
+    fn load(&self) -> Result<Option<VerificationCursor>, StorageError>;
+    /// Replaces the stored cursor; `None` clears it
+    fn save(&self, cursor: Option<&VerificationCursor>) -> Result<(), StorageError>;
+}
+
+/// Cursor held in process memory, so every restart verifies in full
+#[derive(Default)]
+pub struct InMemoryCursorStore {
+    cursor: Mutex<Option<VerificationCursor>>,
+}
+
+impl VerificationCursorStore for InMemoryCursorStore {
+    fn load(&self) -> Result<Option<VerificationCursor>, StorageError> {
+        Ok(self.cursor.lock().unwrap().clone())
+    }
+
+    fn save(&self, cursor: Option<&VerificationCursor>) -> Result<(), StorageError> {
+        *self.cursor.lock().unwrap() = cursor.cloned();
+        Ok(())
+    }
+}
+
+/// Cursor kept as JSON in a file, replaced through a temporary file so a
+/// crash mid-save leaves the previous cursor intact
+pub struct FileCursorStore {
+    path: PathBuf,
+}
+
+impl FileCursorStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl VerificationCursorStore for FileCursorStore {
+    fn load(&self) -> Result<Option<VerificationCursor>, StorageError> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, cursor: Option<&VerificationCursor>) -> Result<(), StorageError> {
+        let Some(cursor) = cursor else {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        };
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, serde_json::to_vec(cursor)?)?;
+        std::fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
 use validator::Validate;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "event_type")]
 pub enum LedgerEvent {
     #[serde(rename = "financial_transaction")]
@@ -19,15 +22,200 @@ pub enum LedgerEvent {
     
     #[serde(rename = "audit_log")]
     AuditLog(AuditLog),
+    
+    #[serde(rename = "transaction_reversal")]
+    TransactionReversal(TransactionReversal),
+    
+    #[serde(rename = "journal_entry")]
+    JournalEntry(JournalEntry),
+}
+
+/// A single failed validation constraint on an event field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Dotted path to the field, e.g. `amount.currency_code`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Flattens `validator` errors, including nested structs, into field errors
+    pub fn from_validation_errors(errors: &validator::ValidationErrors) -> Vec<FieldError> {
+        let mut field_errors = Vec::new();
+        collect_field_errors("", errors, &mut field_errors);
+        field_errors
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// A string naming none of an enum's variants
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Unknown {kind} {value:?}")]
+pub struct UnknownVariant {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+impl UnknownVariant {
+    fn new(kind: &'static str, value: &str) -> Self {
+        Self {
+            kind,
+            value: value.to_string(),
+        }
+    }
+}
+
+fn collect_field_errors(prefix: &str, errors: &validator::ValidationErrors, out: &mut Vec<FieldError>) {
+    use validator::ValidationErrorsKind;
+
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+        match kind {
+            ValidationErrorsKind::Field(errs) => {
+                for err in errs {
+                    let message = err
+                        .message
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| format!("failed {} check", err.code));
+                    out.push(FieldError::new(&path, message));
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(&path, nested, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(&format!("{}[{}]", path, index), nested, out);
+                }
+            }
+        }
+    }
 }
 
 impl LedgerEvent {
-    pub fn validate(&self) -> Result<(), String> {
+    /// Structural validation, with money precision checked against the ISO
+    /// 4217 minor units
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        self.validate_with_currencies(&CurrencyRegistry::default())
+    }
+    
+    /// Structural validation, with money precision checked against `currencies`
+    pub fn validate_with_currencies(&self, currencies: &CurrencyRegistry) -> Result<(), Vec<FieldError>> {
+        self.validate_with(currencies, &EventLimits::default())
+    }
+    
+    /// Structural validation, with money precision checked against
+    /// `currencies` and metadata and tags held to `limits`
+    pub fn validate_with(&self, currencies: &CurrencyRegistry, limits: &EventLimits) -> Result<(), Vec<FieldError>> {
+        self.validate_fields(currencies.rounding())?;
+        let mut errors: Vec<FieldError> = self
+            .money_fields()
+            .into_iter()
+            .filter_map(|(field, money)| money.check_precision(&field, currencies).err())
+            .collect();
+        errors.extend(self.check_limits(limits));
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    
+    fn check_limits(&self, limits: &EventLimits) -> Vec<FieldError> {
+        let (metadata, tags): (&serde_json::Value, &[String]) = match self {
+            LedgerEvent::FinancialTransaction(tx) => (&tx.metadata, &tx.tags),
+            LedgerEvent::AccountCreation(acct) => (&acct.metadata, &[]),
+            _ => return Vec::new(),
+        };
+        let mut errors = Vec::new();
+        
+        // Serializing a Value only fails for non-string map keys, which it can't hold
+        let metadata_bytes = serde_json::to_vec(metadata).map_or(0, |bytes| bytes.len());
+        if metadata_bytes > limits.max_metadata_bytes {
+            errors.push(FieldError::new(
+                "metadata",
+                format!(
+                    "metadata is {} bytes, the limit is {}",
+                    metadata_bytes, limits.max_metadata_bytes
+                ),
+            ));
+        }
+        if tags.len() > limits.max_tags {
+            errors.push(FieldError::new(
+                "tags",
+                format!("{} tags given, the limit is {}", tags.len(), limits.max_tags),
+            ));
+        }
+        for (i, tag) in tags.iter().enumerate() {
+            if tag.len() > limits.max_tag_bytes {
+                errors.push(FieldError::new(
+                    &format!("tags[{}]", i),
+                    format!("tag is {} bytes, the limit is {}", tag.len(), limits.max_tag_bytes),
+                ));
+            }
+        }
+        errors
+    }
+    
+    /// Every `Money` the event carries, with its field name
+    fn money_fields(&self) -> Vec<(String, &Money)> {
         match self {
-            LedgerEvent::FinancialTransaction(tx) => tx.validate()
-                .map_err(|e| format!("Financial transaction validation failed: {:?}", e)),
+            LedgerEvent::FinancialTransaction(tx) => {
+                let mut fields = vec![("amount".to_string(), &tx.amount)];
+                if let Some(settlement) = &tx.settlement_amount {
+                    fields.push(("settlement_amount".to_string(), settlement));
+                }
+                fields
+            }
+            LedgerEvent::AccountCreation(acct) => vec![("initial_balance".to_string(), &acct.initial_balance)],
+            LedgerEvent::BalanceAdjustment(adj) => vec![("amount".to_string(), &adj.amount)],
+            LedgerEvent::TransactionReversal(rev) => vec![("reversed_amount".to_string(), &rev.reversed_amount)],
+            LedgerEvent::JournalEntry(entry) => entry
+                .lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| (format!("lines[{}].amount", i), &line.amount))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+    
+    fn validate_fields(&self, rounding: RoundingMode) -> Result<(), Vec<FieldError>> {
+        match self {
+            LedgerEvent::FinancialTransaction(tx) => {
+                tx.validate()
+                    .map_err(|e| FieldError::from_validation_errors(&e))?;
+                tx.validate_settlement_with(rounding).map_err(|e| vec![e])
+            }
             LedgerEvent::AccountCreation(acct) => acct.validate()
-                .map_err(|e| format!("Account creation validation failed: {:?}", e)),
+                .map_err(|e| FieldError::from_validation_errors(&e)),
+            LedgerEvent::TransactionReversal(rev) => {
+                rev.validate()
+                    .map_err(|e| FieldError::from_validation_errors(&e))?;
+                if rev.reversed_amount.amount <= rust_decimal::Decimal::ZERO {
+                    return Err(vec![FieldError::new(
+                        "reversed_amount.amount",
+                        "reversed amount must be positive",
+                    )]);
+                }
+                Ok(())
+            }
+            LedgerEvent::JournalEntry(entry) => entry.validate_lines(),
             _ => Ok(()),
         }
     }
@@ -39,11 +227,39 @@ impl LedgerEvent {
             LedgerEvent::AccountCreation(acct) => acct.account_id.clone(),
             LedgerEvent::BalanceAdjustment(adj) => adj.adjustment_id.clone(),
             LedgerEvent::AuditLog(log) => log.log_id.clone(),
+            LedgerEvent::TransactionReversal(rev) => rev.reversal_id.clone(),
+            LedgerEvent::JournalEntry(entry) => entry.entry_id.clone(),
+        }
+    }
+
+    pub fn get_timestamp(&self) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            LedgerEvent::FinancialTransaction(tx) => tx.timestamp,
+            LedgerEvent::ComplianceAlert(alert) => alert.timestamp,
+            LedgerEvent::AccountCreation(acct) => acct.created_at,
+            LedgerEvent::BalanceAdjustment(adj) => adj.timestamp,
+            LedgerEvent::AuditLog(log) => log.timestamp,
+            LedgerEvent::TransactionReversal(rev) => rev.timestamp,
+            LedgerEvent::JournalEntry(entry) => entry.timestamp,
+        }
+    }
+
+    /// The serde tag value for this variant
+    pub fn event_type_name(&self) -> &'static str {
+        match self {
+            LedgerEvent::FinancialTransaction(_) => "financial_transaction",
+            LedgerEvent::ComplianceAlert(_) => "compliance_alert",
+            LedgerEvent::AccountCreation(_) => "account_creation",
+            LedgerEvent::BalanceAdjustment(_) => "balance_adjustment",
+            LedgerEvent::AuditLog(_) => "audit_log",
+            LedgerEvent::TransactionReversal(_) => "transaction_reversal",
+            LedgerEvent::JournalEntry(_) => "journal_entry",
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FinancialTransaction {
     #[validate(length(min = 1))]
     pub transaction_id: String,
@@ -64,9 +280,257 @@ pub struct FinancialTransaction {
     
     #[serde(default)]
     pub tags: Vec<String>,
+    
+    /// What `to_account` receives when it settles in another currency.
+    /// Absent for single-currency transfers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settlement_amount: Option<Money>,
+    
+    /// Units of settlement currency per unit of `amount`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange_rate: Option<rust_decimal::Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MetadataError {
+    #[error("Metadata key {key} has the wrong type: {message}")]
+    WrongType { key: String, message: String },
+}
+
+/// Typed access to an event's free-form JSON metadata.
+///
+/// A missing key (or an explicit `null`) is `Ok(None)`; a present value of
+/// the wrong shape is a `MetadataError`, so callers can tell the two apart.
+pub trait HasMetadata {
+    fn metadata_value(&self) -> &serde_json::Value;
+    
+    fn metadata_get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Result<Option<T>, MetadataError> {
+        match self.metadata_value().get(key) {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(value) => serde_json::from_value(value.clone())
+                .map(Some)
+                .map_err(|e| MetadataError::WrongType {
+                    key: key.to_string(),
+                    message: e.to_string(),
+                }),
+        }
+    }
+    
+    fn metadata_str(&self, key: &str) -> Result<Option<&str>, MetadataError> {
+        match self.metadata_value().get(key) {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(s)) => Ok(Some(s)),
+            Some(other) => Err(MetadataError::WrongType {
+                key: key.to_string(),
+                message: format!("expected a string, found {}", other),
+            }),
+        }
+    }
+    
+    /// A boolean flag, `false` when absent
+    fn metadata_flag(&self, key: &str) -> Result<bool, MetadataError> {
+        Ok(self.metadata_get::<bool>(key)?.unwrap_or(false))
+    }
 }
 
+impl HasMetadata for FinancialTransaction {
+    fn metadata_value(&self) -> &serde_json::Value {
+        &self.metadata
+    }
+}
+
+impl HasMetadata for AccountCreation {
+    fn metadata_value(&self) -> &serde_json::Value {
+        &self.metadata
+    }
+}
+
+impl FinancialTransaction {
+    pub fn builder() -> FinancialTransactionBuilder {
+        FinancialTransactionBuilder::default()
+    }
+    
+    /// The amount credited to `to_account`: the settlement amount for FX
+    /// transfers, otherwise `amount`
+    pub fn credited_amount(&self) -> &Money {
+        self.settlement_amount.as_ref().unwrap_or(&self.amount)
+    }
+    
+    /// Checks the FX leg. A settlement in another currency needs a rate, and
+    /// `amount * exchange_rate`, rounded to the settlement currency's minor
+    /// units, must equal `settlement_amount`. A same-currency settlement must
+    /// equal `amount`. Rounds half to even.
+    pub fn validate_settlement(&self) -> Result<(), FieldError> {
+        self.validate_settlement_with(RoundingMode::default())
+    }
+    
+    /// `validate_settlement`, rounding the converted amount with `rounding`
+    pub fn validate_settlement_with(&self, rounding: RoundingMode) -> Result<(), FieldError> {
+        let settlement = match (&self.settlement_amount, self.exchange_rate) {
+            (None, None) => return Ok(()),
+            (None, Some(_)) => {
+                return Err(FieldError::new(
+                    "settlement_amount",
+                    "exchange_rate requires a settlement_amount",
+                ))
+            }
+            (Some(settlement), _) => settlement,
+        };
+        
+        if settlement.currency_code == self.amount.currency_code {
+            if settlement.amount != self.amount.amount {
+                return Err(FieldError::new(
+                    "settlement_amount",
+                    "same-currency settlement must equal amount",
+                ));
+            }
+            return Ok(());
+        }
+        
+        let Some(rate) = self.exchange_rate else {
+            return Err(FieldError::new(
+                "exchange_rate",
+                format!(
+                    "exchange_rate is required to settle {} in {}",
+                    self.amount.currency_code, settlement.currency_code
+                ),
+            ));
+        };
+        if rate <= rust_decimal::Decimal::ZERO {
+            return Err(FieldError::new("exchange_rate", "exchange_rate must be positive"));
+        }
+        
+        let dp = currency_minor_units(&settlement.currency_code) as u32;
+        let expected = match self.amount.convert(rate, &settlement.currency_code, rounding) {
+            Ok(converted) => converted.amount,
+            Err(e) => return Err(FieldError::new("settlement_amount", e.to_string())),
+        };
+        if expected != settlement.amount.round_dp_with_strategy(dp, rounding.strategy()) {
+            return Err(FieldError::new(
+                "settlement_amount",
+                format!(
+                    "{} {} at rate {} is {} {}, not {}",
+                    self.amount.amount,
+                    self.amount.currency_code,
+                    rate,
+                    expected,
+                    settlement.currency_code,
+                    settlement.amount
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FinancialTransactionBuilder {
+    transaction_id: Option<String>,
+    from_account: String,
+    to_account: String,
+    amount: Option<Money>,
+    currency: Option<String>,
+    description: String,
+    metadata: Option<serde_json::Value>,
+    timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    tags: Vec<String>,
+    settlement_amount: Option<Money>,
+    exchange_rate: Option<rust_decimal::Decimal>,
+}
+
+impl FinancialTransactionBuilder {
+    pub fn transaction_id(mut self, transaction_id: impl Into<String>) -> Self {
+        self.transaction_id = Some(transaction_id.into());
+        self
+    }
+    
+    pub fn from_account(mut self, account: impl Into<String>) -> Self {
+        self.from_account = account.into();
+        self
+    }
+    
+    pub fn to_account(mut self, account: impl Into<String>) -> Self {
+        self.to_account = account.into();
+        self
+    }
+    
+    pub fn amount(mut self, amount: Money) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+    
+    /// Defaults to the currency code of `amount` when not set
+    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = Some(currency.into());
+        self
+    }
+    
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+    
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+    
+    pub fn timestamp(mut self, timestamp: chrono::DateTime<chrono::Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+    
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+    
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+    
+    /// Settles `to_account` in another currency at `exchange_rate`
+    pub fn settlement(mut self, settlement_amount: Money, exchange_rate: rust_decimal::Decimal) -> Self {
+        self.settlement_amount = Some(settlement_amount);
+        self.exchange_rate = Some(exchange_rate);
+        self
+    }
+    
+    pub fn build(self) -> Result<FinancialTransaction, String> {
+        let amount = self.amount.ok_or_else(|| "Financial transaction amount is required".to_string())?;
+        
+        let tx = FinancialTransaction {
+            transaction_id: self.transaction_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            from_account: self.from_account,
+            to_account: self.to_account,
+            currency: self.currency.unwrap_or_else(|| amount.currency_code.clone()),
+            amount,
+            description: self.description,
+            metadata: self.metadata.unwrap_or_else(|| serde_json::json!({})),
+            timestamp: self.timestamp.unwrap_or_else(chrono::Utc::now),
+            tags: self.tags,
+            settlement_amount: self.settlement_amount,
+            exchange_rate: self.exchange_rate,
+        };
+        
+        tx.validate()
+            .map_err(|e| format!("Financial transaction validation failed: {:?}", e))?;
+        tx.validate_settlement()
+            .map_err(|e| format!("Financial transaction validation failed: {:?}", e))?;
+        Ok(tx)
+    }
+}
+
+/// When `precision` is omitted on input it is filled in from the currency's
+/// ISO 4217 minor units rather than defaulting to 0.
+///
+/// `amount` is read from a JSON string or number, including scientific
+/// notation, and always written as its `canonical_amount` string. Equal
+/// amounts therefore serialize, and hash, identically however they were
+/// written: 100, "100.00" and "1E2" in USD all become "100.00".
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(from = "MoneyInput", into = "MoneyOutput")]
 pub struct Money {
     #[validate(range(min = 0))]
     pub amount: rust_decimal::Decimal,
@@ -74,11 +538,417 @@ pub struct Money {
     #[validate(length(equal = 3))]
     pub currency_code: String,
     
-    #[serde(default)]
     pub precision: u8,
 }
 
+#[derive(Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Money"))]
+struct MoneyInput {
+    #[serde(deserialize_with = "deserialize_amount")]
+    #[cfg_attr(feature = "schema", schemars(schema_with = "amount_schema"))]
+    amount: rust_decimal::Decimal,
+    #[cfg_attr(feature = "schema", schemars(length(equal = 3)))]
+    currency_code: String,
+    #[serde(default)]
+    precision: Option<u8>,
+}
+
+/// Money is described by what it accepts, which is everything it writes
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Money {
+    fn schema_name() -> String {
+        "Money".to_string()
+    }
+    
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        MoneyInput::json_schema(gen)
+    }
+}
+
+/// What `deserialize_amount` accepts: a plain or scientific decimal string,
+/// or a JSON number
+#[cfg(feature = "schema")]
+fn amount_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    serde_json::from_value(serde_json::json!({
+        "anyOf": [
+            {"type": "string", "pattern": "^[-+]?([0-9]+(\\.[0-9]*)?|\\.[0-9]+)([eE][-+]?[0-9]+)?$"},
+            {"type": "number"},
+        ]
+    }))
+    .expect("amount schema is valid")
+}
+
+#[derive(Serialize)]
+struct MoneyOutput {
+    amount: String,
+    currency_code: String,
+    precision: u8,
+}
+
+impl From<Money> for MoneyOutput {
+    fn from(money: Money) -> Self {
+        MoneyOutput {
+            amount: money.canonical_amount(),
+            currency_code: money.currency_code,
+            precision: money.precision,
+        }
+    }
+}
+
+/// Reads an amount however `rust_decimal`'s serde features would have
+/// written it: a plain or scientific string, or a JSON number
+fn deserialize_amount<'de, D>(deserializer: D) -> Result<rust_decimal::Decimal, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct AmountVisitor;
+
+    impl serde::de::Visitor<'_> for AmountVisitor {
+        type Value = rust_decimal::Decimal;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a decimal amount as a string or number")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            value
+                .parse::<rust_decimal::Decimal>()
+                .or_else(|_| rust_decimal::Decimal::from_scientific(value))
+                .map_err(|_| E::custom(format!("invalid amount {:?}", value)))
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+            Ok(value.into())
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+            Ok(value.into())
+        }
+
+        fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<Self::Value, E> {
+            rust_decimal::Decimal::try_from(value).map_err(|_| E::custom(format!("invalid amount {}", value)))
+        }
+    }
+
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+impl From<MoneyInput> for Money {
+    fn from(input: MoneyInput) -> Self {
+        Money {
+            precision: input
+                .precision
+                .unwrap_or_else(|| currency_minor_units(&input.currency_code)),
+            amount: input.amount,
+            currency_code: input.currency_code,
+        }
+    }
+}
+
+/// JSON Schema (draft 2019-09) for `LedgerEvent`, for partners generating
+/// client types or checking payloads before submitting them.
+///
+/// It follows the wire format, `event_type` tags and snake_case names
+/// included, and carries the field-level constraints (lengths, ranges and
+/// item counts). Checks spanning several fields, such as a journal entry
+/// balancing or money precision matching its currency, are only made on
+/// append.
+#[cfg(feature = "schema")]
+pub fn event_schema() -> serde_json::Value {
+    let schema = schemars::gen::SchemaSettings::draft2019_09()
+        .into_generator()
+        .into_root_schema_for::<LedgerEvent>();
+    serde_json::to_value(schema).expect("JSON Schema serializes")
+}
+
+/// Size limits on the free-form parts of an event, so a single append can't
+/// bloat the chain or make hashing slow
+#[derive(Debug, Clone)]
+pub struct EventLimits {
+    /// Serialized JSON size of an event's `metadata`
+    pub max_metadata_bytes: usize,
+    pub max_tags: usize,
+    pub max_tag_bytes: usize,
+}
+
+impl Default for EventLimits {
+    fn default() -> Self {
+        Self {
+            max_metadata_bytes: 64 * 1024,
+            max_tags: 64,
+            max_tag_bytes: 128,
+        }
+    }
+}
+
+/// Minor units per currency code: ISO 4217 by default, with overrides for
+/// currencies the table doesn't know or that a deployment treats differently
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyRegistry {
+    overrides: HashMap<String, u8>,
+    rounding: RoundingMode,
+}
+
+impl CurrencyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn with_currency(mut self, currency_code: impl Into<String>, minor_units: u8) -> Self {
+        self.overrides.insert(currency_code.into(), minor_units);
+        self
+    }
+    
+    /// Rounding applied wherever the ledger rounds amounts, such as when
+    /// checking an FX settlement. Defaults to half-even.
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+    
+    pub fn rounding(&self) -> RoundingMode {
+        self.rounding
+    }
+    
+    pub fn minor_units(&self, currency_code: &str) -> u8 {
+        self.overrides
+            .get(currency_code)
+            .copied()
+            .unwrap_or_else(|| currency_minor_units(currency_code))
+    }
+}
+
+/// How an amount is rounded to a currency's precision. Jurisdictions
+/// differ, so it is configurable ledger-wide on `CurrencyRegistry` and per
+/// call on `Money`'s rounding operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// Ties go to the even digit (banker's rounding): 2.675 -> 2.68, 2.665 -> 2.66
+    #[default]
+    HalfEven,
+    /// Ties go away from zero: 2.675 -> 2.68, 2.665 -> 2.67
+    HalfUp,
+    /// Ties go toward zero: 2.675 -> 2.67, 2.665 -> 2.66
+    HalfDown,
+    /// Away from zero: 2.671 -> 2.68
+    Up,
+    /// Toward zero, truncating: 2.679 -> 2.67
+    Down,
+    /// Toward positive infinity
+    Ceiling,
+    /// Toward negative infinity
+    Floor,
+}
+
+impl RoundingMode {
+    pub fn strategy(self) -> rust_decimal::RoundingStrategy {
+        use rust_decimal::RoundingStrategy;
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfDown => RoundingStrategy::MidpointTowardZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Ceiling => RoundingStrategy::ToPositiveInfinity,
+            RoundingMode::Floor => RoundingStrategy::ToNegativeInfinity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MoneyError {
+    #[error("Expected an amount and a currency code, got {0:?}")]
+    Malformed(String),
+    #[error("Invalid currency code: {0}")]
+    InvalidCurrency(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("{currency} allows {allowed} decimal places, got {given}")]
+    TooManyDecimals { currency: String, allowed: u8, given: u32 },
+    #[error("{currency} amount is outside the representable range")]
+    Overflow { currency: String },
+}
+
+impl MoneyError {
+    pub fn overflow(currency: &str) -> Self {
+        MoneyError::Overflow {
+            currency: currency.to_string(),
+        }
+    }
+}
+
+/// Number of minor-unit digits for an ISO 4217 currency. Codes not listed
+/// use two, which covers the large majority of currencies.
+pub fn currency_minor_units(currency_code: &str) -> u8 {
+    match currency_code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+impl Money {
+    /// Money in `currency_code` with the currency's ISO 4217 precision
+    pub fn with_currency_defaults(amount: rust_decimal::Decimal, currency_code: impl Into<String>) -> Money {
+        let currency_code = currency_code.into();
+        Money {
+            precision: currency_minor_units(&currency_code),
+            amount,
+            currency_code,
+        }
+    }
+    
+    /// `amount` as written when serialized and hashed: trailing zeros
+    /// dropped, then padded to `precision` places. Digits beyond
+    /// `precision` are kept rather than rounded, so amounts that differ
+    /// never share a canonical form.
+    pub fn canonical_amount(&self) -> String {
+        let mut amount = self.amount.normalize();
+        if amount.scale() < self.precision as u32 {
+            amount.rescale(self.precision as u32);
+        }
+        amount.to_string()
+    }
+    
+    /// The amount rounded to `precision` places with `rounding`
+    pub fn round_to_precision(&self, rounding: RoundingMode) -> Money {
+        Money {
+            amount: self.amount.round_dp_with_strategy(self.precision as u32, rounding.strategy()),
+            currency_code: self.currency_code.clone(),
+            precision: self.precision,
+        }
+    }
+    
+    /// `amount + delta`, or `MoneyError::Overflow` when the sum is outside
+    /// `Decimal`'s range
+    pub fn checked_add(&self, delta: rust_decimal::Decimal) -> Result<Money, MoneyError> {
+        let amount = self
+            .amount
+            .checked_add(delta)
+            .ok_or_else(|| MoneyError::overflow(&self.currency_code))?;
+        Ok(Money {
+            amount,
+            ..self.clone()
+        })
+    }
+    
+    /// `amount * factor`, or `MoneyError::Overflow` when the product is
+    /// outside `Decimal`'s range
+    pub fn checked_mul(&self, factor: rust_decimal::Decimal) -> Result<Money, MoneyError> {
+        let amount = self
+            .amount
+            .checked_mul(factor)
+            .ok_or_else(|| MoneyError::overflow(&self.currency_code))?;
+        Ok(Money {
+            amount,
+            ..self.clone()
+        })
+    }
+    
+    /// `amount * rate` in `currency_code`, rounded with `rounding` to that
+    /// currency's ISO 4217 precision
+    pub fn convert(
+        &self,
+        rate: rust_decimal::Decimal,
+        currency_code: &str,
+        rounding: RoundingMode,
+    ) -> Result<Money, MoneyError> {
+        let amount = self
+            .amount
+            .checked_mul(rate)
+            .ok_or_else(|| MoneyError::overflow(currency_code))?;
+        Ok(Money::with_currency_defaults(amount, currency_code).round_to_precision(rounding))
+    }
+    
+    /// Rejects a precision that disagrees with the currency's canonical
+    /// minor units, which would otherwise truncate or pad amounts
+    pub fn check_precision(&self, field: &str, currencies: &CurrencyRegistry) -> Result<(), FieldError> {
+        let expected = currencies.minor_units(&self.currency_code);
+        if self.precision != expected {
+            return Err(FieldError::new(
+                &format!("{}.precision", field),
+                format!(
+                    "{} uses {} decimal places, got precision {}",
+                    self.currency_code, expected, self.precision
+                ),
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Parses "1234.50 USD" or "USD 1234.50". Thousands separators are
+    /// accepted, the code is uppercased, and the precision is taken from the
+    /// currency's minor units.
+    pub fn parse(s: &str) -> Result<Money, MoneyError> {
+        let mut parts = s.split_whitespace();
+        let (Some(first), Some(second), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(MoneyError::Malformed(s.to_string()));
+        };
+        let is_code = |part: &str| part.len() == 3 && part.chars().all(|c| c.is_ascii_alphabetic());
+        let (amount, code) = match (is_code(first), is_code(second)) {
+            (false, true) => (first, second),
+            (true, false) => (second, first),
+            _ => return Err(MoneyError::InvalidCurrency(s.to_string())),
+        };
+
+        let currency_code = code.to_ascii_uppercase();
+        let amount: rust_decimal::Decimal = amount
+            .replace(',', "")
+            .parse()
+            .map_err(|_| MoneyError::InvalidAmount(amount.to_string()))?;
+
+        let allowed = currency_minor_units(&currency_code);
+        if amount.scale() > allowed as u32 {
+            return Err(MoneyError::TooManyDecimals {
+                currency: currency_code,
+                allowed,
+                given: amount.scale(),
+            });
+        }
+
+        Ok(Money {
+            amount,
+            currency_code,
+            precision: allowed,
+        })
+    }
+}
+
+impl std::fmt::Display for Money {
+    /// Formats as "1,234.50 USD": rounded to `precision` places, with comma
+    /// grouping regardless of locale
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rounded = self.amount.round_dp(self.precision as u32);
+        let digits = format!("{:.*}", self.precision as usize, rounded.abs());
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+
+        let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+        for (i, c) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+
+        if rounded.is_sign_negative() && !rounded.is_zero() {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", grouped)?;
+        if let Some(fraction) = fraction {
+            write!(f, ".{}", fraction)?;
+        }
+        write!(f, " {}", self.currency_code)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ComplianceAlert {
     pub alert_id: String,
     pub rule_id: String,
@@ -90,6 +960,8 @@ pub struct ComplianceAlert {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum AlertSeverity {
     Low,
     Medium,
@@ -97,7 +969,44 @@ pub enum AlertSeverity {
     Critical,
 }
 
+impl AlertSeverity {
+    pub const ALL: [AlertSeverity; 4] = [
+        AlertSeverity::Low,
+        AlertSeverity::Medium,
+        AlertSeverity::High,
+        AlertSeverity::Critical,
+    ];
+    
+    /// Wire name, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Low => "low",
+            AlertSeverity::Medium => "medium",
+            AlertSeverity::High => "high",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+}
+
+impl std::fmt::Display for AlertSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AlertSeverity {
+    type Err = UnknownVariant;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|v| v.as_str() == s)
+            .ok_or_else(|| UnknownVariant::new("AlertSeverity", s))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AccountCreation {
     #[validate(length(min = 1))]
     pub account_id: String,
@@ -113,7 +1022,81 @@ pub struct AccountCreation {
     pub metadata: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl AccountCreation {
+    pub fn builder() -> AccountCreationBuilder {
+        AccountCreationBuilder::default()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AccountCreationBuilder {
+    account_id: Option<String>,
+    account_type: Option<AccountType>,
+    owner_id: String,
+    initial_balance: Option<Money>,
+    compliance_level: Option<ComplianceLevel>,
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+    metadata: Option<serde_json::Value>,
+}
+
+impl AccountCreationBuilder {
+    pub fn account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+    
+    pub fn account_type(mut self, account_type: AccountType) -> Self {
+        self.account_type = Some(account_type);
+        self
+    }
+    
+    pub fn owner_id(mut self, owner_id: impl Into<String>) -> Self {
+        self.owner_id = owner_id.into();
+        self
+    }
+    
+    pub fn initial_balance(mut self, initial_balance: Money) -> Self {
+        self.initial_balance = Some(initial_balance);
+        self
+    }
+    
+    pub fn compliance_level(mut self, compliance_level: ComplianceLevel) -> Self {
+        self.compliance_level = Some(compliance_level);
+        self
+    }
+    
+    pub fn created_at(mut self, created_at: chrono::DateTime<chrono::Utc>) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+    
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+    
+    pub fn build(self) -> Result<AccountCreation, String> {
+        let acct = AccountCreation {
+            account_id: self.account_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            account_type: self.account_type
+                .ok_or_else(|| "Account type is required".to_string())?,
+            owner_id: self.owner_id,
+            initial_balance: self.initial_balance
+                .ok_or_else(|| "Initial balance is required".to_string())?,
+            compliance_level: self.compliance_level.unwrap_or(ComplianceLevel::LowRisk),
+            created_at: self.created_at.unwrap_or_else(chrono::Utc::now),
+            metadata: self.metadata.unwrap_or_else(|| serde_json::json!({})),
+        };
+        
+        acct.validate()
+            .map_err(|e| format!("Account creation validation failed: {:?}", e))?;
+        Ok(acct)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum AccountType {
     Asset,
     Liability,
@@ -122,7 +1105,83 @@ pub enum AccountType {
     Expense,
 }
 
+impl AccountType {
+    pub const ALL: [AccountType; 5] = [
+        AccountType::Asset,
+        AccountType::Liability,
+        AccountType::Equity,
+        AccountType::Revenue,
+        AccountType::Expense,
+    ];
+    
+    /// Wire name, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountType::Asset => "asset",
+            AccountType::Liability => "liability",
+            AccountType::Equity => "equity",
+            AccountType::Revenue => "revenue",
+            AccountType::Expense => "expense",
+        }
+    }
+    
+    /// The side that raises this type's balance: debit for assets and
+    /// expenses, credit for liabilities, equity and revenue
+    pub fn normal_balance(&self) -> EntryDirection {
+        match self {
+            AccountType::Asset | AccountType::Expense => EntryDirection::Debit,
+            AccountType::Liability | AccountType::Equity | AccountType::Revenue => EntryDirection::Credit,
+        }
+    }
+    
+    /// `amount` posted on `direction`, signed by how it moves this type's
+    /// balance
+    pub fn signed(&self, direction: EntryDirection, amount: rust_decimal::Decimal) -> rust_decimal::Decimal {
+        if direction == self.normal_balance() {
+            amount
+        } else {
+            -amount
+        }
+    }
+    
+    /// The side a posting must be on to move this type's balance by `delta`
+    pub fn direction_of(&self, delta: rust_decimal::Decimal) -> EntryDirection {
+        if delta.is_sign_negative() {
+            self.normal_balance().opposite()
+        } else {
+            self.normal_balance()
+        }
+    }
+    
+    /// The balance after posting `line` to an account of this type holding
+    /// `current`, or `MoneyError::Overflow` outside `Decimal`'s range
+    pub fn apply(&self, current: rust_decimal::Decimal, line: &JournalLine) -> Result<rust_decimal::Decimal, MoneyError> {
+        current
+            .checked_add(self.signed(line.direction, line.amount.amount))
+            .ok_or_else(|| MoneyError::overflow(&line.amount.currency_code))
+    }
+}
+
+impl std::fmt::Display for AccountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AccountType {
+    type Err = UnknownVariant;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|v| v.as_str() == s)
+            .ok_or_else(|| UnknownVariant::new("AccountType", s))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum ComplianceLevel {
     LowRisk,
     MediumRisk,
@@ -130,7 +1189,44 @@ pub enum ComplianceLevel {
     Sanctioned,
 }
 
+impl ComplianceLevel {
+    pub const ALL: [ComplianceLevel; 4] = [
+        ComplianceLevel::LowRisk,
+        ComplianceLevel::MediumRisk,
+        ComplianceLevel::HighRisk,
+        ComplianceLevel::Sanctioned,
+    ];
+    
+    /// Wire name, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplianceLevel::LowRisk => "low_risk",
+            ComplianceLevel::MediumRisk => "medium_risk",
+            ComplianceLevel::HighRisk => "high_risk",
+            ComplianceLevel::Sanctioned => "sanctioned",
+        }
+    }
+}
+
+impl std::fmt::Display for ComplianceLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ComplianceLevel {
+    type Err = UnknownVariant;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|v| v.as_str() == s)
+            .ok_or_else(|| UnknownVariant::new("ComplianceLevel", s))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BalanceAdjustment {
     pub adjustment_id: String,
     pub account_id: String,
@@ -141,7 +1237,9 @@ pub struct BalanceAdjustment {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum AdjustmentReason {
     Correction,
     WriteOff,
@@ -149,7 +1247,44 @@ pub enum AdjustmentReason {
     Regulatory,
 }
 
+impl AdjustmentReason {
+    pub const ALL: [AdjustmentReason; 4] = [
+        AdjustmentReason::Correction,
+        AdjustmentReason::WriteOff,
+        AdjustmentReason::Revaluation,
+        AdjustmentReason::Regulatory,
+    ];
+    
+    /// Wire name, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdjustmentReason::Correction => "correction",
+            AdjustmentReason::WriteOff => "write_off",
+            AdjustmentReason::Revaluation => "revaluation",
+            AdjustmentReason::Regulatory => "regulatory",
+        }
+    }
+}
+
+impl std::fmt::Display for AdjustmentReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AdjustmentReason {
+    type Err = UnknownVariant;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|v| v.as_str() == s)
+            .ok_or_else(|| UnknownVariant::new("AdjustmentReason", s))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AuditLog {
     pub log_id: String,
     pub action: String,
@@ -160,3 +1295,135 @@ pub struct AuditLog {
     pub user_agent: Option<String>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TransactionReversal {
+    #[validate(length(min = 1))]
+    pub reversal_id: String,
+    
+    #[validate(length(min = 1))]
+    pub original_transaction_id: String,
+    
+    pub reason: String,
+    
+    #[validate]
+    pub reversed_amount: Money,
+    
+    pub authorized_by: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Which side of an account a journal line posts to. Whether that raises or
+/// lowers the balance depends on the account's type; see
+/// `AccountType::normal_balance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum EntryDirection {
+    Debit,
+    Credit,
+}
+
+impl EntryDirection {
+    pub fn opposite(self) -> EntryDirection {
+        match self {
+            EntryDirection::Debit => EntryDirection::Credit,
+            EntryDirection::Credit => EntryDirection::Debit,
+        }
+    }
+}
+
+/// Several debits and credits posted together, for entries a single
+/// transfer can't express. Per currency, the debits must sum to the
+/// credits.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct JournalEntry {
+    #[validate(length(min = 1))]
+    pub entry_id: String,
+    
+    pub description: String,
+    
+    #[validate(length(min = 2))]
+    pub lines: Vec<JournalLine>,
+    
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct JournalLine {
+    #[validate(length(min = 1))]
+    pub account: String,
+    
+    pub direction: EntryDirection,
+    
+    #[validate]
+    pub amount: Money,
+}
+
+impl JournalEntry {
+    /// Total debits and credits per currency, ordered by currency code, or
+    /// `MoneyError::Overflow` if a total is outside `Decimal`'s range
+    pub fn totals(
+        &self,
+    ) -> Result<std::collections::BTreeMap<&str, (rust_decimal::Decimal, rust_decimal::Decimal)>, MoneyError> {
+        let mut totals = std::collections::BTreeMap::new();
+        for line in &self.lines {
+            let (debits, credits) = totals
+                .entry(line.amount.currency_code.as_str())
+                .or_insert((rust_decimal::Decimal::ZERO, rust_decimal::Decimal::ZERO));
+            let total = match line.direction {
+                EntryDirection::Debit => debits,
+                EntryDirection::Credit => credits,
+            };
+            *total = total
+                .checked_add(line.amount.amount)
+                .ok_or_else(|| MoneyError::overflow(&line.amount.currency_code))?;
+        }
+        Ok(totals)
+    }
+    
+    fn validate_lines(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = match self.validate() {
+            Ok(()) => Vec::new(),
+            Err(e) => FieldError::from_validation_errors(&e),
+        };
+        for (i, line) in self.lines.iter().enumerate() {
+            let field = format!("lines[{}]", i);
+            if let Err(e) = line.validate() {
+                collect_field_errors(&field, &e, &mut errors);
+            }
+            if line.amount.amount.is_zero() {
+                errors.push(FieldError::new(&format!("{}.amount.amount", field), "line amount must be positive"));
+            }
+        }
+        let totals = match self.totals() {
+            Ok(totals) => totals,
+            Err(e) => {
+                errors.push(FieldError::new("lines", e.to_string()));
+                return Err(errors);
+            }
+        };
+        for (currency, (debits, credits)) in totals {
+            if debits != credits {
+                errors.push(FieldError::new(
+                    "lines",
+                    format!(
+                        "{} debits of {} do not equal credits of {} (off by {})",
+                        currency,
+                        debits,
+                        credits,
+                        (debits - credits).abs()
+                    ),
+                ));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
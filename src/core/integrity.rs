@@ -0,0 +1,258 @@
+use async_trait::async_trait;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const HMAC_SHA256_SCHEME: &str = "hmac-sha256";
+pub const ED25519_SCHEME: &str = "ed25519";
+
+/// Shared secret for HMAC integrity tags, wiped from memory on drop
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct HmacKey(Vec<u8>);
+
+impl HmacKey {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self(key.into())
+    }
+}
+
+/// How records are tagged for tamper evidence.
+///
+/// The tag is stored in `LedgerRecord.signature` as `<scheme>:<hex>`, so the
+/// record itself says which strategy produced it and verification can pick
+/// the matching path even if the ledger's strategy later changes.
+#[derive(Clone, Default)]
+pub enum Integrity {
+    #[default]
+    None,
+    Hmac(HmacKey),
+    Ed25519(Arc<Ed25519KeyPair>),
+}
+
+impl Integrity {
+    pub fn sign(&self, message: &[u8]) -> Option<String> {
+        match self {
+            Integrity::None => None,
+            Integrity::Hmac(key) => {
+                let key = hmac::Key::new(hmac::HMAC_SHA256, &key.0);
+                let tag = hmac::sign(&key, message);
+                Some(format!("{}:{}", HMAC_SHA256_SCHEME, hex::encode(tag.as_ref())))
+            }
+            Integrity::Ed25519(keypair) => {
+                let signature = keypair.sign(message);
+                Some(format!("{}:{}", ED25519_SCHEME, hex::encode(signature.as_ref())))
+            }
+        }
+    }
+
+    /// Public key third parties can check Ed25519 tags with. HMAC tags can
+    /// only be checked by holders of the shared key.
+    pub fn public_key(&self) -> Option<Vec<u8>> {
+        match self {
+            Integrity::Ed25519(keypair) => Some(keypair.public_key().as_ref().to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Checks a `<scheme>:<hex>` tag. A tag whose scheme this strategy can't
+    /// verify is reported as an error rather than a mismatch.
+    pub fn verify(&self, message: &[u8], tag: &str) -> Result<bool, String> {
+        let (scheme, encoded) = tag
+            .split_once(':')
+            .ok_or_else(|| "signature has no scheme prefix".to_string())?;
+        let bytes = hex::decode(encoded).map_err(|e| format!("signature is not hex: {}", e))?;
+
+        match (self, scheme) {
+            (Integrity::Hmac(key), HMAC_SHA256_SCHEME) => {
+                let key = hmac::Key::new(hmac::HMAC_SHA256, &key.0);
+                Ok(hmac::verify(&key, message, &bytes).is_ok())
+            }
+            (Integrity::Ed25519(keypair), ED25519_SCHEME) => {
+                let public_key = UnparsedPublicKey::new(&ED25519, keypair.public_key().as_ref());
+                Ok(public_key.verify(message, &bytes).is_ok())
+            }
+            _ => Err(format!("no key configured for signature scheme {}", scheme)),
+        }
+    }
+}
+
+/// Checks an `ed25519:<hex>` tag against a bare public key, without a
+/// configured `Integrity`
+pub fn verify_ed25519_tag(public_key: &[u8], message: &[u8], tag: &str) -> Result<bool, String> {
+    let encoded = tag
+        .strip_prefix(ED25519_SCHEME)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .ok_or_else(|| format!("expected an {} signature", ED25519_SCHEME))?;
+    let bytes = hex::decode(encoded).map_err(|e| format!("signature is not hex: {}", e))?;
+    Ok(UnparsedPublicKey::new(&ED25519, public_key).verify(message, &bytes).is_ok())
+}
+
+/// Domain tag hashed with the chain id into its genesis seed
+const GENESIS_SEED_DOMAIN: &str = "ledger-core/genesis/v1";
+
+/// `previous_hash` of a chain's first record, derived from the chain id so
+/// no chain's history can be spliced in as another's start.
+///
+/// Chains started before seeds existed have no `previous_hash` on their
+/// first record. They still verify as long as that record predates
+/// sequence numbers too; every new chain starts from its seed.
+pub fn genesis_seed(chain_id: &str) -> String {
+    let digest = Sha256::digest(format!("{}\n{}", GENESIS_SEED_DOMAIN, chain_id).as_bytes());
+    hex::encode(digest)
+}
+
+/// Domain tag hashed with a record's id, sequence and nonce into its link
+const LINK_HASH_DOMAIN: &str = "ledger-core/link/v1";
+
+/// `previous_hash` the record after this one must carry. Binding the
+/// sequence and nonce here protects them without a signature, since
+/// changing either breaks the next record's link, while the event id
+/// itself stays a pure content hash.
+pub fn link_hash(event_id: &str, sequence: u64, nonce: &str) -> String {
+    let digest = Sha256::digest(format!("{}\n{}\n{}\n{}", LINK_HASH_DOMAIN, event_id, sequence, nonce).as_bytes());
+    hex::encode(digest)
+}
+
+/// Event id of a record restricted to `visibility`, binding the scopes
+/// into the id so they cannot be widened after the fact without breaking
+/// the chain. Unrestricted records keep the bare event hash as their id.
+pub fn visibility_bound_id(event_hash: String, visibility: &[String]) -> String {
+    if visibility.is_empty() {
+        return event_hash;
+    }
+    let mut scopes: Vec<&str> = visibility.iter().map(String::as_str).collect();
+    scopes.sort_unstable();
+    scopes.dedup();
+    let digest = Sha256::digest(format!("{}\nvisibility\n{}", event_hash, scopes.join("\n")).as_bytes());
+    hex::encode(digest)
+}
+
+/// Bytes covered by a sealed bundle's root signature
+pub fn root_signing_message(chain_id: &str, merkle_root: &str, record_count: usize) -> Vec<u8> {
+    format!("{}\n{}\n{}", chain_id, merkle_root, record_count).into_bytes()
+}
+
+/// Bytes covered by a seal attestation's signature. The leading tag keeps a
+/// bundle's root signature from passing as one.
+pub fn seal_signing_message(
+    chain_id: &str,
+    merkle_root: &str,
+    record_count: usize,
+    head_event_id: Option<&str>,
+    sealed_at: chrono::DateTime<chrono::Utc>,
+) -> Vec<u8> {
+    format!(
+        "seal\n{}\n{}\n{}\n{}\n{}",
+        chain_id,
+        merkle_root,
+        record_count,
+        head_event_id.unwrap_or_default(),
+        sealed_at.to_rfc3339()
+    )
+    .into_bytes()
+}
+
+/// Bytes covered by a verification cursor's integrity tag
+pub fn cursor_signing_message(
+    chain_id: &str,
+    event_id: &str,
+    link_hash: &str,
+    sequence: Option<u64>,
+    timestamp: chrono::DateTime<chrono::Utc>,
+) -> Vec<u8> {
+    format!(
+        "cursor\n{}\n{}\n{}\n{}\n{}",
+        chain_id,
+        event_id,
+        link_hash,
+        sequence.map(|s| s.to_string()).unwrap_or_default(),
+        timestamp.to_rfc3339()
+    )
+    .into_bytes()
+}
+
+/// Bytes covered by a record's integrity tag: its chain, id and link, then
+/// its sequence number and nonce. Records from before sequences and nonces
+/// existed have neither, and keep the shorter message they were signed with.
+pub fn signing_message(
+    chain_id: &str,
+    event_id: &str,
+    previous_hash: Option<&str>,
+    sequence: Option<u64>,
+    nonce: Option<&str>,
+) -> Vec<u8> {
+    let mut message = format!("{}\n{}\n{}", chain_id, event_id, previous_hash.unwrap_or_default());
+    if let (Some(sequence), Some(nonce)) = (sequence, nonce) {
+        message.push_str(&format!("\n{}\n{}", sequence, nonce));
+    }
+    message.into_bytes()
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SignatureFailure {
+    pub event_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SignatureVerification {
+    pub records_checked: usize,
+    pub unsigned: usize,
+    /// Records whose ledger integrity tag did not verify
+    pub failures: Vec<SignatureFailure>,
+    /// Records submitted with an actor signature
+    pub actor_signed: usize,
+    /// Records whose actor signature did not verify
+    pub actor_failures: Vec<SignatureFailure>,
+}
+
+impl SignatureVerification {
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty() && self.actor_failures.is_empty()
+    }
+}
+
+/// An actor's Ed25519 signature over the canonical bytes of the event they
+/// submitted, as encoded by the record's codec. Passed to `append_event`
+/// under `ACTOR_SIGNATURE_METADATA`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorSignature {
+    pub actor_id: String,
+    /// `ed25519:<hex>`
+    pub signature: String,
+}
+
+/// Finds the Ed25519 public key an actor signs events with
+#[async_trait]
+pub trait KeyResolver: Send + Sync {
+    /// `None` if the actor is unknown
+    async fn public_key(&self, actor_id: &str) -> anyhow::Result<Option<Vec<u8>>>;
+}
+
+/// Fixed map of actor id to public key
+#[derive(Debug, Clone, Default)]
+pub struct StaticKeyResolver {
+    keys: HashMap<String, Vec<u8>>,
+}
+
+impl StaticKeyResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_key(mut self, actor_id: impl Into<String>, public_key: impl Into<Vec<u8>>) -> Self {
+        self.keys.insert(actor_id.into(), public_key.into());
+        self
+    }
+}
+
+#[async_trait]
+impl KeyResolver for StaticKeyResolver {
+    async fn public_key(&self, actor_id: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.keys.get(actor_id).cloned())
+    }
+}
@@ -1,86 +1,2393 @@
-use crate::core::event::LedgerEvent;
-use crate::compliance::validator::ComplianceValidator;
-use crate::storage::append_only::AppendOnlyStorage;
-use crate::utils::crypto::generate_hash_chain;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use crate::core::authorization::{
+    AuthorizationDecision, AuthorizationPolicy, ADJUSTMENT_AUTHORIZATION_RULE_ID,
+};
+use crate::core::balance::{
+    self, balance_movements, fold_balances, BALANCE_EVENT_TYPES, UNTYPED_ACCOUNT_CONVENTION,
+};
+use crate::core::event::{
+    AlertSeverity, AuditLog, ComplianceAlert, CurrencyRegistry, EventLimits, FieldError,
+    HasMetadata, LedgerEvent, Money, TransactionReversal,
+};
+use crate::core::schema::{default_schema_version, migrate_record, CURRENT_SCHEMA_VERSION};
+use crate::core::bundle::SealedBundle;
+use crate::core::checkpoint::{
+    Checkpoint, InMemoryCursorStore, SealAttestation, VerificationCursor, VerificationCursorStore,
+    CHECKPOINT_ACTION,
+};
+use crate::core::reconciliation::{
+    BalanceChange, CreatedAccount, LedgerDiff, PostedAdjustment, ReconciliationLine, ReconciliationReport,
+};
+use crate::core::integrity::{
+    cursor_signing_message, genesis_seed, link_hash, root_signing_message, seal_signing_message, signing_message,
+    verify_ed25519_tag, visibility_bound_id, ActorSignature, Integrity, KeyResolver, SignatureFailure, SignatureVerification, ED25519_SCHEME,
+};
+use crate::compliance::validator::{ComplianceOutcome, ComplianceValidator, RuleSeverity, Violation};
+use crate::storage::append_only::{AppendOnlyStorage, DurabilityLevel, IndexEntry, StorageError, TagFilter};
+use crate::storage::codec::{codec_for_id, default_codec_id, JsonCodec, RecordCodec};
+use crate::storage::encryption::{erased_ciphertext_sha256, is_erased, SubjectKeys};
+use crate::storage::merkle_tree;
+use crate::utils::metrics;
+use crate::utils::nonce::{NonceSource, SystemNonceSource};
+use crate::utils::timestamp::{Clock, SystemClock};
+use arc_swap::ArcSwap;
+use ring::signature::Ed25519KeyPair;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::{mpsc, oneshot, Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard, RwLock};
 use thiserror::Error;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 
-#[derive(Error, Debug)]
+/// Secondary index of records by the account ids their event touches
+pub const ACCOUNT_INDEX: &str = "account";
+
+/// Secondary index of transactions by their tags
+pub const TAG_INDEX: &str = "tag";
+
+/// Ids of the accounts an event names
+fn event_accounts(event: &LedgerEvent) -> Vec<&str> {
+    match event {
+        LedgerEvent::FinancialTransaction(tx) => vec![&tx.from_account, &tx.to_account],
+        LedgerEvent::AccountCreation(acct) => vec![&acct.account_id],
+        LedgerEvent::BalanceAdjustment(adj) => vec![&adj.account_id],
+        LedgerEvent::JournalEntry(entry) => entry.lines.iter().map(|line| line.account.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn index_entries(event: &LedgerEvent) -> Vec<IndexEntry> {
+    let accounts = event_accounts(event);
+    let tags: &[String] = match event {
+        LedgerEvent::FinancialTransaction(tx) => &tx.tags,
+        _ => &[],
+    };
+    accounts
+        .into_iter()
+        .map(|account| IndexEntry::new(ACCOUNT_INDEX, account))
+        .chain(tags.iter().map(|tag| IndexEntry::new(TAG_INDEX, tag.as_str())))
+        .collect()
+}
+
+fn join_messages<T: std::fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    items.into_iter().map(|i| i.to_string()).collect::<Vec<_>>().join("; ")
+}
+
+impl LedgerError {
+    pub fn validation(field: &str, message: impl Into<String>) -> Self {
+        LedgerError::ValidationError {
+            field_errors: vec![FieldError::new(field, message)],
+        }
+    }
+
+    pub fn compliance(violations: Vec<Violation>) -> Self {
+        LedgerError::ComplianceViolation {
+            violations,
+            alert_event_id: None,
+        }
+    }
+}
+
+/// `AuditLog` action marking the first record of a chain
+pub const GENESIS_ACTION: &str = "ledger_genesis";
+
+/// Metadata key under which callers pass an idempotency key to `append_event`
+pub const IDEMPOTENCY_KEY_METADATA: &str = "idempotency_key";
+
+/// Metadata key under which callers pass an `ActorSignature` to `append_event`
+pub const ACTOR_SIGNATURE_METADATA: &str = "actor_signature";
+
+/// Metadata key under which the `EventEnricher` stamps server-side fields.
+/// Anything a client submits under it is discarded.
+pub const SERVER_METADATA: &str = "_server";
+
+/// Metadata key naming the data subject a record is about, whose key seals
+/// it when the storage is an `EncryptionLayer` with subject keys
+pub const SUBJECT_ID_METADATA: &str = "subject_id";
+
+/// `AuditLog` action recording that a subject's keys were destroyed
+pub const SUBJECT_ERASURE_ACTION: &str = "subject_erased";
+
+/// Metadata key recording who released a record from quarantine
+pub const QUARANTINE_RELEASE_METADATA: &str = "quarantine_release";
+
+/// `AuditLog` action marking a tombstone that replaced an archived range
+pub const RETENTION_TOMBSTONE_ACTION: &str = "retention_tombstone";
+
+/// Destination for records moved out of hot storage by retention.
+#[async_trait::async_trait]
+pub trait ArchiveSink: Send + Sync {
+    /// Stores the records, in chain order. Must be durable before returning,
+    /// since the records are deleted from hot storage afterwards.
+    async fn archive(&self, records: &[LedgerRecord]) -> Result<(), StorageError>;
+}
+
+pub struct RetentionPolicy {
+    pub max_age: chrono::Duration,
+    pub archive_sink: Arc<dyn ArchiveSink>,
+    /// Allow archival even when the ledger is sealed
+    pub allow_when_sealed: bool,
+}
+
+/// The idempotency key `metadata` carries, if any
+fn idempotency_key(metadata: Option<&serde_json::Value>) -> Option<&str> {
+    metadata
+        .and_then(|m| m.get(IDEMPOTENCY_KEY_METADATA))
+        .and_then(|k| k.as_str())
+}
+
+/// The hash the record following `record` must link to. Records with a
+/// sequence and nonce are linked through `link_hash`; older records by
+/// their bare event id. Chains whose successors linked to the bare id of a
+/// record with a nonce were written before links were hashed and report a
+/// break there.
+pub(crate) fn link_target(record: &LedgerRecord) -> String {
+    match &record.event {
+        LedgerEvent::AuditLog(log) if log.action == RETENTION_TOMBSTONE_ACTION => log
+            .changes
+            .get("bridge_to")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| record.event_id.clone()),
+        _ => match (record.sequence, &record.nonce) {
+            (Some(sequence), Some(nonce)) => link_hash(&record.event_id, sequence, nonce),
+            _ => record.event_id.clone(),
+        },
+    }
+}
+
+fn alert_severity(severity: &RuleSeverity) -> AlertSeverity {
+    match severity {
+        RuleSeverity::Warning => AlertSeverity::Medium,
+        RuleSeverity::Error => AlertSeverity::High,
+        RuleSeverity::Critical => AlertSeverity::Critical,
+    }
+}
+
+/// Last sequence number covered by a record: its own, or for a retention
+/// tombstone that of the last record it replaced
+fn last_sequence(record: &LedgerRecord) -> Option<u64> {
+    match &record.event {
+        LedgerEvent::AuditLog(log) if log.action == RETENTION_TOMBSTONE_ACTION => {
+            log.changes.get("last_sequence").and_then(|v| v.as_u64())
+        }
+        _ => record.sequence,
+    }
+}
+
+/// Sequence number the record after `record` must carry
+pub(crate) fn next_sequence(record: &LedgerRecord) -> Option<u64> {
+    last_sequence(record).map(|s| s + 1)
+}
+
+/// Whether `record`, as the first of `chain_id`'s chain, links to the
+/// chain's genesis seed. A first record from before seeds and sequence
+/// numbers existed links to nothing instead.
+pub(crate) fn links_to_genesis(record: &LedgerRecord, chain_id: &str) -> bool {
+    match &record.previous_hash {
+        Some(previous) => *previous == genesis_seed(chain_id),
+        None => record.sequence.is_none(),
+    }
+}
+
+/// Whether `record` carries the sequence number expected after the records
+/// before it. Unsequenced records may only come before the first sequenced one.
+fn sequence_follows(record: &LedgerRecord, expected: Option<u64>) -> bool {
+    match (record.sequence, expected) {
+        (Some(sequence), expected) => sequence == expected.unwrap_or(0),
+        (None, expected) => expected.is_none(),
+    }
+}
+
+/// Verifies a dump written by `DigitalLedger::export_ndjson` one line at a
+/// time, without a ledger or storage.
+///
+/// Checks the same links and sequence numbers as `verify_integrity`,
+/// recomputes each event id, and folds the ids into a Merkle root the caller
+/// can compare against an attested one. Only the current line and the
+/// Merkle tree's O(log n) frontier are held, so memory does not grow with
+/// the dump, except for erased records: their erasure is recorded after
+/// them, so each is held until the end to be checked against it. Stops at
+/// the first break; a line that doesn't parse is a break, while a read
+/// failure is an error. An erased record the dump never covers is a break,
+/// even when its erasure lies past a later break.
+pub fn verify_ndjson_stream<R: Read>(reader: R) -> Result<ChainVerification, LedgerError> {
+    let mut tree = merkle_tree::IncrementalMerkleTree::new();
+    let mut report = ChainVerification::default();
+    let mut previous: Option<LedgerRecord> = None;
+    let mut erasures = Erasures::default();
+    // Erased records awaiting their erasure, with the report up to them
+    let mut pending: Vec<(usize, LedgerRecord, ChainVerification)> = Vec::new();
+
+    for (line_no, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.map_err(StorageError::from)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_no = line_no + 1;
+        let mut fail = |event_id: &str, reason: String| {
+            report.first_break = Some(ChainBreak {
+                line: line_no,
+                event_id: event_id.to_string(),
+                reason,
+            });
+        };
+
+        let record: LedgerRecord = match serde_json::from_str(&line) {
+            Ok(record) => record,
+            Err(e) => {
+                fail("", format!("not a ledger record: {}", e));
+                break;
+            }
+        };
+        let reason = match link_break(&record, previous.as_ref()) {
+            Some(reason) => Some(reason),
+            None if is_erased(&record) => None,
+            None => id_break(&record, &erasures)?,
+        };
+        if let Some(reason) = reason {
+            fail(&record.event_id, reason);
+            break;
+        }
+
+        if is_erased(&record) {
+            let so_far = ChainVerification {
+                merkle_root: tree.root_hex(),
+                ..report.clone()
+            };
+            pending.push((line_no, record.clone(), so_far));
+        }
+        erasures.note(&record);
+        tree.push(&record.event_id);
+        report.records_checked += 1;
+        previous = Some(record);
+    }
+
+    report.merkle_root = tree.root_hex();
+    for (line_no, record, mut so_far) in pending {
+        if let Some(reason) = erasures.check(&record) {
+            so_far.first_break = Some(ChainBreak {
+                line: line_no,
+                event_id: record.event_id,
+                reason,
+            });
+            return Ok(so_far);
+        }
+    }
+    Ok(report)
+}
+
+/// `verify_ndjson_stream` over records already in memory, in chain order.
+/// A break's `line` is the 1-based position of the record.
+pub fn verify_record_slice(records: &[LedgerRecord]) -> Result<ChainVerification, LedgerError> {
+    let erasures = Erasures::from_records(records);
+    let mut first_break = None;
+    for (index, record) in records.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|i| &records[i]);
+        if let Some(reason) = record_break(record, previous, &erasures)? {
+            first_break = Some((index, reason));
+            break;
+        }
+    }
+    Ok(chain_verification(records, first_break))
+}
+
+/// `verify_record_slice`, with the work split across threads.
+///
+/// Links only depend on the record before, so the chain is cut into
+/// segments of `segment_size` records whose ids and internal links are
+/// checked concurrently, one thread per available core. Each segment's
+/// first record is then checked against the last record of the segment
+/// before, in order. The result, including which break is reported first,
+/// is the same as the sequential check's.
+pub fn verify_record_slice_parallel(
+    records: &[LedgerRecord],
+    segment_size: usize,
+) -> Result<ChainVerification, LedgerError> {
+    let segment_size = segment_size.max(1);
+    let segments: Vec<(usize, &[LedgerRecord])> = records
+        .chunks(segment_size)
+        .enumerate()
+        .map(|(i, segment)| (i * segment_size, segment))
+        .collect();
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(segments.len().max(1));
+    let erasures = Erasures::from_records(records);
+
+    // First break or error inside each segment, by index into `records`. A
+    // segment's first record is only checked for its id here.
+    let mut outcomes: Vec<SegmentOutcome> = (0..segments.len()).map(|_| Ok(None)).collect();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|worker| {
+                let (segments, erasures) = (&segments, &erasures);
+                scope.spawn(move || {
+                    segments
+                        .iter()
+                        .enumerate()
+                        .skip(worker)
+                        .step_by(workers)
+                        .map(|(segment_no, (start, segment))| (segment_no, check_segment(*start, segment, erasures)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            for (segment_no, outcome) in handle.join().expect("verification worker panicked") {
+                outcomes[segment_no] = outcome;
+            }
+        }
+    });
+
+    // Stitch the boundaries in chain order. A boundary is checked before
+    // the segment it starts, as the sequential check would reach it first.
+    let mut first_break = None;
+    for ((start, _), outcome) in segments.iter().zip(outcomes) {
+        let previous = start.checked_sub(1).map(|i| &records[i]);
+        if let Some(reason) = link_break(&records[*start], previous) {
+            first_break = Some((*start, reason));
+            break;
+        }
+        if let Some(segment_break) = outcome? {
+            first_break = Some(segment_break);
+            break;
+        }
+    }
+    Ok(chain_verification(records, first_break))
+}
+
+type SegmentOutcome = Result<Option<(usize, String)>, LedgerError>;
+
+/// The first break in a segment starting at index `start`, not counting
+/// its first record's link
+fn check_segment(start: usize, segment: &[LedgerRecord], erasures: &Erasures) -> SegmentOutcome {
+    for (offset, record) in segment.iter().enumerate() {
+        let reason = match offset.checked_sub(1) {
+            Some(i) => record_break(record, Some(&segment[i]), erasures)?,
+            None => id_break(record, erasures)?,
+        };
+        if let Some(reason) = reason {
+            return Ok(Some((start + offset, reason)));
+        }
+    }
+    Ok(None)
+}
+
+/// `link_break`, then `id_break`
+fn record_break(
+    record: &LedgerRecord,
+    previous: Option<&LedgerRecord>,
+    erasures: &Erasures,
+) -> Result<Option<String>, LedgerError> {
+    match link_break(record, previous) {
+        Some(reason) => Ok(Some(reason)),
+        None => id_break(record, erasures),
+    }
+}
+
+/// Why `record` doesn't link on from `previous`, the record before it or
+/// `None` at the start of the chain
+fn link_break(record: &LedgerRecord, previous: Option<&LedgerRecord>) -> Option<String> {
+    let expected_previous = previous.map(link_target);
+    let linked = match previous {
+        None => links_to_genesis(record, &record.chain_id),
+        Some(_) => record.previous_hash == expected_previous,
+    };
+    if !linked {
+        return Some(format!("links to {:?}, expected {:?}", record.previous_hash, expected_previous));
+    }
+    let expected_sequence = previous.and_then(next_sequence);
+    if !sequence_follows(record, expected_sequence) {
+        return Some(format!(
+            "has sequence {:?}, expected {:?}",
+            record.sequence,
+            expected_sequence.unwrap_or(0)
+        ));
+    }
+    None
+}
+
+/// Why `record`'s id doesn't match its event. An older record's id is
+/// checked against the bytes it was stored as, which must also migrate to
+/// its event. An erased record's placeholder must match what its erasure
+/// recorded in `erasures`.
+pub(crate) fn id_break(record: &LedgerRecord, erasures: &Erasures) -> Result<Option<String>, LedgerError> {
+    if is_erased(record) {
+        return Ok(erasures.check(record));
+    }
+    let codec = codec_for_id(&record.codec)?;
+    if record.schema_version == CURRENT_SCHEMA_VERSION {
+        let expected_id = record_id(codec.as_ref(), &record.event, &record.visibility)?;
+        if record.event_id != expected_id {
+            return Ok(Some(format!("does not match event hash {}", expected_id)));
+        }
+        return Ok(None);
+    }
+
+    let Some(bytes) = record.legacy_event_bytes.as_deref().and_then(|hex| hex::decode(hex).ok()) else {
+        return Ok(Some(format!(
+            "has schema version {} but no stored bytes to check its id against",
+            record.schema_version
+        )));
+    };
+    let expected_id = visibility_bound_id(codec.hash_bytes(&bytes), &record.visibility);
+    if record.event_id != expected_id {
+        return Ok(Some(format!("does not match stored event hash {}", expected_id)));
+    }
+    let migrated = codec
+        .decode_payload(&bytes)
+        .map_err(|e| e.to_string())
+        .and_then(|payload| migrate_record(record.schema_version, payload).map_err(|e| e.to_string()));
+    let matches = match migrated {
+        Ok(migrated) => serde_json::to_value(&migrated).ok() == serde_json::to_value(&record.event).ok(),
+        Err(reason) => return Ok(Some(format!("stored event does not migrate: {}", reason))),
+    };
+    if !matches {
+        return Ok(Some("event does not match its stored bytes".to_string()));
+    }
+    Ok(None)
+}
+
+/// Ciphertext hashes of the records `DigitalLedger::erase_subject` erased,
+/// by event id, as its `SUBJECT_ERASURE_ACTION` records committed to them.
+/// Those records are verified like any other, so an erased record is only
+/// accepted if an erasure in its chain vouches for the ciphertext still
+/// stored for it.
+#[derive(Debug, Default)]
+pub(crate) struct Erasures(HashMap<String, String>);
+
+impl Erasures {
+    pub(crate) fn from_records<'a>(records: impl IntoIterator<Item = &'a LedgerRecord>) -> Self {
+        let mut erasures = Self::default();
+        records.into_iter().for_each(|record| erasures.note(record));
+        erasures
+    }
+
+    /// Takes in the erasures `record` commits to, if it records any
+    pub(crate) fn note(&mut self, record: &LedgerRecord) {
+        let LedgerEvent::AuditLog(log) = &record.event else {
+            return;
+        };
+        if log.action != SUBJECT_ERASURE_ACTION {
+            return;
+        }
+        let Some(erased) = log.changes.get("erased_records").and_then(|v| v.as_object()) else {
+            return;
+        };
+        for (event_id, ciphertext_sha256) in erased {
+            if let Some(ciphertext_sha256) = ciphertext_sha256.as_str() {
+                self.0.insert(event_id.clone(), ciphertext_sha256.to_string());
+            }
+        }
+    }
+
+    /// Why erased `record` isn't one an erasure vouches for
+    fn check(&self, record: &LedgerRecord) -> Option<String> {
+        let Some(committed) = self.0.get(&record.event_id) else {
+            return Some("is erased, but no erasure in the chain covers it".to_string());
+        };
+        match erased_ciphertext_sha256(record) {
+            Some(found) if found == committed => None,
+            found => Some(format!(
+                "has ciphertext hash {:?}, but its erasure recorded {}",
+                found, committed
+            )),
+        }
+    }
+}
+
+fn chain_verification(records: &[LedgerRecord], first_break: Option<(usize, String)>) -> ChainVerification {
+    let records_checked = first_break.as_ref().map_or(records.len(), |(index, _)| *index);
+    let mut tree = merkle_tree::IncrementalMerkleTree::new();
+    for record in &records[..records_checked] {
+        tree.push(&record.event_id);
+    }
+    ChainVerification {
+        records_checked,
+        merkle_root: tree.root_hex(),
+        first_break: first_break.map(|(index, reason)| ChainBreak {
+            line: index + 1,
+            event_id: records[index].event_id.clone(),
+            reason,
+        }),
+    }
+}
+
+#[derive(Error, Debug, Clone)]
 pub enum LedgerError {
-    #[error("Compliance validation failed: {0}")]
-    ComplianceViolation(String),
+    #[error("Compliance validation failed: {}", join_messages(violations.iter().map(|v| &v.message)))]
+    ComplianceViolation {
+        violations: Vec<Violation>,
+        /// Event id of the `ComplianceAlert` recording this rejection, when
+        /// the ledger records rejections
+        alert_event_id: Option<String>,
+    },
     #[error("Storage error: {0}")]
     StorageError(#[from] crate::storage::append_only::StorageError),
-    #[error("Event validation failed: {0}")]
-    ValidationError(String),
+    #[error("Event validation failed: {}", join_messages(field_errors))]
+    ValidationError { field_errors: Vec<FieldError> },
     #[error("Ledger is sealed, no new entries allowed")]
     LedgerSealed,
+    #[error("Import rejected: {0}")]
+    ImportRejected(String),
+    #[error("Idempotency key {key} was already used for a different event ({existing_event_id})")]
+    IdempotencyConflict { key: String, existing_event_id: String },
+    #[error("Ledger must be sealed first")]
+    NotSealed,
+    #[error("Event {event_id} is already in the ledger")]
+    DuplicateEvent { event_id: String },
+    #[error("Append queue worker has stopped")]
+    AppendQueueClosed,
+    #[error("Event enrichment failed: {0}")]
+    EnrichmentFailed(String),
+    /// Appends are paused for maintenance and may be retried later, unlike
+    /// `LedgerSealed`
+    #[error("Ledger is temporarily read-only for maintenance")]
+    TemporarilyReadOnly,
+    #[error("Event timestamp {event_time} is outside the accepted window around server time {server_time}")]
+    TimestampOutOfBounds {
+        event_time: chrono::DateTime<chrono::Utc>,
+        server_time: chrono::DateTime<chrono::Utc>,
+    },
+    #[error("Event type {event_type} is not accepted by this ledger")]
+    EventTypeNotAllowed { event_type: String },
+    #[error("Event {event_id} is not in quarantine")]
+    NotQuarantined { event_id: String },
+    #[error("Chain {chain_id} has no records in this storage")]
+    ChainNotFound { chain_id: String },
+    /// Money arithmetic, such as summing a balance, left `Decimal`'s range
+    #[error("Money error: {0}")]
+    MoneyError(#[from] crate::core::event::MoneyError),
+    /// A replicated record links to a record this ledger doesn't have, so
+    /// records between its tip and this one are missing
+    #[error("Replicated record {event_id} links to {previous_hash:?}, which is not in the chain (tip {tip:?})")]
+    ReplicationGap {
+        event_id: String,
+        previous_hash: Option<String>,
+        tip: Option<String>,
+    },
+    /// A replicated record links to a record before this ledger's tip, or
+    /// onto the tip with the wrong sequence number, so the two chains differ
+    #[error("Replicated record {event_id} forks the chain: {reason}")]
+    ReplicationFork { event_id: String, reason: String },
+}
+
+/// Rule id of the violation an event's structural validation errors are
+/// kept under when `ValidationMode::Lenient` stores it anyway
+pub const EVENT_VALIDATION_RULE_ID: &str = "EVENT_VALIDATION";
+
+/// How strictly appends are checked, for running the same ledger leniently
+/// in staging and strictly in production.
+///
+/// Only structural validation (`LedgerEvent::validate_with`, including
+/// money precision, FX settlement and `EventLimits`) and compliance rule
+/// verdicts change with the mode. Sealing, maintenance mode, timestamp
+/// bounds, actor signatures, adjustment authorization, idempotency and
+/// duplicate checks are enforced in every mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationMode {
+    /// Nothing blocks. Structural validation errors are logged as warnings
+    /// and kept with the record as a `Warning` violation under
+    /// `EVENT_VALIDATION_RULE_ID`. Every compliance violation, including a
+    /// failure of the validator itself, is kept with the record instead of
+    /// rejecting it.
+    Lenient,
+    /// Structural validation errors reject the event. Compliance violations
+    /// block at the rule set's or validator's blocking severity and are
+    /// otherwise kept with the record.
+    #[default]
+    Standard,
+    /// As `Standard`, except any compliance violation, `Warning` included,
+    /// rejects the event.
+    Strict,
+}
+
+/// Stamps server-authoritative fields, such as a received-at time or a
+/// source identifier, onto events as they are appended.
+///
+/// Runs after an event passes validation and before it is hashed and
+/// stored. The fields it sets land in the record's metadata under
+/// `SERVER_METADATA`, which clients cannot write to, and are stored and
+/// exported with the record like the rest of its metadata. An error rejects
+/// the append.
+pub trait EventEnricher: Send + Sync {
+    fn enrich(
+        &self,
+        event: &LedgerEvent,
+        server: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> anyhow::Result<()>;
+}
+
+/// Adds no server fields
+pub struct NoopEnricher;
+
+impl EventEnricher for NoopEnricher {
+    fn enrich(
+        &self,
+        _event: &LedgerEvent,
+        _server: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Receives notifications about appends without being part of them.
+///
+/// Each observer has its own bounded queue and worker thread. Appends only
+/// queue notifications after the storage commit, so they never wait for an
+/// observer, and observers never wait for each other. An observer receives
+/// records committed through one ledger in chain order, usually after
+/// `append_event` has returned. When its queue is full, new notifications
+/// for it are dropped and counted (see `DigitalLedger::observer_lag`). An
+/// observer error or panic is logged, and never changes the append result
+/// or stops later notifications.
+pub trait LedgerObserver: Send + Sync {
+    fn on_appended(&self, record: &LedgerRecord) -> anyhow::Result<()>;
+
+    fn on_rejected(&self, _event: &LedgerEvent, _error: &LedgerError) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Used in logs, metrics and `ObserverLag`
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// Queue capacity of each observer added with `DigitalLedger::subscribe`
+pub const DEFAULT_OBSERVER_QUEUE_CAPACITY: usize = 1024;
+
+enum ObserverNotification {
+    Appended(LedgerRecord),
+    Rejected(LedgerEvent, LedgerError),
+}
+
+#[derive(Default)]
+struct ObserverCounters {
+    queued: AtomicU64,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    panicked: AtomicU64,
+}
+
+/// An observer's queue, and counters shared with its worker thread
+struct ObserverWorker {
+    name: String,
+    capacity: usize,
+    queue: std::sync::mpsc::SyncSender<ObserverNotification>,
+    counters: Arc<ObserverCounters>,
+}
+
+impl ObserverWorker {
+    fn spawn(observer: Arc<dyn LedgerObserver>, capacity: usize) -> Self {
+        let name = observer.name().to_string();
+        let (queue, receiver) = std::sync::mpsc::sync_channel(capacity);
+        let counters = Arc::new(ObserverCounters::default());
+        let worker_counters = counters.clone();
+        std::thread::Builder::new()
+            .name(format!("ledger-observer-{}", name))
+            .spawn(move || run_observer(observer.as_ref(), receiver, &worker_counters))
+            .expect("failed to spawn observer thread");
+        Self { name, capacity, queue, counters }
+    }
+
+    /// Queues a notification built by `notification`, which is only called
+    /// when there is room for it
+    fn send(&self, notification: impl FnOnce() -> ObserverNotification) {
+        // Reserve a slot first, so a full queue costs no clone
+        if self.counters.queued.fetch_add(1, Ordering::SeqCst) >= self.capacity as u64 {
+            self.counters.queued.fetch_sub(1, Ordering::SeqCst);
+            self.drop_notification();
+            return;
+        }
+        if self.queue.try_send(notification()).is_err() {
+            self.counters.queued.fetch_sub(1, Ordering::SeqCst);
+            self.drop_notification();
+        }
+    }
+
+    fn drop_notification(&self) {
+        let dropped = self.counters.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        metrics::record_observer_drop(&self.name);
+        // Once per power of two, so a stuck observer doesn't flood the log
+        if dropped.is_power_of_two() {
+            warn!("Observer {} queue full, {} notification(s) dropped so far", self.name, dropped);
+        }
+    }
+
+    fn lag(&self) -> ObserverLag {
+        ObserverLag {
+            observer: self.name.clone(),
+            queued: self.counters.queued.load(Ordering::SeqCst) as usize,
+            capacity: self.capacity,
+            delivered: self.counters.delivered.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            panicked: self.counters.panicked.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn run_observer(
+    observer: &dyn LedgerObserver,
+    receiver: std::sync::mpsc::Receiver<ObserverNotification>,
+    counters: &ObserverCounters,
+) {
+    for notification in receiver {
+        let (subject, result) = match &notification {
+            ObserverNotification::Appended(record) => (
+                record.event_id.clone(),
+                std::panic::catch_unwind(AssertUnwindSafe(|| observer.on_appended(record))),
+            ),
+            ObserverNotification::Rejected(event, error) => (
+                event.get_entity_id(),
+                std::panic::catch_unwind(AssertUnwindSafe(|| observer.on_rejected(event, error))),
+            ),
+        };
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Observer {} failed on {}: {}", observer.name(), subject, e),
+            Err(_) => {
+                counters.panicked.fetch_add(1, Ordering::Relaxed);
+                error!("Observer {} panicked on {}", observer.name(), subject);
+            }
+        }
+        counters.delivered.fetch_add(1, Ordering::Relaxed);
+        counters.queued.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// How far one observer is behind, from `DigitalLedger::observer_lag`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObserverLag {
+    /// `LedgerObserver::name`
+    pub observer: String,
+    /// Notifications waiting or being handled
+    pub queued: usize,
+    pub capacity: usize,
+    /// Notifications handled, successfully or not
+    pub delivered: u64,
+    /// Notifications discarded because the queue was full
+    pub dropped: u64,
+    /// Notifications whose handler panicked, included in `delivered`
+    pub panicked: u64,
+}
+
+pub struct LedgerConfig {
+    pub chain_id: String,
+    /// Write a genesis `AuditLog` record when the chain is empty
+    pub write_genesis: bool,
+    /// Encoding for persisted events and the bytes their ids are hashed from
+    pub codec: Arc<dyn RecordCodec>,
+    /// Time source for record timestamps and ledger-generated events
+    pub clock: Arc<dyn Clock>,
+    /// Source of each record's nonce, hashed with its sequence into the
+    /// link the next record carries and into its integrity tag if any
+    pub nonces: Arc<dyn NonceSource>,
+    /// Strategy used to tag each record's `signature`
+    pub integrity: Integrity,
+    /// Rule set whose verdict gates appends; all rules with the validator's
+    /// blocking severity when unset
+    pub rule_set: Option<String>,
+    /// Who may authorize balance adjustments; unchecked when unset
+    pub authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    /// Canonical precision per currency, enforced on every `Money` appended
+    pub currencies: CurrencyRegistry,
+    /// Return the existing id when an event hashes to a record already in
+    /// the ledger, instead of rejecting it with `DuplicateEvent`
+    pub idempotent_duplicates: bool,
+    /// Append a `ComplianceAlert` for every compliance rejection
+    pub record_rejections: bool,
+    /// Caps on metadata size and tags, enforced on every event appended
+    pub event_limits: EventLimits,
+    /// Public keys for checking actor signatures; an event submitted with
+    /// one is rejected when unset
+    pub key_resolver: Option<Arc<dyn KeyResolver>>,
+    /// Stamps server-side metadata on every event appended
+    pub enricher: Arc<dyn EventEnricher>,
+    /// Which checks block an append
+    pub validation_mode: ValidationMode,
+    /// How far ahead of the clock an event's timestamp may be; unchecked
+    /// when unset
+    pub max_future_skew: Option<chrono::Duration>,
+    /// How far behind the clock an event's timestamp may be; unchecked
+    /// when unset
+    pub max_past_age: Option<chrono::Duration>,
+    /// Per-subject keys destroyed by `erase_subject`; shared with the
+    /// `EncryptionLayer` that seals with them
+    pub subject_keys: Option<Arc<dyn SubjectKeys>>,
+    /// `event_type_name`s this ledger accepts; every type when empty. The
+    /// ledger writes its own genesis, checkpoint and erasure records as
+    /// `AuditLog`s, so a list without it makes those fail.
+    pub allowed_event_types: HashSet<String>,
+    /// Separate chain `append_or_quarantine` diverts compliance rejections
+    /// to for review; they are rejected when unset
+    pub quarantine_storage: Option<Arc<dyn AppendOnlyStorage>>,
+    /// Where `verify_since_checkpoint` keeps how far it has verified
+    pub cursor_store: Arc<dyn VerificationCursorStore>,
+    /// Applied to the storage (and quarantine storage) when the ledger is
+    /// opened; the storage keeps its own setting when unset
+    pub durability: Option<DurabilityLevel>,
+}
+
+impl LedgerConfig {
+    pub fn new(chain_id: impl Into<String>) -> Self {
+        Self {
+            chain_id: chain_id.into(),
+            write_genesis: true,
+            codec: Arc::new(JsonCodec),
+            clock: Arc::new(SystemClock),
+            nonces: Arc::new(SystemNonceSource::new()),
+            integrity: Integrity::None,
+            rule_set: None,
+            authorization: None,
+            currencies: CurrencyRegistry::default(),
+            idempotent_duplicates: false,
+            record_rejections: false,
+            event_limits: EventLimits::default(),
+            key_resolver: None,
+            enricher: Arc::new(NoopEnricher),
+            validation_mode: ValidationMode::default(),
+            max_future_skew: None,
+            max_past_age: None,
+            subject_keys: None,
+            allowed_event_types: HashSet::new(),
+            quarantine_storage: None,
+            cursor_store: Arc::new(InMemoryCursorStore::default()),
+            durability: None,
+        }
+    }
+
+    pub fn with_genesis(mut self, write_genesis: bool) -> Self {
+        self.write_genesis = write_genesis;
+        self
+    }
+
+    pub fn with_codec(mut self, codec: Arc<dyn RecordCodec>) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_nonce_source(mut self, nonces: Arc<dyn NonceSource>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
+    pub fn with_integrity(mut self, integrity: Integrity) -> Self {
+        self.integrity = integrity;
+        self
+    }
+
+    pub fn with_rule_set(mut self, rule_set: impl Into<String>) -> Self {
+        self.rule_set = Some(rule_set.into());
+        self
+    }
+
+    pub fn with_authorization(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.authorization = Some(policy);
+        self
+    }
+
+    pub fn with_currency_registry(mut self, currencies: CurrencyRegistry) -> Self {
+        self.currencies = currencies;
+        self
+    }
+
+    pub fn with_idempotent_duplicates(mut self, idempotent_duplicates: bool) -> Self {
+        self.idempotent_duplicates = idempotent_duplicates;
+        self
+    }
+
+    pub fn with_record_rejections(mut self, record_rejections: bool) -> Self {
+        self.record_rejections = record_rejections;
+        self
+    }
+
+    pub fn with_event_limits(mut self, event_limits: EventLimits) -> Self {
+        self.event_limits = event_limits;
+        self
+    }
+
+    pub fn with_key_resolver(mut self, key_resolver: Arc<dyn KeyResolver>) -> Self {
+        self.key_resolver = Some(key_resolver);
+        self
+    }
+
+    pub fn with_enricher(mut self, enricher: Arc<dyn EventEnricher>) -> Self {
+        self.enricher = enricher;
+        self
+    }
+
+    pub fn with_validation_mode(mut self, validation_mode: ValidationMode) -> Self {
+        self.validation_mode = validation_mode;
+        self
+    }
+
+    pub fn with_max_future_skew(mut self, max_future_skew: chrono::Duration) -> Self {
+        self.max_future_skew = Some(max_future_skew);
+        self
+    }
+
+    pub fn with_max_past_age(mut self, max_past_age: chrono::Duration) -> Self {
+        self.max_past_age = Some(max_past_age);
+        self
+    }
+
+    pub fn with_subject_keys(mut self, subject_keys: Arc<dyn SubjectKeys>) -> Self {
+        self.subject_keys = Some(subject_keys);
+        self
+    }
+
+    /// Accepts only events of these types, as named by `event_type_name`
+    pub fn with_allowed_event_types<I, S>(mut self, event_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_event_types = event_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_quarantine_storage(mut self, storage: Arc<dyn AppendOnlyStorage>) -> Self {
+        self.quarantine_storage = Some(storage);
+        self
+    }
+
+    pub fn with_cursor_store(mut self, cursor_store: Arc<dyn VerificationCursorStore>) -> Self {
+        self.cursor_store = cursor_store;
+        self
+    }
+
+    /// See `DurabilityLevel` for what a crash can lose at each level
+    pub fn with_durability(mut self, level: DurabilityLevel) -> Self {
+        self.durability = Some(level);
+        self
+    }
 }
 
 pub struct DigitalLedger {
     storage: Arc<dyn AppendOnlyStorage>,
-    validator: Arc<ComplianceValidator>,
+    // Swapped whole by `reload_validator`; each append loads it once
+    validator: ArcSwap<ComplianceValidator>,
     is_sealed: RwLock<bool>,
+    in_maintenance: RwLock<bool>,
     chain_id: String,
+    codec: Arc<dyn RecordCodec>,
+    clock: Arc<dyn Clock>,
+    nonces: Arc<dyn NonceSource>,
+    integrity: Integrity,
+    rule_set: Option<String>,
+    authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    currencies: CurrencyRegistry,
+    idempotent_duplicates: bool,
+    record_rejections: bool,
+    event_limits: EventLimits,
+    key_resolver: Option<Arc<dyn KeyResolver>>,
+    enricher: Arc<dyn EventEnricher>,
+    validation_mode: ValidationMode,
+    max_future_skew: Option<chrono::Duration>,
+    max_past_age: Option<chrono::Duration>,
+    subject_keys: Option<Arc<dyn SubjectKeys>>,
+    allowed_event_types: HashSet<String>,
+    quarantine_storage: Option<Arc<dyn AppendOnlyStorage>>,
+    cursor_store: Arc<dyn VerificationCursorStore>,
+    observers: Mutex<Vec<ObserverWorker>>,
+    // Held from reading the latest hash until the record linking to it is
+    // stored, so concurrent appends chain linearly instead of forking
+    append_lock: AsyncMutex<()>,
+    append_queue: Option<mpsc::Sender<QueuedAppend>>,
+}
+
+/// An `append_event` call waiting for the append queue worker
+struct QueuedAppend {
+    event: LedgerEvent,
+    metadata: Option<serde_json::Value>,
+    visibility: Vec<String>,
+    reply: oneshot::Sender<Result<String, LedgerError>>,
+}
+
+/// Appends queued events one at a time, in submission order, until the
+/// ledger is dropped
+async fn drain_append_queue(ledger: Weak<DigitalLedger>, mut queue: mpsc::Receiver<QueuedAppend>) {
+    while let Some(append) = queue.recv().await {
+        let Some(ledger) = ledger.upgrade() else {
+            break;
+        };
+        let result = ledger
+            .append_direct(append.event, append.metadata, append.visibility)
+            .await;
+        // The caller may have stopped waiting; the append still happened
+        let _ = append.reply.send(result);
+    }
 }
 
 impl DigitalLedger {
     pub async fn new(
         storage: Arc<dyn AppendOnlyStorage>,
         validator: Arc<ComplianceValidator>,
-        chain_id: String,
+        config: LedgerConfig,
     ) -> Result<Self, LedgerError> {
-        Ok(Self {
+        if let Some(level) = config.durability {
+            storage.set_durability(level)?;
+            if let Some(quarantine) = &config.quarantine_storage {
+                quarantine.set_durability(level)?;
+            }
+        }
+
+        let ledger = Self {
             storage,
-            validator,
+            validator: ArcSwap::new(validator),
             is_sealed: RwLock::new(false),
+            in_maintenance: RwLock::new(false),
+            chain_id: config.chain_id,
+            codec: config.codec,
+            clock: config.clock,
+            nonces: config.nonces,
+            integrity: config.integrity,
+            rule_set: config.rule_set,
+            authorization: config.authorization,
+            currencies: config.currencies,
+            idempotent_duplicates: config.idempotent_duplicates,
+            record_rejections: config.record_rejections,
+            event_limits: config.event_limits,
+            key_resolver: config.key_resolver,
+            enricher: config.enricher,
+            validation_mode: config.validation_mode,
+            max_future_skew: config.max_future_skew,
+            max_past_age: config.max_past_age,
+            subject_keys: config.subject_keys,
+            allowed_event_types: config.allowed_event_types,
+            quarantine_storage: config.quarantine_storage,
+            cursor_store: config.cursor_store,
+            observers: Mutex::new(Vec::new()),
+            append_lock: AsyncMutex::new(()),
+            append_queue: None,
+        };
+
+        // Only an empty chain gets a genesis record
+        if config.write_genesis && ledger.storage.get_latest_hash(&ledger.chain_id).await?.is_none() {
+            ledger.write_genesis().await?;
+        }
+
+        Ok(ledger)
+    }
+
+    /// Attaches to `config.chain_id` in a storage that already holds it,
+    /// possibly alongside other chains (see `AppendOnlyStorage::list_chains`).
+    ///
+    /// Unlike `new`, never writes a genesis record: a chain with no records
+    /// is `ChainNotFound`, and one whose first record does not link to the
+    /// chain's genesis seed fails with a chain verification error, since it
+    /// was started for another chain id or had its beginning replaced.
+    pub async fn open_existing(
+        storage: Arc<dyn AppendOnlyStorage>,
+        validator: Arc<ComplianceValidator>,
+        config: LedgerConfig,
+    ) -> Result<Self, LedgerError> {
+        let chain_id = config.chain_id.clone();
+        let summary = storage
+            .list_chains()
+            .await?
+            .into_iter()
+            .find(|chain| chain.chain_id == chain_id)
+            .ok_or_else(|| LedgerError::ChainNotFound { chain_id: chain_id.clone() })?;
+
+        let first_hash = summary.first_hash.ok_or(StorageError::NotFound)?;
+        let first = storage.get(&chain_id, &first_hash).await?.ok_or(StorageError::NotFound)?;
+        if !links_to_genesis(&first, &chain_id) {
+            return Err(StorageError::ChainVerification(format!(
+                "first record {} of chain {} does not link to its genesis",
+                first.event_id, chain_id
+            ))
+            .into());
+        }
+
+        Self::new(storage, validator, config.with_genesis(false)).await
+    }
+
+    async fn write_genesis(&self) -> Result<String, LedgerError> {
+        let created_at = self.clock.now();
+        let genesis = LedgerEvent::AuditLog(AuditLog {
+            log_id: uuid::Uuid::new_v4().to_string(),
+            action: GENESIS_ACTION.to_string(),
+            actor: "ledger".to_string(),
+            resource: self.chain_id.clone(),
+            changes: serde_json::json!({
+                "chain_id": self.chain_id,
+                "created_at": created_at,
+            }),
+            ip_address: None,
+            user_agent: None,
+            timestamp: created_at,
+        });
+
+        self.append_event(genesis, None).await
+    }
+
+    /// Routes `append_event` through a bounded queue drained by a single
+    /// worker task, spawned on the current tokio runtime.
+    ///
+    /// Appends then run strictly one at a time in submission order, and a
+    /// caller finding `capacity` appends already queued waits before its
+    /// own is accepted, so bursts back up in the queue instead of reaching
+    /// storage. The cost is throughput and latency: direct appends validate
+    /// concurrently and only serialize on the append lock, while queued
+    /// appends also validate one after another, and each caller waits for
+    /// everything queued ahead of it. `append_batch` and imports bypass the
+    /// queue and serialize with it on the append lock. An accepted append
+    /// completes even if its caller stops waiting for it.
+    pub fn with_append_queue(mut self, capacity: usize) -> Arc<Self> {
+        let (sender, receiver) = mpsc::channel(capacity);
+        self.append_queue = Some(sender);
+        let ledger = Arc::new(self);
+        tokio::spawn(drain_append_queue(Arc::downgrade(&ledger), receiver));
+        ledger
+    }
+
+    /// Replaces the compliance validator without stopping appends, for
+    /// applying rule changes from a config watcher (see
+    /// `ComplianceValidator::from_config`).
+    ///
+    /// Each append loads the validator once before running its compliance
+    /// checks, so it is checked entirely by the old validator or entirely
+    /// by the new one. The swap is a single sequentially consistent atomic
+    /// store: every append that loads the validator after this returns
+    /// sees `validator` and everything written to it before the call,
+    /// while appends that loaded the old one finish with it. The old
+    /// validator is dropped once the last of those appends completes.
+    ///
+    /// If the ledger gates appends on a rule set, `validator` must define
+    /// it too, or appends fail their compliance check.
+    pub fn reload_validator(&self, validator: ComplianceValidator) {
+        self.validator.store(Arc::new(validator));
+        info!("Compliance validator reloaded for chain {}", self.chain_id);
+    }
+
+    /// The validator appends are currently checked against
+    pub fn validator(&self) -> Arc<ComplianceValidator> {
+        self.validator.load_full()
+    }
+
+    /// Delivers notifications to `observer` from now on, through a queue of
+    /// `DEFAULT_OBSERVER_QUEUE_CAPACITY`; see `subscribe_with_capacity`
+    pub fn subscribe(&self, observer: Arc<dyn LedgerObserver>) {
+        self.subscribe_with_capacity(observer, DEFAULT_OBSERVER_QUEUE_CAPACITY);
+    }
+
+    /// Delivers notifications to `observer` from now on, through a queue
+    /// holding up to `capacity` of them, drained by a thread of its own.
+    /// The thread exits once the ledger is dropped and the queue is empty.
+    pub fn subscribe_with_capacity(&self, observer: Arc<dyn LedgerObserver>, capacity: usize) {
+        self.observers
+            .lock()
+            .unwrap()
+            .push(ObserverWorker::spawn(observer, capacity));
+    }
+
+    /// Queue and delivery counts of each observer, in subscription order
+    pub fn observer_lag(&self) -> Vec<ObserverLag> {
+        self.observers.lock().unwrap().iter().map(ObserverWorker::lag).collect()
+    }
+
+    pub async fn append_event(
+        &self,
+        event: LedgerEvent,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<String, LedgerError> {
+        self.append_event_with_visibility(event, metadata, Vec::new()).await
+    }
+
+    /// `append_event` for a record only callers holding one of the
+    /// `visibility` scopes can query; empty means anyone can. The scopes
+    /// are part of the record's id, so the same event appended with
+    /// different scopes is a different record.
+    pub async fn append_event_with_visibility(
+        &self,
+        event: LedgerEvent,
+        metadata: Option<serde_json::Value>,
+        mut visibility: Vec<String>,
+    ) -> Result<String, LedgerError> {
+        visibility.sort();
+        visibility.dedup();
+        let Some(queue) = &self.append_queue else {
+            return self.append_direct(event, metadata, visibility).await;
+        };
+
+        let (reply, result) = oneshot::channel();
+        queue
+            .send(QueuedAppend { event, metadata, visibility, reply })
+            .await
+            .map_err(|_| LedgerError::AppendQueueClosed)?;
+        result.await.map_err(|_| LedgerError::AppendQueueClosed)?
+    }
+
+    #[tracing::instrument(
+        skip(self, event, metadata),
+        fields(chain_id = %self.chain_id, event_type = event.event_type_name(), event_id)
+    )]
+    async fn append_direct(
+        &self,
+        event: LedgerEvent,
+        metadata: Option<serde_json::Value>,
+        visibility: Vec<String>,
+    ) -> Result<String, LedgerError> {
+        let started = std::time::Instant::now();
+        let result = match self.find_idempotent_append(&event, metadata.as_ref(), &visibility).await {
+            Ok(Some(existing_event_id)) => return Ok(existing_event_id),
+            Ok(None) => self.try_append(&event, metadata, visibility).await,
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(Appended::Existing(existing_event_id)) => Ok(existing_event_id),
+            Ok(Appended::Stored(record, _append_guard)) => {
+                tracing::Span::current().record("event_id", record.event_id.as_str());
+                metrics::record_append(started.elapsed());
+                // Still holding the append lock, so observers see chain order
+                self.notify_appended(&record);
+                Ok(record.event_id)
+            }
+            Err(LedgerError::DuplicateEvent { event_id }) if self.idempotent_duplicates => {
+                info!("Duplicate append of {} returned existing record", event_id);
+                Ok(event_id)
+            }
+            Err(err) => {
+                let err = self.record_rejection(&event, err).await;
+                self.notify_rejected(&event, &err);
+                Err(err)
+            }
+        }
+    }
+
+    /// When rejections are recorded, appends a `ComplianceAlert` for a
+    /// compliance rejection and returns the error carrying the alert's id.
+    ///
+    /// The alert is stored directly rather than through `append_event`, so
+    /// it is never itself validated and cannot trigger another alert. If
+    /// storing it fails, the failure is logged and the original error is
+    /// returned unchanged.
+    async fn record_rejection(&self, event: &LedgerEvent, err: LedgerError) -> LedgerError {
+        let LedgerError::ComplianceViolation { violations, alert_event_id: None } = &err else {
+            return err;
+        };
+        if !self.record_rejections {
+            return err;
+        }
+
+        let Some(worst) = violations.iter().max_by_key(|v| v.severity.clone()) else {
+            return err;
+        };
+        let mut affected_entities: Vec<String> =
+            index_entries(event).into_iter().map(|entry| entry.key).collect();
+        affected_entities.dedup();
+        if affected_entities.is_empty() {
+            affected_entities.push(event.get_entity_id());
+        }
+
+        let alert = LedgerEvent::ComplianceAlert(ComplianceAlert {
+            alert_id: uuid::Uuid::new_v4().to_string(),
+            rule_id: worst.rule_id.clone(),
+            severity: alert_severity(&worst.severity),
+            description: format!(
+                "Rejected {} {}: {}",
+                event.event_type_name(),
+                event.get_entity_id(),
+                join_messages(violations.iter().map(|v| &v.message))
+            ),
+            affected_entities,
+            evidence: serde_json::json!({
+                "rejected_event_type": event.event_type_name(),
+                "rejected_entity_id": event.get_entity_id(),
+                "violations": violations,
+            }),
+            timestamp: self.clock.now(),
+        });
+
+        let stored = async {
+            let event_hash = self.codec.hash_event(&alert)?;
+            let _append_guard = self.append_lock.lock().await;
+            let record = self
+                .store_record(&alert, event_hash, None, Vec::new(), Vec::new())
+                .await?;
+            self.notify_appended(&record);
+            Ok::<_, LedgerError>(record.event_id)
+        }
+        .await;
+
+        match stored {
+            Ok(event_id) => LedgerError::ComplianceViolation {
+                violations: violations.clone(),
+                alert_event_id: Some(event_id),
+            },
+            Err(e) => {
+                error!("Failed to record rejection of {}: {}", event.get_entity_id(), e);
+                err
+            }
+        }
+    }
+
+    fn notify_rejected(&self, event: &LedgerEvent, err: &LedgerError) {
+        metrics::record_rejection(err);
+        for observer in self.observers.lock().unwrap().iter() {
+            observer.send(|| ObserverNotification::Rejected(event.clone(), err.clone()));
+        }
+    }
+
+    /// Queues `record` for every observer. Called under the append lock, so
+    /// each observer receives records in chain order.
+    fn notify_appended(&self, record: &LedgerRecord) {
+        for observer in self.observers.lock().unwrap().iter() {
+            observer.send(|| ObserverNotification::Appended(record.clone()));
+        }
+    }
+
+    /// When `metadata` carries an idempotency key that was already used,
+    /// returns the event id it was used for, or a conflict if it was used for
+    /// a different event or the same event with other `visibility` scopes.
+    async fn find_idempotent_append(
+        &self,
+        event: &LedgerEvent,
+        metadata: Option<&serde_json::Value>,
+        visibility: &[String],
+    ) -> Result<Option<String>, LedgerError> {
+        let Some(key) = idempotency_key(metadata) else {
+            return Ok(None);
+        };
+
+        let Some(existing) = self.storage.get_by_idempotency_key(&self.chain_id, key).await? else {
+            return Ok(None);
+        };
+
+        if existing.event_id != record_id(self.codec.as_ref(), event, visibility)? {
+            return Err(LedgerError::IdempotencyConflict {
+                key: key.to_string(),
+                existing_event_id: existing.event_id,
+            });
+        }
+
+        info!("Idempotent append of {} returned existing record", existing.event_id);
+        Ok(Some(existing.event_id))
+    }
+
+    /// Validates and stores the event, returning the record together with the
+    /// still-held append lock.
+    async fn try_append(
+        &self,
+        event: &LedgerEvent,
+        metadata: Option<serde_json::Value>,
+        visibility: Vec<String>,
+    ) -> Result<Appended<'_>, LedgerError> {
+        let violations = self.check_event(event, metadata.as_ref()).await?;
+        let metadata = self.enrich_metadata(event, metadata)?;
+
+        // Generate event ID with cryptographic hash
+        let event_hash = record_id(self.codec.as_ref(), event, &visibility)?;
+        
+        let append_guard = self.append_lock.lock().await;
+        // Checked again under the lock, which `seal_and_attest` seals under
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        // The idempotency key is looked up again under the lock, so of two
+        // concurrent appends under one key the later is answered as a replay
+        // or a conflict rather than failing on the storage's unique index
+        if let Some(existing_event_id) = self
+            .find_idempotent_append(event, metadata.as_ref(), &visibility)
+            .await?
+        {
+            return Ok(Appended::Existing(existing_event_id));
+        }
+        // Checked under the lock so a concurrent append of the same event
+        // cannot slip in between
+        if self.storage.get(&self.chain_id, &event_hash).await?.is_some() {
+            return Err(LedgerError::DuplicateEvent { event_id: event_hash });
+        }
+        let record = self
+            .store_record(event, event_hash, metadata, violations, visibility)
+            .await?;
+        Ok(Appended::Stored(record, append_guard))
+    }
+
+    /// Seal, structure, actor signature and compliance checks, returning the
+    /// non-blocking violations to keep with the record. What blocks depends
+    /// on the `ValidationMode`.
+    async fn check_event(
+        &self,
+        event: &LedgerEvent,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<Vec<Violation>, LedgerError> {
+        // Cheapest check first, so a ledger's foreign event types cost nothing
+        let event_type = event.event_type_name();
+        if !self.allowed_event_types.is_empty() && !self.allowed_event_types.contains(event_type) {
+            return Err(LedgerError::EventTypeNotAllowed { event_type: event_type.to_string() });
+        }
+
+        // Check if ledger is sealed
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        if *self.in_maintenance.read().await {
+            return Err(LedgerError::TemporarilyReadOnly);
+        }
+        self.check_timestamp(event)?;
+
+        // Validate event structure
+        let mut kept = Vec::new();
+        if let Err(field_errors) = event.validate_with(&self.currencies, &self.event_limits) {
+            if self.validation_mode != ValidationMode::Lenient {
+                return Err(LedgerError::ValidationError { field_errors });
+            }
+            warn!(
+                "Storing {} despite validation errors: {}",
+                event.event_type_name(),
+                join_messages(&field_errors)
+            );
+            kept.push(Violation {
+                rule_id: EVENT_VALIDATION_RULE_ID.to_string(),
+                severity: RuleSeverity::Warning,
+                message: format!("Event validation failed: {}", join_messages(&field_errors)),
+                evidence: serde_json::json!({ "field_errors": field_errors }),
+            });
+        }
+
+        self.check_actor_signature(event, metadata).await?;
+        self.check_authorization(event).await?;
+
+        // Run compliance checks, all against one validator even if it is
+        // reloaded meanwhile
+        let validator = self.validator.load_full();
+        let outcome = match &self.rule_set {
+            Some(rule_set) => validator.validate_with_rule_set(event, rule_set).await,
+            None => validator.check(event).await,
+        }
+        .unwrap_or_else(|e| {
+            ComplianceOutcome::Blocked(vec![Violation {
+                rule_id: "COMPLIANCE_VALIDATOR".to_string(),
+                severity: RuleSeverity::Critical,
+                message: format!("Compliance check failed: {}", e),
+                evidence: serde_json::json!({"error": e.to_string()}),
+            }])
+        });
+
+        let blocked = match self.validation_mode {
+            ValidationMode::Lenient => false,
+            ValidationMode::Standard => outcome.is_blocked(),
+            ValidationMode::Strict => !outcome.violations().is_empty(),
+        };
+        let violations = outcome.into_violations();
+        if blocked {
+            return Err(LedgerError::compliance(violations));
+        }
+        if self.validation_mode == ValidationMode::Lenient && !violations.is_empty() {
+            warn!("Storing {} despite {} compliance violation(s)", event.event_type_name(), violations.len());
+        }
+        kept.extend(violations);
+        Ok(kept)
+    }
+
+    /// Replaces whatever the client sent under `SERVER_METADATA` with the
+    /// enricher's fields. Metadata that isn't an object can't carry them, so
+    /// it is rejected if the enricher sets any.
+    fn enrich_metadata(
+        &self,
+        event: &LedgerEvent,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<Option<serde_json::Value>, LedgerError> {
+        let mut metadata = metadata;
+        if let Some(serde_json::Value::Object(fields)) = &mut metadata {
+            fields.remove(SERVER_METADATA);
+        }
+
+        let mut server = serde_json::Map::new();
+        self.enricher
+            .enrich(event, &mut server)
+            .map_err(|e| LedgerError::EnrichmentFailed(e.to_string()))?;
+        if server.is_empty() {
+            return Ok(metadata);
+        }
+
+        let mut fields = match metadata {
+            None | Some(serde_json::Value::Null) => serde_json::Map::new(),
+            Some(serde_json::Value::Object(fields)) => fields,
+            Some(_) => {
+                return Err(LedgerError::validation(
+                    "metadata",
+                    "metadata must be an object to carry server fields",
+                ))
+            }
+        };
+        fields.insert(SERVER_METADATA.to_string(), serde_json::Value::Object(server));
+        Ok(Some(serde_json::Value::Object(fields)))
+    }
+
+    /// Rejects events timestamped further from the clock than
+    /// `max_future_skew` ahead or `max_past_age` behind
+    fn check_timestamp(&self, event: &LedgerEvent) -> Result<(), LedgerError> {
+        let event_time = event.get_timestamp();
+        let server_time = self.clock.now();
+        let too_new = self.max_future_skew.is_some_and(|skew| event_time > server_time + skew);
+        let too_old = self.max_past_age.is_some_and(|age| event_time < server_time - age);
+        if too_new || too_old {
+            return Err(LedgerError::TimestampOutOfBounds { event_time, server_time });
+        }
+        Ok(())
+    }
+
+    /// Enforces the authorization policy on balance adjustments
+    async fn check_authorization(&self, event: &LedgerEvent) -> Result<(), LedgerError> {
+        let (Some(policy), LedgerEvent::BalanceAdjustment(adj)) = (&self.authorization, event) else {
+            return Ok(());
+        };
+
+        let reason = match policy.authorize_adjustment(&adj.reason, &adj.authorized_by).await {
+            Ok(AuthorizationDecision::Allow) => return Ok(()),
+            Ok(AuthorizationDecision::Deny(reason)) => reason,
+            Err(e) => format!("Authorization check failed: {}", e),
+        };
+
+        Err(LedgerError::compliance(vec![Violation {
+            rule_id: ADJUSTMENT_AUTHORIZATION_RULE_ID.to_string(),
+            severity: RuleSeverity::Critical,
+            message: reason.clone(),
+            evidence: serde_json::json!({
+                "adjustment_id": adj.adjustment_id,
+                "reason": adj.reason,
+                "authorized_by": adj.authorized_by,
+                "denial": reason,
+            }),
+        }]))
+    }
+
+    /// Links and stores an already checked and hashed event. Callers must
+    /// hold the append lock.
+    async fn store_record(
+        &self,
+        event: &LedgerEvent,
+        event_hash: String,
+        metadata: Option<serde_json::Value>,
+        violations: Vec<Violation>,
+        visibility: Vec<String>,
+    ) -> Result<LedgerRecord, LedgerError> {
+        self.store_record_in(
+            self.storage.as_ref(),
+            &self.chain_id,
+            event,
+            event_hash,
+            metadata,
+            violations,
+            visibility,
+        )
+        .await
+    }
+
+    /// `store_record` onto another chain, such as the quarantine chain
+    async fn store_record_in(
+        &self,
+        storage: &dyn AppendOnlyStorage,
+        chain_id: &str,
+        event: &LedgerEvent,
+        event_hash: String,
+        metadata: Option<serde_json::Value>,
+        violations: Vec<Violation>,
+        visibility: Vec<String>,
+    ) -> Result<LedgerRecord, LedgerError> {
+        // Create the immutable record from whatever head the storage holds
+        // once no other appender can move it
+        let metadata = metadata.unwrap_or_default();
+        let merkle_root_at_append = storage.get_merkle_root(chain_id).await?;
+        let build = |head: Option<&LedgerRecord>| -> Result<LedgerRecord, StorageError> {
+            Ok(self.link_record(
+                chain_id,
+                head,
+                event,
+                &event_hash,
+                &metadata,
+                &violations,
+                &visibility,
+                &merkle_root_at_append,
+            ))
+        };
+
+        // Store append-only, together with its index entries
+        let record = storage
+            .append_linked(chain_id, &build, &index_entries(event))
+            .await?;
+
+        info!("Event appended successfully: {}", record.event_id);
+        Ok(record)
+    }
+
+    /// `store_record` for several unscoped events in one storage commit,
+    /// each linked to the one before it. Callers must hold the append lock.
+    async fn store_records(
+        &self,
+        batch: Vec<(LedgerEvent, String, Option<serde_json::Value>, Vec<Violation>)>,
+    ) -> Result<Vec<LedgerRecord>, LedgerError> {
+        let batch: Vec<_> = batch
+            .into_iter()
+            .map(|(event, event_hash, metadata, violations)| (event, event_hash, metadata.unwrap_or_default(), violations))
+            .collect();
+        let entries: Vec<Vec<IndexEntry>> = batch.iter().map(|(event, ..)| index_entries(event)).collect();
+        let build = |position: usize, head: Option<&LedgerRecord>, merkle_root: &str| -> Result<LedgerRecord, StorageError> {
+            let (event, event_hash, metadata, violations) = &batch[position];
+            Ok(self.link_record(&self.chain_id, head, event, event_hash, metadata, violations, &[], merkle_root))
+        };
+
+        let records = self
+            .storage
+            .append_linked_batch(&self.chain_id, &build, &entries)
+            .await?;
+
+        info!("{} events appended in one commit", records.len());
+        Ok(records)
+    }
+
+    /// The record for `event` following `head` in `chain_id`
+    fn link_record(
+        &self,
+        chain_id: &str,
+        head: Option<&LedgerRecord>,
+        event: &LedgerEvent,
+        event_hash: &str,
+        metadata: &serde_json::Value,
+        violations: &[Violation],
+        visibility: &[String],
+        merkle_root_at_append: &str,
+    ) -> LedgerRecord {
+        let previous_hash = Some(head.map_or_else(|| genesis_seed(chain_id), link_target));
+        // Chains from before sequences existed start counting here
+        let sequence = Some(head.and_then(next_sequence).unwrap_or(0));
+        let nonce = Some(self.nonces.nonce());
+        let signature = self.integrity.sign(&signing_message(
             chain_id,
+            event_hash,
+            previous_hash.as_deref(),
+            sequence,
+            nonce.as_deref(),
+        ));
+        LedgerRecord {
+            event_id: event_hash.to_string(),
+            event: event.clone(),
+            metadata: metadata.clone(),
+            timestamp: self.clock.now(),
+            previous_hash,
+            chain_id: chain_id.to_string(),
+            signature,
+            violations: violations.to_vec(),
+            codec: self.codec.codec_id().to_string(),
+            schema_version: CURRENT_SCHEMA_VERSION,
+            legacy_event_bytes: None,
+            sequence,
+            nonce,
+            merkle_root_at_append: Some(merkle_root_at_append.to_string()),
+            visibility: visibility.to_vec(),
+        }
+    }
+
+    /// Appends several events in order under a single hold of the append
+    /// lock, hashing them in one `RecordCodec::hash_events` pass.
+    ///
+    /// Every event is checked before anything is stored, so a structural or
+    /// compliance rejection leaves the chain untouched, and the records are
+    /// stored in one storage commit (see `append_linked_batch`).
+    /// Events whose idempotency key was already used return the existing id.
+    /// Within the batch, a key repeated with the same event returns the same
+    /// id and with another event is an `IdempotencyConflict`. Batched
+    /// records have no visibility scopes, so reusing the key of a scoped
+    /// record is an `IdempotencyConflict` too.
+    pub async fn append_batch(
+        &self,
+        events: Vec<(LedgerEvent, Option<serde_json::Value>)>,
+    ) -> Result<Vec<String>, LedgerError> {
+        let started = std::time::Instant::now();
+        let mut checked = Vec::with_capacity(events.len());
+        let mut batch_keys = HashMap::new();
+        let mut events = events;
+        for (event, metadata) in &mut events {
+            let result = match self.find_idempotent_append(event, metadata.as_ref(), &[]).await {
+                Ok(Some(event_id)) => Ok(BatchItem::Existing(event_id)),
+                Ok(None) => match self.check_event(event, metadata.as_ref()).await {
+                    Ok(violations) => self
+                        .note_batch_key(&mut batch_keys, event, metadata.as_ref())
+                        .and_then(|()| self.enrich_metadata(event, metadata.take()))
+                        .map(|enriched| {
+                            *metadata = enriched;
+                            BatchItem::New(violations)
+                        }),
+                    Err(err) => Err(err),
+                },
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(item) => checked.push(item),
+                Err(err) => {
+                    let err = self.record_rejection(event, err).await;
+                    self.notify_rejected(event, &err);
+                    return Err(err);
+                }
+            }
+        }
+
+        let new_events: Vec<&(LedgerEvent, Option<serde_json::Value>)> = events
+            .iter()
+            .zip(&checked)
+            .filter(|(_, item)| matches!(item, BatchItem::New(_)))
+            .map(|(entry, _)| entry)
+            .collect();
+        let hashes = self
+            .codec
+            .hash_events(&new_events.iter().map(|(event, _)| event).collect::<Vec<_>>())?;
+
+        let _append_guard = self.append_lock.lock().await;
+        // Checked again under the lock, which `seal_and_attest` seals under
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        // Duplicates, against the ledger or earlier in the batch, are found
+        // before anything is stored so a rejected batch stores nothing. An
+        // event repeated under its idempotency key, earlier in the batch or
+        // by a concurrent append since the key was first looked up, is a
+        // replay rather than a duplicate.
+        let mut seen = HashSet::with_capacity(hashes.len());
+        let mut duplicates = Vec::with_capacity(hashes.len());
+        for ((event, metadata), event_hash) in new_events.iter().zip(&hashes) {
+            let replayed = match self.find_idempotent_append(event, metadata.as_ref(), &[]).await {
+                Ok(existing) => existing.is_some(),
+                Err(err) => {
+                    self.notify_rejected(event, &err);
+                    return Err(err);
+                }
+            };
+            let repeated = !seen.insert(event_hash.as_str());
+            let replayed = replayed || (repeated && idempotency_key(metadata.as_ref()).is_some());
+            let duplicate = repeated || self.storage.get(&self.chain_id, event_hash).await?.is_some();
+            if duplicate && !replayed && !self.idempotent_duplicates {
+                let err = LedgerError::DuplicateEvent { event_id: event_hash.clone() };
+                self.notify_rejected(event, &err);
+                return Err(err);
+            }
+            duplicates.push(duplicate);
+        }
+        let mut hashes = hashes.into_iter().zip(duplicates);
+
+        let mut event_ids = Vec::with_capacity(events.len());
+        let mut batch = Vec::with_capacity(events.len());
+        for ((event, metadata), item) in events.into_iter().zip(checked) {
+            let violations = match item {
+                BatchItem::Existing(event_id) => {
+                    event_ids.push(event_id);
+                    continue;
+                }
+                BatchItem::New(violations) => violations,
+            };
+            let (event_hash, duplicate) = hashes.next().expect("one hash per new event");
+            event_ids.push(event_hash.clone());
+            if !duplicate {
+                batch.push((event, event_hash, metadata, violations));
+            }
+        }
+        if !batch.is_empty() {
+            for record in self.store_records(batch).await? {
+                self.notify_appended(&record);
+            }
+        }
+        self.storage.flush_batch().await?;
+
+        metrics::record_append(started.elapsed());
+        Ok(event_ids)
+    }
+
+    /// Notes the idempotency key in `metadata` as used by `event` earlier in
+    /// the batch. Reusing a noted key for another event is a conflict.
+    fn note_batch_key(
+        &self,
+        batch_keys: &mut HashMap<String, String>,
+        event: &LedgerEvent,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<(), LedgerError> {
+        let Some(key) = idempotency_key(metadata) else {
+            return Ok(());
+        };
+        let event_id = record_id(self.codec.as_ref(), event, &[])?;
+        match batch_keys.get(key) {
+            Some(existing_event_id) if *existing_event_id != event_id => Err(LedgerError::IdempotencyConflict {
+                key: key.to_string(),
+                existing_event_id: existing_event_id.clone(),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                batch_keys.insert(key.to_string(), event_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// `append_batch` that commits the events that pass and reports the
+    /// rest, for importers that would rather not resubmit a whole batch
+    /// over one bad event.
+    ///
+    /// Each committed record links to the one committed before it, so a
+    /// rejected event leaves no gap in the chain. Committed records keep
+    /// their input order. A storage failure rejects only the event being
+    /// stored. Errors that prevent the batch from being processed at all,
+    /// such as a codec failure, are still returned as `Err`.
+    pub async fn append_batch_lenient(
+        &self,
+        events: Vec<(LedgerEvent, Option<serde_json::Value>)>,
+    ) -> Result<BatchResult, LedgerError> {
+        let started = std::time::Instant::now();
+        let mut result = BatchResult::default();
+        let mut checked = Vec::with_capacity(events.len());
+        let mut batch_keys = HashMap::new();
+        for (index, (event, mut metadata)) in events.into_iter().enumerate() {
+            let item = match self.find_idempotent_append(&event, metadata.as_ref(), &[]).await {
+                Ok(Some(event_id)) => Ok(BatchItem::Existing(event_id)),
+                Ok(None) => match self.check_event(&event, metadata.as_ref()).await {
+                    Ok(violations) => self
+                        .note_batch_key(&mut batch_keys, &event, metadata.as_ref())
+                        .and_then(|()| self.enrich_metadata(&event, metadata.take()))
+                        .map(|enriched| {
+                            metadata = enriched;
+                            BatchItem::New(violations)
+                        }),
+                    Err(err) => Err(err),
+                },
+                Err(err) => Err(err),
+            };
+            match item {
+                Ok(item) => checked.push((index, event, metadata, item)),
+                Err(err) => {
+                    let err = self.record_rejection(&event, err).await;
+                    self.notify_rejected(&event, &err);
+                    result.rejected.push((index, err));
+                }
+            }
+        }
+
+        let new_events: Vec<&LedgerEvent> = checked
+            .iter()
+            .filter(|(_, _, _, item)| matches!(item, BatchItem::New(_)))
+            .map(|(_, event, _, _)| event)
+            .collect();
+        let hashes = self.codec.hash_events(&new_events)?;
+        let mut hashes = hashes.into_iter();
+
+        let _append_guard = self.append_lock.lock().await;
+        // Checked again under the lock, which `seal_and_attest` seals under
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        let mut seen = HashSet::new();
+        for (index, event, metadata, item) in checked {
+            let violations = match item {
+                BatchItem::Existing(event_id) => {
+                    result.appended.push(event_id);
+                    continue;
+                }
+                BatchItem::New(violations) => violations,
+            };
+            let event_hash = hashes.next().expect("one hash per new event");
+            // Records are committed one at a time, so this also finds a key
+            // repeated earlier in the batch
+            let replayed = match self.find_idempotent_append(&event, metadata.as_ref(), &[]).await {
+                Ok(existing) => existing.is_some(),
+                Err(err) => {
+                    self.notify_rejected(&event, &err);
+                    result.rejected.push((index, err));
+                    continue;
+                }
+            };
+            let duplicate = match self.storage.get(&self.chain_id, &event_hash).await {
+                Ok(existing) => !seen.insert(event_hash.clone()) || existing.is_some(),
+                Err(e) => {
+                    result.rejected.push((index, e.into()));
+                    continue;
+                }
+            };
+            if duplicate {
+                if replayed || self.idempotent_duplicates {
+                    result.appended.push(event_hash);
+                } else {
+                    let err = LedgerError::DuplicateEvent { event_id: event_hash };
+                    self.notify_rejected(&event, &err);
+                    result.rejected.push((index, err));
+                }
+                continue;
+            }
+            match self
+                .store_record(&event, event_hash.clone(), metadata, violations, Vec::new())
+                .await
+            {
+                Ok(record) => {
+                    self.notify_appended(&record);
+                    result.appended.push(record.event_id);
+                }
+                Err(err) => {
+                    // Only an event that was never stored may be retried
+                    // later under the same hash
+                    seen.remove(&event_hash);
+                    result.rejected.push((index, err));
+                }
+            }
+        }
+
+        // Everything reported appended must be as durable as the level promises
+        self.storage.flush_batch().await?;
+
+        result.rejected.sort_by_key(|(index, _)| *index);
+        metrics::record_append(started.elapsed());
+        Ok(result)
+    }
+
+    #[tracing::instrument(skip(self), fields(chain_id = %self.chain_id, records_checked))]
+    pub async fn verify_integrity(&self) -> Result<bool, LedgerError> {
+        let started = std::time::Instant::now();
+        let result = if self.storage.verify_chain(&self.chain_id).await? {
+            self.verify_records().await
+        } else {
+            Ok(false)
+        };
+        metrics::record_verify_integrity(started.elapsed());
+        result
+    }
+
+    /// Checks genesis and `previous_hash` links across the whole chain.
+    ///
+    /// The first record must link to the chain's genesis seed (see
+    /// `genesis_seed`), and only it may. A chain whose first record has been
+    /// deleted therefore fails here, because its new head still points at
+    /// the missing record.
+    ///
+    /// A retention tombstone stands in for an archived range: it inherits the
+    /// `previous_hash` of the first archived record, and the record after it
+    /// links to the last archived record, whose id the tombstone carries as
+    /// `bridge_to`. Verification follows that bridge instead of the
+    /// tombstone's own id.
+    ///
+    /// Sequence numbers must count up by one from 0, so a suffix that was
+    /// cut off and replaced by re-linked records still shows as a gap. A
+    /// tombstone covers its range's numbers. Records from before sequences
+    /// existed may only precede the first sequenced record.
+    ///
+    /// Every record's id must match its event as well, checked as
+    /// `verify_integrity_parallel` checks it, so both agree on every chain.
+    async fn verify_records(&self) -> Result<bool, LedgerError> {
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        tracing::Span::current().record("records_checked", records.len());
+        self.records_link(&records, &Erasures::from_records(&records))
+    }
+
+    /// `verify_integrity` for only the records appended since the last
+    /// successful run, advancing the stored cursor to the chain head when
+    /// they check out.
+    ///
+    /// The records after the cursor must link on from the cursor's record,
+    /// which must still be in the chain unchanged, with their sequence
+    /// numbers counting on from it. Runs verify in full when there is no
+    /// cursor, when its record has since been archived by retention, and
+    /// always when the ledger has no integrity key: the cursor is tagged
+    /// with the ledger's integrity strategy, and one whose tag doesn't
+    /// check out is discarded rather than trusted. A failed run leaves the
+    /// cursor where it was.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.chain_id, records_checked))]
+    pub async fn verify_since_checkpoint(&self) -> Result<bool, LedgerError> {
+        let started = std::time::Instant::now();
+        let verification = match self.trusted_cursor()? {
+            Some(cursor) => match self.verify_after(&cursor).await? {
+                Some(verification) => verification,
+                None => {
+                    warn!("Verification cursor record {} is gone, verifying in full", cursor.event_id);
+                    self.verify_in_full().await?
+                }
+            },
+            None => self.verify_in_full().await?,
+        };
+        metrics::record_verify_integrity(started.elapsed());
+
+        let Verification::Passed(head) = verification else {
+            return Ok(false);
+        };
+        if let Some(head) = head {
+            self.advance_cursor(&head)?;
+        }
+        Ok(true)
+    }
+
+    /// Clears the verification cursor, so the next `verify_since_checkpoint`
+    /// verifies the whole chain
+    pub fn reset_verification_checkpoint(&self) -> Result<(), LedgerError> {
+        self.cursor_store.save(None)?;
+        info!("Verification cursor of chain {} reset", self.chain_id);
+        Ok(())
+    }
+
+    /// The stored cursor, if it is this chain's and its tag checks out
+    fn trusted_cursor(&self) -> Result<Option<VerificationCursor>, LedgerError> {
+        let Some(cursor) = self.cursor_store.load()? else {
+            return Ok(None);
+        };
+        if cursor.chain_id != self.chain_id {
+            warn!("Ignoring verification cursor of chain {}", cursor.chain_id);
+            return Ok(None);
+        }
+        match self.integrity.verify(&cursor.signing_message(), &cursor.signature) {
+            Ok(true) => Ok(Some(cursor)),
+            Ok(false) => {
+                error!("Verification cursor at {} has an invalid tag, ignoring it", cursor.event_id);
+                Ok(None)
+            }
+            Err(e) => {
+                warn!("Cannot check verification cursor at {}: {}", cursor.event_id, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Checks the records after `cursor`, or `None` if its record is no
+    /// longer in the chain
+    async fn verify_after(&self, cursor: &VerificationCursor) -> Result<Option<Verification>, LedgerError> {
+        let records = self.storage.records_from(&self.chain_id, &cursor.event_id).await?;
+        let Some(at_cursor) = records.first() else {
+            return Ok(None);
+        };
+
+        if link_target(at_cursor) != cursor.link_hash || at_cursor.sequence != cursor.sequence {
+            error!("Record {} no longer matches the verification cursor", at_cursor.event_id);
+            return Ok(Some(Verification::Failed));
+        }
+        let after = &records[1..];
+        tracing::Span::current().record("records_checked", after.len());
+        let erasures = Erasures::from_records(after);
+        if !self.links_from(after, Some(cursor.link_hash.clone()), next_sequence(at_cursor), &erasures)? {
+            return Ok(Some(Verification::Failed));
+        }
+        Ok(Some(Verification::Passed(Some(after.last().unwrap_or(at_cursor).clone()))))
+    }
+
+    async fn verify_in_full(&self) -> Result<Verification, LedgerError> {
+        if !self.storage.verify_chain(&self.chain_id).await? {
+            return Ok(Verification::Failed);
+        }
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        tracing::Span::current().record("records_checked", records.len());
+        if !self.records_link(&records, &Erasures::from_records(&records))? {
+            return Ok(Verification::Failed);
+        }
+        Ok(Verification::Passed(records.last().cloned()))
+    }
+
+    fn advance_cursor(&self, head: &LedgerRecord) -> Result<(), LedgerError> {
+        let link_hash = link_target(head);
+        let message = cursor_signing_message(
+            &self.chain_id,
+            &head.event_id,
+            &link_hash,
+            head.sequence,
+            head.timestamp,
+        );
+        // An untagged cursor could be forged to skip records, so none is kept
+        let Some(signature) = self.integrity.sign(&message) else {
+            return Ok(());
+        };
+        self.cursor_store.save(Some(&VerificationCursor {
+            chain_id: self.chain_id.clone(),
+            event_id: head.event_id.clone(),
+            link_hash,
+            sequence: head.sequence,
+            timestamp: head.timestamp,
+            verified_at: self.clock.now(),
+            signature,
+        }))?;
+        Ok(())
+    }
+
+    /// Checks every record's link, sequence number and id like
+    /// `verify_ndjson_stream`, spreading the work over all cores with
+    /// `verify_record_slice_parallel`, and reports the first break. Runs on
+    /// a blocking thread so the async runtime keeps serving other tasks.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.chain_id, records_checked))]
+    pub async fn verify_integrity_parallel(&self, segment_size: usize) -> Result<ChainVerification, LedgerError> {
+        let started = std::time::Instant::now();
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        let report = tokio::task::spawn_blocking(move || verify_record_slice_parallel(&records, segment_size))
+            .await
+            .map_err(|e| StorageError::ChainVerification(e.to_string()))??;
+        tracing::Span::current().record("records_checked", report.records_checked);
+        metrics::record_verify_integrity(started.elapsed());
+        Ok(report)
+    }
+
+    /// The checks of `verify_records` over a prefix of the chain, with
+    /// erased records checked against `erasures`
+    fn records_link(&self, records: &[LedgerRecord], erasures: &Erasures) -> Result<bool, LedgerError> {
+        let Some(first) = records.first() else {
+            return Ok(true);
+        };
+
+        if !links_to_genesis(first, &self.chain_id) {
+            error!("Chain {} does not start from its genesis seed", self.chain_id);
+            return Ok(false);
+        }
+
+        if let LedgerEvent::AuditLog(log) = &first.event {
+            if log.action == GENESIS_ACTION && log.resource != self.chain_id {
+                error!("Genesis record belongs to chain {}, not {}", log.resource, self.chain_id);
+                return Ok(false);
+            }
+        }
+
+        self.links_from(records, first.previous_hash.clone(), None, erasures)
+    }
+
+    /// Whether `records` link on from `expected_previous` with sequence
+    /// numbers counting on from `expected_sequence`, each with an id that
+    /// matches its event
+    fn links_from(
+        &self,
+        records: &[LedgerRecord],
+        mut expected_previous: Option<String>,
+        mut expected_sequence: Option<u64>,
+        erasures: &Erasures,
+    ) -> Result<bool, LedgerError> {
+        for record in records {
+            if record.previous_hash != expected_previous {
+                error!(
+                    "Record {} links to {:?}, expected {:?}",
+                    record.event_id, record.previous_hash, expected_previous
+                );
+                return Ok(false);
+            }
+            expected_previous = Some(link_target(record));
+
+            if !sequence_follows(record, expected_sequence) {
+                error!(
+                    "Record {} has sequence {:?}, expected {:?}",
+                    record.event_id, record.sequence, expected_sequence.unwrap_or(0)
+                );
+                return Ok(false);
+            }
+            expected_sequence = next_sequence(record);
+
+            if let Some(reason) = id_break(record, erasures)? {
+                error!("Record {} {}", record.event_id, reason);
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Captures the current Merkle root, record count and head, signed with
+    /// the ledger's integrity strategy when it has one, for anchoring in an
+    /// external system.
+    pub async fn create_checkpoint(&self) -> Result<Checkpoint, LedgerError> {
+        // Hold off appends so the root, count and head describe one state
+        let _append_guard = self.append_lock.lock().await;
+        let ids = self.chain_event_ids().await?;
+        let leaves: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let merkle_root = hex::encode(merkle_tree::compute_root(&leaves));
+        let signature = self
+            .integrity
+            .sign(&root_signing_message(&self.chain_id, &merkle_root, ids.len()));
+
+        Ok(Checkpoint {
+            merkle_tree_version: merkle_tree::MERKLE_TREE_VERSION,
+            chain_id: self.chain_id.clone(),
+            merkle_root,
+            record_count: ids.len(),
+            head_event_id: ids.last().cloned(),
+            created_at: self.clock.now(),
+            signature,
         })
     }
 
-    pub async fn append_event(
+    /// Records that `checkpoint` was anchored at `external_anchor`, such as
+    /// a blockchain transaction id, as an `AuditLog` in the chain. Returns
+    /// the new record's event id.
+    pub async fn append_checkpoint(
         &self,
-        event: LedgerEvent,
-        metadata: Option<serde_json::Value>,
+        checkpoint: &Checkpoint,
+        external_anchor: impl Into<String>,
     ) -> Result<String, LedgerError> {
-        // Check if ledger is sealed
-        if *self.is_sealed.read().await {
-            return Err(LedgerError::LedgerSealed);
+        if checkpoint.chain_id != self.chain_id {
+            return Err(LedgerError::validation(
+                "checkpoint.chain_id",
+                format!("checkpoint is for chain {}, not {}", checkpoint.chain_id, self.chain_id),
+            ));
         }
 
-        // Validate event structure
-        event.validate()?;
+        let event = LedgerEvent::AuditLog(AuditLog {
+            log_id: uuid::Uuid::new_v4().to_string(),
+            action: CHECKPOINT_ACTION.to_string(),
+            actor: "ledger".to_string(),
+            resource: self.chain_id.clone(),
+            changes: serde_json::json!({
+                "checkpoint": checkpoint,
+                "external_anchor": external_anchor.into(),
+            }),
+            ip_address: None,
+            user_agent: None,
+            timestamp: self.clock.now(),
+        });
 
-        // Run compliance checks
-        self.validator.validate(&event).await.map_err(|e| {
-            LedgerError::ComplianceViolation(format!("Compliance check failed: {}", e))
-        })?;
+        self.append_event(event, None).await
+    }
 
-        // Generate event ID with cryptographic hash
-        let event_hash = generate_hash_chain(&event)?;
-        
-        // Create immutable record
-        let record = LedgerRecord {
-            event_id: event_hash.clone(),
-            event,
-            metadata: metadata.unwrap_or_default(),
-            timestamp: chrono::Utc::now(),
-            previous_hash: self.storage.get_latest_hash().await?,
-            chain_id: self.chain_id.clone(),
-            signature: None, // Would be populated with actual signing
+    /// Verifies the chain up to `checkpoint` on its own: the records it
+    /// covers must link and count up from genesis as in `verify_integrity`,
+    /// end at its head and hash to its Merkle root, and its signature must
+    /// check out when it has one. Records appended after the checkpoint are
+    /// only read for erasures of records it covers. Fails once retention
+    /// has archived records it covers.
+    pub async fn verify_integrity_to(&self, checkpoint: &Checkpoint) -> Result<bool, LedgerError> {
+        if checkpoint.chain_id != self.chain_id {
+            error!("Checkpoint is for chain {}, not {}", checkpoint.chain_id, self.chain_id);
+            return Ok(false);
+        }
+        if checkpoint.merkle_tree_version != merkle_tree::MERKLE_TREE_VERSION {
+            error!("Checkpoint uses Merkle tree version {}", checkpoint.merkle_tree_version);
+            return Ok(false);
+        }
+
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        let Some(covered) = records.get(..checkpoint.record_count) else {
+            error!(
+                "Chain has {} records, checkpoint covers {}",
+                records.len(), checkpoint.record_count
+            );
+            return Ok(false);
         };
+        // An erasure after the checkpoint still covers the records it erased
+        if !self.records_link(covered, &Erasures::from_records(&records))? {
+            return Ok(false);
+        }
+
+        let head = covered.last().map(|r| r.event_id.as_str());
+        if head != checkpoint.head_event_id.as_deref() {
+            error!("Checkpoint head is {:?}, chain has {:?}", checkpoint.head_event_id, head);
+            return Ok(false);
+        }
+        let leaves: Vec<&str> = covered.iter().map(|r| r.event_id.as_str()).collect();
+        let merkle_root = hex::encode(merkle_tree::compute_root(&leaves));
+        if merkle_root != checkpoint.merkle_root {
+            error!("Checkpoint root is {}, chain hashes to {}", checkpoint.merkle_root, merkle_root);
+            return Ok(false);
+        }
 
-        // Store append-only
-        self.storage.append(record).await?;
+        if let Some(tag) = &checkpoint.signature {
+            let message = root_signing_message(&checkpoint.chain_id, &checkpoint.merkle_root, checkpoint.record_count);
+            match self.integrity.verify(&message, tag) {
+                Ok(true) => {}
+                Ok(false) => {
+                    error!("Checkpoint signature does not match");
+                    return Ok(false);
+                }
+                Err(reason) => {
+                    error!("Checkpoint signature cannot be checked: {}", reason);
+                    return Ok(false);
+                }
+            }
+        }
 
-        info!("Event appended successfully: {}", event_hash);
-        Ok(event_hash)
+        Ok(true)
     }
 
-    pub async fn verify_integrity(&self) -> Result<bool, LedgerError> {
-        self.storage.verify_chain().await.map_err(|e| e.into())
+    /// Rejects an event whose metadata carries an actor signature that does
+    /// not verify against the actor's key. Events without one pass.
+    async fn check_actor_signature(
+        &self,
+        event: &LedgerEvent,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<(), LedgerError> {
+        let Some(value) = metadata.and_then(|m| m.get(ACTOR_SIGNATURE_METADATA)) else {
+            return Ok(());
+        };
+        let field = format!("metadata.{}", ACTOR_SIGNATURE_METADATA);
+        let signature: ActorSignature = serde_json::from_value(value.clone())
+            .map_err(|e| LedgerError::validation(&field, format!("malformed actor signature: {}", e)))?;
+        let event_bytes = self.codec.encode_event(event)?;
+
+        match self.verify_actor_signature(&signature, &event_bytes).await {
+            Ok(()) => Ok(()),
+            Err(reason) => Err(LedgerError::validation(&field, reason)),
+        }
+    }
+
+    async fn verify_actor_signature(&self, signature: &ActorSignature, event_bytes: &[u8]) -> Result<(), String> {
+        let Some(resolver) = &self.key_resolver else {
+            return Err("no key resolver configured to check actor signatures".to_string());
+        };
+        let public_key = resolver
+            .public_key(&signature.actor_id)
+            .await
+            .map_err(|e| format!("key lookup for actor {} failed: {}", signature.actor_id, e))?
+            .ok_or_else(|| format!("no public key for actor {}", signature.actor_id))?;
+        match verify_ed25519_tag(&public_key, event_bytes, &signature.signature) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("actor signature of {} does not match the event", signature.actor_id)),
+            Err(reason) => Err(reason),
+        }
+    }
+
+    /// Checks every record's integrity tag with the configured strategy,
+    /// and every actor signature against the actor's resolved key. Unsigned
+    /// records are counted but not treated as failures. Ledger tag and
+    /// actor signature failures are reported separately.
+    pub async fn verify_signatures(&self) -> Result<SignatureVerification, LedgerError> {
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        let mut report = SignatureVerification {
+            records_checked: records.len(),
+            ..Default::default()
+        };
+
+        for record in &records {
+            // An erased event can no longer be checked against its signer
+            let actor_signature = record.metadata.get(ACTOR_SIGNATURE_METADATA).filter(|_| !is_erased(record));
+            if let Some(value) = actor_signature {
+                report.actor_signed += 1;
+                let result = match serde_json::from_value::<ActorSignature>(value.clone()) {
+                    Ok(signature) => {
+                        let event_bytes = codec_for_id(&record.codec)?.encode_event(&record.event)?;
+                        self.verify_actor_signature(&signature, &event_bytes).await
+                    }
+                    Err(e) => Err(format!("malformed actor signature: {}", e)),
+                };
+                if let Err(reason) = result {
+                    report.actor_failures.push(SignatureFailure {
+                        event_id: record.event_id.clone(),
+                        reason,
+                    });
+                }
+            }
+
+            let Some(tag) = &record.signature else {
+                report.unsigned += 1;
+                continue;
+            };
+            let message = signing_message(
+                &record.chain_id,
+                &record.event_id,
+                record.previous_hash.as_deref(),
+                record.sequence,
+                record.nonce.as_deref(),
+            );
+            let reason = match self.integrity.verify(&message, tag) {
+                Ok(true) => continue,
+                Ok(false) => "signature does not match".to_string(),
+                Err(reason) => reason,
+            };
+            report.failures.push(SignatureFailure {
+                event_id: record.event_id.clone(),
+                reason,
+            });
+        }
+
+        Ok(report)
     }
 
     pub async fn get_audit_trail(
@@ -88,22 +2395,954 @@ impl DigitalLedger {
         entity_id: Option<&str>,
         start_time: Option<chrono::DateTime<chrono::Utc>>,
         end_time: Option<chrono::DateTime<chrono::Utc>>,
+        event_types: Option<&[String]>,
+        tags: Option<&TagFilter>,
+        caller_scopes: Option<&[String]>,
     ) -> Result<Vec<LedgerRecord>, LedgerError> {
         self.storage
-            .query_records(entity_id, start_time, end_time)
+            .query_records(&self.chain_id, entity_id, start_time, end_time, event_types, tags, caller_scopes)
             .await
             .map_err(|e| e.into())
     }
 
+    /// The record `event_id` of this ledger's chain, not of others sharing
+    /// its storage
+    pub async fn get_record(&self, event_id: &str) -> Result<Option<LedgerRecord>, LedgerError> {
+        self.storage.get(&self.chain_id, event_id).await.map_err(|e| e.into())
+    }
+
+    /// Per-currency balances of an account from events timestamped at or
+    /// before `as_of`.
+    pub async fn balance_at(
+        &self,
+        account_id: &str,
+        as_of: chrono::DateTime<chrono::Utc>,
+    ) -> Result<HashMap<String, Money>, LedgerError> {
+        let event_types: Vec<String> = BALANCE_EVENT_TYPES.iter().map(|t| t.to_string()).collect();
+        let records = self
+            .storage
+            .query_records(&self.chain_id, None, None, None, Some(&event_types), None, None)
+            .await?;
+
+        fold_balances(
+            account_id,
+            records
+                .iter()
+                .map(|r| &r.event)
+                .filter(|e| e.get_timestamp() <= as_of),
+        )
+    }
+
+    /// Statement of changes to an account over `(from, to]`.
+    ///
+    /// The opening and closing balances come from `balance_at`; the lines are
+    /// every balance movement timestamped inside the window. A currency whose
+    /// lines do not account for its balance change is reported in
+    /// `discrepancies` and logged, since it points at a bug or tampering.
+    pub async fn reconcile(
+        &self,
+        account_id: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ReconciliationReport, LedgerError> {
+        if from > to {
+            return Err(LedgerError::validation("from", "from must not be after to"));
+        }
+
+        let opening_balances = self.balance_at(account_id, from).await?;
+        let closing_balances = self.balance_at(account_id, to).await?;
+
+        // Reversals need their original transfer, so movements are computed
+        // from the start of the chain and only then cut to the window
+        let event_types: Vec<String> = BALANCE_EVENT_TYPES.iter().map(|t| t.to_string()).collect();
+        let records: Vec<LedgerRecord> = self
+            .storage
+            .query_records(&self.chain_id, None, None, None, Some(&event_types), None, None)
+            .await?
+            .into_iter()
+            .filter(|r| r.event.get_timestamp() <= to)
+            .collect();
+
+        let account_type = balance::account_type(account_id, records.iter().map(|r| &r.event));
+        let convention = account_type.clone().unwrap_or(UNTYPED_ACCOUNT_CONVENTION);
+        let lines = balance_movements(account_id, records.iter().map(|r| &r.event))?
+            .into_iter()
+            .filter_map(|movement| {
+                let record = &records[movement.index];
+                let timestamp = record.event.get_timestamp();
+                if timestamp <= from {
+                    return None;
+                }
+                let direction = convention.direction_of(movement.delta.amount);
+                Some(ReconciliationLine {
+                    event_id: record.event_id.clone(),
+                    event_type: record.event.event_type_name().to_string(),
+                    entity_id: record.event.get_entity_id(),
+                    timestamp,
+                    direction,
+                    amount: Money {
+                        amount: movement.delta.amount.abs(),
+                        ..movement.delta
+                    },
+                })
+            })
+            .collect();
+
+        let mut report = ReconciliationReport {
+            account_id: account_id.to_string(),
+            account_type,
+            from,
+            to,
+            opening_balances,
+            lines,
+            closing_balances,
+            discrepancies: Vec::new(),
+        };
+        report.tie_out()?;
+
+        for discrepancy in &report.discrepancies {
+            error!(
+                "Reconciliation of {} on chain {} is off by {} {} between {} and {}",
+                account_id, self.chain_id, discrepancy.difference, discrepancy.currency, from, to
+            );
+        }
+        Ok(report)
+    }
+
+    /// Accounts created, balances changed and adjustments posted over
+    /// `(from, to]`.
+    ///
+    /// Balances at each end are folded as `balance_at` folds them, for
+    /// every account named by a balance event up to `to`. A currency held at
+    /// only one end is compared as zero at the other, and an account that
+    /// didn't exist at `from` has no balance there.
+    pub async fn diff(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<LedgerDiff, LedgerError> {
+        if from > to {
+            return Err(LedgerError::validation("from", "from must not be after to"));
+        }
+
+        // One read serves both ends, rather than a `balance_at` per account
+        let event_types: Vec<String> = BALANCE_EVENT_TYPES.iter().map(|t| t.to_string()).collect();
+        let records: Vec<LedgerRecord> = self
+            .storage
+            .query_records(&self.chain_id, None, None, None, Some(&event_types), None, None)
+            .await?
+            .into_iter()
+            .filter(|r| r.event.get_timestamp() <= to)
+            .collect();
+        let events_at = |instant: chrono::DateTime<chrono::Utc>| {
+            records
+                .iter()
+                .map(|r| &r.event)
+                .filter(move |e| e.get_timestamp() <= instant)
+        };
+
+        let mut accounts: Vec<&str> = records.iter().flat_map(|r| event_accounts(&r.event)).collect();
+        accounts.sort_unstable();
+        accounts.dedup();
+
+        let mut balance_changes = Vec::new();
+        for account_id in accounts {
+            let before = fold_balances(account_id, events_at(from))?;
+            let mut after = fold_balances(account_id, events_at(to))?;
+            let mut currencies: Vec<&String> = before.keys().chain(after.keys()).collect();
+            currencies.sort_unstable();
+            currencies.dedup();
+            let changed: Vec<String> = currencies
+                .into_iter()
+                .filter(|currency| {
+                    let amount = |balances: &HashMap<String, Money>| {
+                        balances.get(*currency).map(|m| m.amount).unwrap_or_default()
+                    };
+                    amount(&before) != amount(&after)
+                })
+                .cloned()
+                .collect();
+            for currency in changed {
+                balance_changes.push(BalanceChange {
+                    account_id: account_id.to_string(),
+                    before: before.get(&currency).cloned(),
+                    after: after.remove(&currency),
+                    currency,
+                });
+            }
+        }
+
+        let mut accounts_created = Vec::new();
+        let mut adjustments = Vec::new();
+        for record in records.iter().filter(|r| r.event.get_timestamp() > from) {
+            match &record.event {
+                LedgerEvent::AccountCreation(acct) => accounts_created.push(CreatedAccount {
+                    event_id: record.event_id.clone(),
+                    account_id: acct.account_id.clone(),
+                    account_type: acct.account_type.clone(),
+                    initial_balance: acct.initial_balance.clone(),
+                    timestamp: record.event.get_timestamp(),
+                }),
+                LedgerEvent::BalanceAdjustment(adj) => adjustments.push(PostedAdjustment {
+                    event_id: record.event_id.clone(),
+                    adjustment: adj.clone(),
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(LedgerDiff {
+            from,
+            to,
+            accounts_created,
+            balance_changes,
+            adjustments,
+        })
+    }
+
+    /// Appends a reversal of a previously recorded financial transaction,
+    /// linked to the original by its transaction id.
+    pub async fn reverse_transaction(
+        &self,
+        original_id: &str,
+        reason: &str,
+        authorized_by: &str,
+    ) -> Result<String, LedgerError> {
+        let event_types = ["financial_transaction".to_string()];
+        let original = self
+            .storage
+            .query_records(&self.chain_id, Some(original_id), None, None, Some(&event_types), None, None)
+            .await?
+            .into_iter()
+            .find_map(|record| match record.event {
+                LedgerEvent::FinancialTransaction(tx) if tx.transaction_id == original_id => Some(tx),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                LedgerError::validation(
+                    "original_transaction_id",
+                    format!("Original transaction not found: {}", original_id),
+                )
+            })?;
+
+        let reversal = LedgerEvent::TransactionReversal(TransactionReversal {
+            reversal_id: uuid::Uuid::new_v4().to_string(),
+            original_transaction_id: original.transaction_id,
+            reason: reason.to_string(),
+            reversed_amount: original.amount,
+            authorized_by: authorized_by.to_string(),
+            timestamp: self.clock.now(),
+        });
+
+        self.append_event(reversal, None).await
+    }
+
+    /// Writes every record as one JSON line, in chain order.
+    pub async fn export_ndjson<W: Write>(&self, mut writer: W) -> Result<usize, LedgerError> {
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+
+        for record in &records {
+            serde_json::to_writer(&mut writer, record).map_err(StorageError::from)?;
+            writer.write_all(b"\n").map_err(StorageError::from)?;
+        }
+        writer.flush().map_err(StorageError::from)?;
+
+        info!("Exported {} records from chain {}", records.len(), self.chain_id);
+        Ok(records.len())
+    }
+
+    /// Reads records written by `export_ndjson` and stores them unchanged.
+    ///
+    /// Every record is checked before anything is written, so a broken dump
+    /// leaves the ledger untouched: it must belong to this ledger's chain,
+    /// its event id must match its event, a signature this ledger's
+    /// integrity strategy can check must match, and it must link on from
+    /// the record before it, following tombstone bridges, with the next
+    /// sequence number. The first imported record must chain onto the
+    /// current tip, which for an empty ledger means it must be a genesis
+    /// record.
+    pub async fn import_ndjson<R: Read>(&self, reader: R) -> Result<usize, LedgerError> {
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        if *self.in_maintenance.read().await {
+            return Err(LedgerError::TemporarilyReadOnly);
+        }
+
+        let mut lines: Vec<(usize, LedgerRecord)> = Vec::new();
+        for (line_no, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line.map_err(StorageError::from)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: LedgerRecord = serde_json::from_str(&line).map_err(StorageError::from)?;
+
+            if record.chain_id != self.chain_id {
+                return Err(LedgerError::ImportRejected(format!(
+                    "line {}: record {} is from chain {}, not {}",
+                    line_no + 1, record.event_id, record.chain_id, self.chain_id
+                )));
+            }
+            // Storing re-encodes the event, which would lose an older
+            // record's stored bytes and with them the means to check its id
+            if record.schema_version != CURRENT_SCHEMA_VERSION {
+                return Err(LedgerError::ImportRejected(format!(
+                    "line {}: record {} has schema version {}, only version {} can be imported",
+                    line_no + 1, record.event_id, record.schema_version, CURRENT_SCHEMA_VERSION
+                )));
+            }
+            lines.push((line_no + 1, record));
+        }
+
+        // An erased record is imported as its placeholder, vouched for by
+        // an erasure later in the dump
+        let erasures = Erasures::from_records(lines.iter().map(|(_, record)| record));
+        for (line_no, record) in &lines {
+            let broken = match id_break(record, &erasures)? {
+                Some(reason) => Some(reason),
+                None => self.signature_break(record),
+            };
+            if let Some(reason) = broken {
+                return Err(LedgerError::ImportRejected(format!(
+                    "line {}: record {} {}",
+                    line_no, record.event_id, reason
+                )));
+            }
+        }
+        let records: Vec<LedgerRecord> = lines.into_iter().map(|(_, record)| record).collect();
+
+        let _append_guard = self.append_lock.lock().await;
+        // Checked again under the lock, which `seal_and_attest` seals under
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        let tip = match self.storage.get_latest_hash(&self.chain_id).await? {
+            Some(latest) => Some(self.storage.get(&self.chain_id, &latest).await?.ok_or(StorageError::NotFound)?),
+            None => None,
+        };
+        let mut previous = tip.as_ref();
+        for record in &records {
+            if let Some(reason) = link_break(record, previous) {
+                return Err(LedgerError::ImportRejected(format!("record {} {}", record.event_id, reason)));
+            }
+            previous = Some(record);
+        }
+
+        let count = records.len();
+        for record in records {
+            let entries = index_entries(&record.event);
+            self.storage.append_atomic(record, &entries).await?;
+        }
+        self.storage.flush_batch().await?;
+
+        info!("Imported {} records into chain {}", count, self.chain_id);
+        Ok(count)
+    }
+
+    /// Why `record`'s integrity tag doesn't check out. A tag this ledger's
+    /// integrity strategy can't check, such as one from another key, is
+    /// logged and accepted.
+    fn signature_break(&self, record: &LedgerRecord) -> Option<String> {
+        let tag = record.signature.as_ref()?;
+        let message = signing_message(
+            &record.chain_id,
+            &record.event_id,
+            record.previous_hash.as_deref(),
+            record.sequence,
+            record.nonce.as_deref(),
+        );
+        match self.integrity.verify(&message, tag) {
+            Ok(true) => None,
+            Ok(false) => Some("signature does not match".to_string()),
+            Err(reason) => {
+                warn!("Signature of record {} not checked: {}", record.event_id, reason);
+                None
+            }
+        }
+    }
+
+    /// Stores a record appended on another ledger of the same chain as is,
+    /// for keeping a follower in step with its leader.
+    ///
+    /// The record keeps its id, link, sequence, timestamp and violations;
+    /// compliance rules don't run again. Its id must match its event and it
+    /// must link onto this ledger's tip, which makes it byte-for-byte the
+    /// record the leader stored. A signature this ledger's integrity
+    /// strategy can check must match, and one it can't is logged and kept.
+    ///
+    /// A record already stored is a `DuplicateEvent`. One whose previous
+    /// record isn't stored here is a `ReplicationGap`: the records between
+    /// must be replicated first. One linking to an earlier record, or onto
+    /// the tip with another sequence number, is a `ReplicationFork`, and the
+    /// follower can't go on without being rebuilt from the leader. An erased
+    /// record is rejected, as only the erasure after it vouches for it;
+    /// rebuild with `import_ndjson` instead.
+    pub async fn append_replicated(&self, record: LedgerRecord) -> Result<String, LedgerError> {
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        if *self.in_maintenance.read().await {
+            return Err(LedgerError::TemporarilyReadOnly);
+        }
+        if record.chain_id != self.chain_id {
+            return Err(LedgerError::validation(
+                "chain_id",
+                format!("record is from chain {}, not {}", record.chain_id, self.chain_id),
+            ));
+        }
+        if record.schema_version != CURRENT_SCHEMA_VERSION {
+            return Err(LedgerError::ImportRejected(format!(
+                "record {} has schema version {}, only version {} can be replicated",
+                record.event_id, record.schema_version, CURRENT_SCHEMA_VERSION
+            )));
+        }
+        // An erased record's erasure comes after it, so it can't be
+        // vouched for one record at a time and is rejected here
+        let broken = match id_break(&record, &Erasures::default())? {
+            Some(reason) => Some(reason),
+            None => self.signature_break(&record),
+        };
+        if let Some(reason) = broken {
+            return Err(LedgerError::ImportRejected(format!("record {} {}", record.event_id, reason)));
+        }
+
+        let _append_guard = self.append_lock.lock().await;
+        // Checked again under the lock, which `seal_and_attest` seals under
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        if self.storage.get(&self.chain_id, &record.event_id).await?.is_some() {
+            return Err(LedgerError::DuplicateEvent {
+                event_id: record.event_id,
+            });
+        }
+        let tip = match self.storage.get_latest_hash(&self.chain_id).await? {
+            Some(latest) => self.storage.get(&self.chain_id, &latest).await?,
+            None => None,
+        };
+        if let Some(reason) = link_break(&record, tip.as_ref()) {
+            let tip_id = tip.as_ref().map(|t| t.event_id.clone());
+            // Linking anywhere this ledger knows, other than onto the tip
+            // with the right sequence, means the chains have diverged
+            let links_to_tip = tip
+                .as_ref()
+                .is_some_and(|t| record.previous_hash.as_deref() == Some(link_target(t).as_str()));
+            // Links hash in the sequence and nonce, so the record linked
+            // to can't be looked up by id
+            let links_to_stored = match &record.previous_hash {
+                Some(previous) => {
+                    *previous == genesis_seed(&self.chain_id)
+                        || self
+                            .storage
+                            .query_records(&self.chain_id, None, None, None, None, None, None)
+                            .await?
+                            .iter()
+                            .any(|stored| link_target(stored) == *previous)
+                }
+                None => record.sequence.is_none(),
+            };
+            return Err(if links_to_tip || links_to_stored {
+                LedgerError::ReplicationFork {
+                    event_id: record.event_id,
+                    reason,
+                }
+            } else {
+                LedgerError::ReplicationGap {
+                    event_id: record.event_id,
+                    previous_hash: record.previous_hash,
+                    tip: tip_id,
+                }
+            });
+        }
+
+        let entries = index_entries(&record.event);
+        self.storage.append_atomic(record.clone(), &entries).await?;
+        self.storage.flush_batch().await?;
+        self.notify_appended(&record);
+
+        info!("Replicated record {} into chain {}", record.event_id, self.chain_id);
+        Ok(record.event_id)
+    }
+
+    /// Moves records older than `policy.max_age` to the archive sink and
+    /// replaces them in hot storage with a single tombstone record.
+    ///
+    /// The tombstone's event carries the Merkle root over the archived event
+    /// ids, so its hash commits to exactly what was archived. Returns the
+    /// number of records archived.
+    pub async fn apply_retention(&self, policy: &RetentionPolicy) -> Result<usize, LedgerError> {
+        if *self.is_sealed.read().await && !policy.allow_when_sealed {
+            return Err(LedgerError::LedgerSealed);
+        }
+
+        let _append_guard = self.append_lock.lock().await;
+        let cutoff = self.clock.now() - policy.max_age;
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        // The latest record always stays live so new appends link to a real record
+        let archivable = records.len().saturating_sub(1);
+        let archived: Vec<LedgerRecord> = records
+            .into_iter()
+            .take(archivable)
+            .take_while(|r| r.timestamp < cutoff)
+            .collect();
+
+        let (Some(first), Some(last)) = (archived.first(), archived.last()) else {
+            return Ok(0);
+        };
+
+        let archived_ids: Vec<&str> = archived.iter().map(|r| r.event_id.as_str()).collect();
+        let merkle_subroot = hex::encode(merkle_tree::compute_root(&archived_ids));
+
+        let tombstone_event = LedgerEvent::AuditLog(AuditLog {
+            log_id: uuid::Uuid::new_v4().to_string(),
+            action: RETENTION_TOMBSTONE_ACTION.to_string(),
+            actor: "ledger".to_string(),
+            resource: self.chain_id.clone(),
+            changes: serde_json::json!({
+                "first_event_id": first.event_id,
+                "last_event_id": last.event_id,
+                "bridge_to": link_target(last),
+                "archived_count": archived.len(),
+                "merkle_subroot": merkle_subroot,
+                "merkle_tree_version": merkle_tree::MERKLE_TREE_VERSION,
+                "last_sequence": last_sequence(last),
+                "archived_from": first.timestamp,
+                "archived_until": last.timestamp,
+            }),
+            ip_address: None,
+            user_agent: None,
+            timestamp: self.clock.now(),
+        });
+
+        let tombstone = LedgerRecord {
+            event_id: self.codec.hash_event(&tombstone_event)?,
+            event: tombstone_event,
+            metadata: serde_json::Value::Null,
+            // Keeps the tombstone in the archived range's position in the chain
+            timestamp: first.timestamp,
+            previous_hash: first.previous_hash.clone(),
+            chain_id: self.chain_id.clone(),
+            signature: None,
+            violations: Vec::new(),
+            codec: self.codec.codec_id().to_string(),
+            schema_version: CURRENT_SCHEMA_VERSION,
+            legacy_event_bytes: None,
+            // Stands in for the archived range's sequence numbers too
+            sequence: first.sequence,
+            nonce: None,
+            merkle_root_at_append: first.merkle_root_at_append.clone(),
+            visibility: Vec::new(),
+        };
+
+        policy.archive_sink.archive(&archived).await?;
+
+        let archived_ids: Vec<String> = archived.iter().map(|r| r.event_id.clone()).collect();
+        self.storage.replace_with_tombstone(&archived_ids, tombstone).await?;
+        self.storage.rebuild_merkle_tree(&self.chain_id).await?;
+
+        info!("Archived {} records from chain {}", archived.len(), self.chain_id);
+        Ok(archived.len())
+    }
+
+    /// Whether a chain with the given Merkle root and length is a prefix of
+    /// (or equal to) this chain.
+    pub async fn compare_with(&self, other_root: &str, other_length: usize) -> Result<bool, LedgerError> {
+        let ids = self.chain_event_ids().await?;
+        if other_length > ids.len() {
+            return Ok(false);
+        }
+        let prefix: Vec<&str> = ids[..other_length].iter().map(String::as_str).collect();
+        Ok(hex::encode(merkle_tree::compute_root(&prefix)) == other_root)
+    }
+
+    /// Finds the last record shared with another copy of this chain.
+    ///
+    /// The shared prefix length is found by binary search over Merkle roots
+    /// of prefixes, which is valid because two chains that agree on a prefix
+    /// agree on every shorter one. Only the records past that point are
+    /// returned for comparison.
+    pub async fn detect_fork(&self, their_records: &[LedgerRecord]) -> Result<ForkReport, LedgerError> {
+        let ours = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        let our_ids: Vec<&str> = ours.iter().map(|r| r.event_id.as_str()).collect();
+        let their_ids: Vec<&str> = their_records.iter().map(|r| r.event_id.as_str()).collect();
+
+        let (mut low, mut high) = (0, our_ids.len().min(their_ids.len()));
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            if merkle_tree::compute_root(&our_ids[..mid]) == merkle_tree::compute_root(&their_ids[..mid]) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        Ok(ForkReport {
+            common_ancestor: low.checked_sub(1).map(|i| our_ids[i].to_string()),
+            common_length: low,
+            ours: ours[low..].to_vec(),
+            theirs: their_records[low..].to_vec(),
+        })
+    }
+
+    async fn chain_event_ids(&self) -> Result<Vec<String>, LedgerError> {
+        Ok(self
+            .storage
+            .query_records(&self.chain_id, None, None, None, None, None, None)
+            .await?
+            .into_iter()
+            .map(|r| r.event_id)
+            .collect())
+    }
+
+    /// Summary of the chain for monitoring, served from the storage
+    /// backend's counters (see `AppendOnlyStorage::stats`)
+    pub async fn stats(&self) -> Result<LedgerStats, LedgerError> {
+        let stats = self.storage.stats(&self.chain_id).await?;
+        Ok(LedgerStats {
+            chain_id: self.chain_id.clone(),
+            record_count: stats.record_count,
+            event_type_counts: stats.event_type_counts,
+            earliest: stats.earliest,
+            latest: stats.latest,
+            merkle_root: self.storage.get_merkle_root(&self.chain_id).await?,
+            sealed: *self.is_sealed.read().await,
+        })
+    }
+
+    /// Exports every record with the Merkle root over them, signing the root
+    /// with the configured integrity strategy. Only sealed ledgers can be
+    /// exported, so the attested contents can no longer change.
+    pub async fn export_sealed_bundle(&self) -> Result<SealedBundle, LedgerError> {
+        if !*self.is_sealed.read().await {
+            return Err(LedgerError::NotSealed);
+        }
+
+        let records = self.storage.query_records(&self.chain_id, None, None, None, None, None, None).await?;
+        let leaves: Vec<&str> = records.iter().map(|r| r.event_id.as_str()).collect();
+        let merkle_root = hex::encode(merkle_tree::compute_root(&leaves));
+        let root_signature = self
+            .integrity
+            .sign(&root_signing_message(&self.chain_id, &merkle_root, records.len()));
+
+        Ok(SealedBundle {
+            merkle_tree_version: merkle_tree::MERKLE_TREE_VERSION,
+            chain_id: self.chain_id.clone(),
+            exported_at: self.clock.now(),
+            records,
+            merkle_root,
+            root_signature,
+        })
+    }
+
+    /// Crypto-shreds a data subject: destroys the keys their records were
+    /// sealed with and appends an `AuditLog` of the erasure, returning its
+    /// event id.
+    ///
+    /// The ciphertext, event ids and links all stay in place, but the
+    /// subject's records now read back as `ERASED_RECORD_ACTION`
+    /// placeholders. The erasure record lists each one's event id with the
+    /// hash of its ciphertext, and verification checks placeholders against
+    /// it, so `verify_integrity` and Merkle proofs still pass while a
+    /// placeholder no erasure covers is a break. Only records appended with
+    /// `SUBJECT_ID_METADATA` through an `EncryptionLayer` sharing these
+    /// subject keys are erased; the subject's records in other chains
+    /// sharing the keys are erased too, but not covered, so those chains
+    /// stop verifying. The keys are destroyed before the erasure is
+    /// recorded, so if recording fails (for instance on a sealed ledger)
+    /// the erasure has still happened and the records are not covered.
+    /// `subject_id` is written to the chain and should be a pseudonymous id.
+    pub async fn erase_subject(&self, subject_id: &str) -> Result<String, LedgerError> {
+        let subject_keys = self.subject_keys.as_ref().ok_or_else(|| {
+            StorageError::Encryption("no subject keys are configured".to_string())
+        })?;
+        // Read while the keys still open them, which checks every seal
+        let sealed: Vec<String> = self
+            .storage
+            .query_records(&self.chain_id, None, None, None, None, None, None)
+            .await?
+            .into_iter()
+            .filter(|record| !is_erased(record))
+            .filter(|record| record.metadata.get(SUBJECT_ID_METADATA).and_then(|s| s.as_str()) == Some(subject_id))
+            .map(|record| record.event_id)
+            .collect();
+        let keys_destroyed = subject_keys.destroy(subject_id)?;
+        info!("Destroyed {} key(s) of subject {}", keys_destroyed, subject_id);
+
+        let mut erased_records = serde_json::Map::new();
+        for event_id in sealed {
+            let record = self.storage.get(&self.chain_id, &event_id).await?.ok_or(StorageError::NotFound)?;
+            if let Some(ciphertext_sha256) = erased_ciphertext_sha256(&record) {
+                erased_records.insert(event_id, ciphertext_sha256.into());
+            }
+        }
+
+        let erased_at = self.clock.now();
+        let erasure = LedgerEvent::AuditLog(AuditLog {
+            log_id: uuid::Uuid::new_v4().to_string(),
+            action: SUBJECT_ERASURE_ACTION.to_string(),
+            actor: "ledger".to_string(),
+            resource: self.chain_id.clone(),
+            changes: serde_json::json!({
+                "subject_id": subject_id,
+                "keys_destroyed": keys_destroyed,
+                "erased_records": erased_records,
+                "erased_at": erased_at,
+            }),
+            ip_address: None,
+            user_agent: None,
+            timestamp: erased_at,
+        });
+        self.append_event(erasure, None).await
+    }
+
     pub async fn seal_ledger(&self) -> Result<(), LedgerError> {
         let mut sealed = self.is_sealed.write().await;
         *sealed = true;
-        info!("Ledger sealed at: {}", chrono::Utc::now());
+        info!("Ledger sealed at: {}", self.clock.now());
         Ok(())
     }
 
+    /// Appends like `append_event`, except that with quarantine storage
+    /// configured an event rejected for compliance is stored on the
+    /// quarantine chain, with its violations, for manual review instead of
+    /// failing. Any other rejection is still an error. The rejection is
+    /// recorded and reported to observers as with `append_event`, so the
+    /// receipt carries the `ComplianceAlert` id when rejections are
+    /// recorded. Metadata is enriched as `append_event` enriches it before
+    /// it is quarantined, so client `SERVER_METADATA` reaches neither chain.
+    pub async fn append_or_quarantine(
+        &self,
+        event: LedgerEvent,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<AppendOutcome, LedgerError> {
+        let Some(quarantine) = &self.quarantine_storage else {
+            return self.append_event(event, metadata).await.map(AppendOutcome::Appended);
+        };
+
+        let (violations, alert_event_id) = match self.append_event(event.clone(), metadata.clone()).await {
+            Ok(event_id) => return Ok(AppendOutcome::Appended(event_id)),
+            Err(LedgerError::ComplianceViolation { violations, alert_event_id }) => {
+                (violations, alert_event_id)
+            }
+            Err(err) => return Err(err),
+        };
+
+        // Client metadata never carries server fields, on the quarantine
+        // chain any more than on the main one
+        let metadata = self.enrich_metadata(&event, metadata)?;
+        let event_hash = self.codec.hash_event(&event)?;
+        let _append_guard = self.append_lock.lock().await;
+        // Quarantining the same event twice keeps the first review item
+        let chain_id = quarantine_chain_id(&self.chain_id);
+        let record = match quarantine.get(&chain_id, &event_hash).await? {
+            Some(existing) => existing,
+            None => {
+                self.store_record_in(
+                    quarantine.as_ref(),
+                    &chain_id,
+                    &event,
+                    event_hash,
+                    metadata,
+                    violations,
+                    Vec::new(),
+                )
+                .await?
+            }
+        };
+        info!("Quarantined {} for review as {}", event.event_type_name(), record.event_id);
+
+        Ok(AppendOutcome::Quarantined(QuarantineReceipt {
+            quarantine_event_id: record.event_id,
+            violations: record.violations,
+            alert_event_id,
+            quarantined_at: record.timestamp,
+        }))
+    }
+
+    /// Moves a reviewed event from the quarantine chain into the main
+    /// chain, returning its event id there.
+    ///
+    /// The event skips the checks it was quarantined for: compliance,
+    /// timestamp bounds (review takes time), actor signature and adjustment
+    /// authorization, with `approver` answering for it instead. Sealing and
+    /// maintenance mode still apply. The quarantine violations are kept
+    /// with the new record, and who released it, when and from which
+    /// quarantine record is added to its metadata under
+    /// `QUARANTINE_RELEASE_METADATA`, and its `SERVER_METADATA` fields are
+    /// stamped afresh as on any append. The quarantine record stays where
+    /// it is; releasing an event already in the main chain is a
+    /// `DuplicateEvent`, and an id that isn't on this ledger's quarantine
+    /// chain is `NotQuarantined`.
+    pub async fn release_from_quarantine(
+        &self,
+        quarantine_event_id: &str,
+        approver: &str,
+    ) -> Result<String, LedgerError> {
+        if approver.trim().is_empty() {
+            return Err(LedgerError::validation("approver", "approver must not be empty"));
+        }
+        let not_quarantined = || LedgerError::NotQuarantined {
+            event_id: quarantine_event_id.to_string(),
+        };
+        let quarantine = self.quarantine_storage.as_ref().ok_or_else(not_quarantined)?;
+        let quarantined = quarantine
+            .get(&quarantine_chain_id(&self.chain_id), quarantine_event_id)
+            .await?
+            .ok_or_else(not_quarantined)?;
+        if quarantined.chain_id != quarantine_chain_id(&self.chain_id) {
+            return Err(not_quarantined());
+        }
+
+        let released_at = self.clock.now();
+        let metadata = match quarantined.metadata {
+            serde_json::Value::Null => serde_json::Map::new(),
+            serde_json::Value::Object(fields) => fields,
+            other => serde_json::Map::from_iter([("metadata".to_string(), other)]),
+        };
+        // Server fields are stamped afresh for the main chain, replacing
+        // whatever the quarantine record carries under them
+        let mut metadata = match self.enrich_metadata(&quarantined.event, Some(serde_json::Value::Object(metadata)))? {
+            Some(serde_json::Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert(
+            QUARANTINE_RELEASE_METADATA.to_string(),
+            serde_json::json!({
+                "quarantine_event_id": quarantined.event_id,
+                "approver": approver,
+                "released_at": released_at,
+            }),
+        );
+
+        let event_hash = record_id(self.codec.as_ref(), &quarantined.event, &quarantined.visibility)?;
+        let _append_guard = self.append_lock.lock().await;
+        if *self.is_sealed.read().await {
+            return Err(LedgerError::LedgerSealed);
+        }
+        if *self.in_maintenance.read().await {
+            return Err(LedgerError::TemporarilyReadOnly);
+        }
+        if self.storage.get(&self.chain_id, &event_hash).await?.is_some() {
+            return Err(LedgerError::DuplicateEvent { event_id: event_hash });
+        }
+        let record = self
+            .store_record(
+                &quarantined.event,
+                event_hash,
+                Some(serde_json::Value::Object(metadata)),
+                quarantined.violations,
+                quarantined.visibility,
+            )
+            .await?;
+        info!("{} released {} from quarantine", approver, record.event_id);
+        self.notify_appended(&record);
+
+        Ok(record.event_id)
+    }
+
+    /// Seals the ledger and signs its final Merkle root, record count and
+    /// head with `signer` in one step. The append lock is held throughout,
+    /// and appends re-check the seal once they hold it, so no record can
+    /// land between sealing and computing the root. Calling this on an
+    /// already sealed ledger attests its state as it stands.
+    pub async fn seal_and_attest(&self, signer: &Ed25519KeyPair) -> Result<SealAttestation, LedgerError> {
+        let _append_guard = self.append_lock.lock().await;
+        *self.is_sealed.write().await = true;
+        let sealed_at = self.clock.now();
+
+        let ids = self.chain_event_ids().await?;
+        let leaves: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let merkle_root = hex::encode(merkle_tree::compute_root(&leaves));
+        let head_event_id = ids.last().cloned();
+        let message = seal_signing_message(
+            &self.chain_id,
+            &merkle_root,
+            ids.len(),
+            head_event_id.as_deref(),
+            sealed_at,
+        );
+        let signature = format!("{}:{}", ED25519_SCHEME, hex::encode(signer.sign(&message).as_ref()));
+        info!("Ledger sealed and attested at {} with {} records", sealed_at, ids.len());
+
+        Ok(SealAttestation {
+            merkle_tree_version: merkle_tree::MERKLE_TREE_VERSION,
+            chain_id: self.chain_id.clone(),
+            merkle_root,
+            record_count: ids.len(),
+            head_event_id,
+            sealed_at,
+            signature,
+        })
+    }
+
+    /// Pauses appends and imports, for example during compaction or a
+    /// backup, rejecting them with `TemporarilyReadOnly` until turned off.
+    /// Unlike sealing this is not recorded in the chain. Retention still
+    /// runs, so it can be the maintenance being done.
+    pub async fn set_maintenance_mode(&self, enabled: bool) {
+        let mut in_maintenance = self.in_maintenance.write().await;
+        if *in_maintenance != enabled {
+            *in_maintenance = enabled;
+            info!(
+                "Ledger {} maintenance mode {} at: {}",
+                self.chain_id,
+                if enabled { "entered" } else { "left" },
+                self.clock.now()
+            );
+        }
+    }
+
     pub async fn get_merkle_root(&self) -> Result<String, LedgerError> {
-        self.storage.get_merkle_root().await.map_err(|e| e.into())
+        self.storage.get_merkle_root(&self.chain_id).await.map_err(|e| e.into())
+    }
+
+    /// Inclusion proof for a record against the current Merkle root, or
+    /// `None` if the record is not in the chain
+    pub async fn get_merkle_proof(&self, event_id: &str) -> Result<Option<merkle_tree::MerkleProof>, LedgerError> {
+        let ids = self.chain_event_ids().await?;
+        let Some(leaf_index) = ids.iter().position(|id| id == event_id) else {
+            return Ok(None);
+        };
+        let leaves: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let path = merkle_tree::inclusion_proof(&leaves, leaf_index).unwrap_or_default();
+
+        Ok(Some(merkle_tree::MerkleProof {
+            event_id: event_id.to_string(),
+            leaf_index,
+            tree_size: leaves.len(),
+            path: path.iter().map(hex::encode).collect(),
+            root: hex::encode(merkle_tree::compute_root(&leaves)),
+        }))
+    }
+
+    /// Proof that the Merkle tree over the first `from_size` records is a
+    /// prefix of the tree over the first `to_size`, so a root a client
+    /// trusts (such as a record's `merkle_root_at_append`) can be checked
+    /// against a later one with `merkle_tree::verify_consistency`. `None`
+    /// if either size is beyond the chain or `from_size` exceeds `to_size`.
+    pub async fn get_consistency_proof(
+        &self,
+        from_size: usize,
+        to_size: usize,
+    ) -> Result<Option<merkle_tree::ConsistencyProof>, LedgerError> {
+        let ids = self.chain_event_ids().await?;
+        if from_size > to_size || to_size > ids.len() {
+            return Ok(None);
+        }
+        let leaves: Vec<&str> = ids[..to_size].iter().map(String::as_str).collect();
+        let path = merkle_tree::consistency_proof(&leaves, from_size).unwrap_or_default();
+
+        Ok(Some(merkle_tree::ConsistencyProof {
+            from_size,
+            to_size,
+            from_root: hex::encode(merkle_tree::compute_root(&leaves[..from_size])),
+            to_root: hex::encode(merkle_tree::compute_root(&leaves)),
+            path: path.iter().map(hex::encode).collect(),
+        }))
+    }
+
+    pub async fn rebuild_merkle_tree(&self) -> Result<String, LedgerError> {
+        self.storage.rebuild_merkle_tree(&self.chain_id).await.map_err(|e| e.into())
     }
 }
 
@@ -116,4 +3355,181 @@ pub struct LedgerRecord {
     pub previous_hash: Option<String>,
     pub chain_id: String,
     pub signature: Option<String>,
+    /// Non-blocking violations flagged at append time, kept with the record
+    #[serde(default)]
+    pub violations: Vec<Violation>,
+    /// Id of the `RecordCodec` the event was encoded and hashed with
+    #[serde(default = "default_codec_id")]
+    pub codec: String,
+    /// Position in the chain, counting from 0 at genesis. Gaps reveal
+    /// deleted records even when the remaining chain was re-linked. Absent
+    /// on records written before sequences existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+    /// Random value hashed into the next record's link and into the
+    /// integrity tag, so neither can be precomputed from the predictable
+    /// chain fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Schema version the event was stored with. `event` is always in the
+    /// current shape; see `core::schema` for how the id of an older record
+    /// is checked.
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u16,
+    /// Hex of the bytes the event was stored as, kept only when `event` was
+    /// migrated from an older schema version. Its id was hashed from these.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legacy_event_bytes: Option<String>,
+    /// Hex Merkle root over the records before this one when it was
+    /// appended, so a light client holding only this record can check it
+    /// against a later trusted root with `get_consistency_proof`. Retention
+    /// rewrites the leaves it archives, so roots recorded before a
+    /// retention run no longer match. Absent on records written before this
+    /// was kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merkle_root_at_append: Option<String>,
+    /// Scopes allowed to read the record, sorted; empty means anyone may.
+    /// Covered by the event id, see `record_id`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visibility: Vec<String>,
+}
+
+/// Outcome of `try_append`
+enum Appended<'a> {
+    /// Stored, with the append lock still held
+    Stored(LedgerRecord, AsyncMutexGuard<'a, ()>),
+    /// Appended under the same idempotency key by a concurrent caller
+    Existing(String),
+}
+
+/// An `append_batch` entry after checking: already appended under its
+/// idempotency key, or new with its non-blocking violations
+enum BatchItem {
+    Existing(String),
+    New(Vec<Violation>),
+}
+
+/// Result of checking part or all of the chain
+enum Verification {
+    /// Chain head the check reached, `None` for an empty chain
+    Passed(Option<LedgerRecord>),
+    Failed,
+}
+
+/// Outcome of `append_or_quarantine`
+#[derive(Debug, Clone)]
+pub enum AppendOutcome {
+    /// Event id in the main chain
+    Appended(String),
+    Quarantined(QuarantineReceipt),
+}
+
+/// An event held on the quarantine chain pending
+/// `DigitalLedger::release_from_quarantine`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct QuarantineReceipt {
+    /// Event id on the quarantine chain, which is also the id the event
+    /// gets in the main chain once released
+    pub quarantine_event_id: String,
+    pub violations: Vec<Violation>,
+    /// `ComplianceAlert` recording the rejection, when the ledger records
+    /// rejections
+    pub alert_event_id: Option<String>,
+    pub quarantined_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Id a record of `event` restricted to `visibility` is stored under: the
+/// event hash, bound to the scopes when there are any
+pub fn record_id(codec: &dyn RecordCodec, event: &LedgerEvent, visibility: &[String]) -> Result<String, LedgerError> {
+    Ok(visibility_bound_id(codec.hash_event(event)?, visibility))
+}
+
+/// Chain id of a ledger's quarantine chain
+pub fn quarantine_chain_id(chain_id: &str) -> String {
+    format!("{}/quarantine", chain_id)
+}
+
+/// Outcome of `append_batch_lenient`
+#[derive(Debug, Default)]
+pub struct BatchResult {
+    /// Event ids of the accepted events, in input order. Events already in
+    /// the ledger under their idempotency key are included.
+    pub appended: Vec<String>,
+    /// Input index and reason of each rejected event, in input order
+    pub rejected: Vec<(usize, LedgerError)>,
+}
+
+impl BatchResult {
+    pub fn is_complete(&self) -> bool {
+        self.rejected.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LedgerStats {
+    pub chain_id: String,
+    pub record_count: usize,
+    pub event_type_counts: HashMap<String, usize>,
+    pub earliest: Option<chrono::DateTime<chrono::Utc>>,
+    pub latest: Option<chrono::DateTime<chrono::Utc>>,
+    pub merkle_root: String,
+    pub sealed: bool,
+}
+
+/// Outcome of `verify_ndjson_stream` and `DigitalLedger::verify_integrity_parallel`
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ChainVerification {
+    /// Records that passed, up to the first break
+    pub records_checked: usize,
+    /// Merkle root over the event ids of the records that passed
+    pub merkle_root: String,
+    pub first_break: Option<ChainBreak>,
+}
+
+impl ChainVerification {
+    pub fn is_valid(&self) -> bool {
+        self.first_break.is_none()
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ChainBreak {
+    /// 1-based line of the dump, or position in the chain
+    pub line: usize,
+    /// Empty when the line is not a record
+    pub event_id: String,
+    pub reason: String,
+}
+
+/// Where two copies of a chain diverge.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ForkReport {
+    /// Last event id both chains share, `None` if they differ from genesis
+    pub common_ancestor: Option<String>,
+    /// Number of records both chains share
+    pub common_length: usize,
+    /// Our records after the common ancestor
+    pub ours: Vec<LedgerRecord>,
+    /// Their records after the common ancestor
+    pub theirs: Vec<LedgerRecord>,
+}
+
+impl ForkReport {
+    /// Both chains have records the other lacks. When only one side has extra
+    /// records the other is simply behind.
+    pub fn is_forked(&self) -> bool {
+        !self.ours.is_empty() && !self.theirs.is_empty()
+    }
+}
+
+impl HasMetadata for LedgerRecord {
+    fn metadata_value(&self) -> &serde_json::Value {
+        &self.metadata
+    }
+}
+
+impl LedgerRecord {
+    pub fn idempotency_key(&self) -> Option<&str> {
+        self.metadata.get(IDEMPOTENCY_KEY_METADATA).and_then(|k| k.as_str())
+    }
 }
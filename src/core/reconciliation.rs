@@ -0,0 +1,154 @@
+use crate::core::balance::UNTYPED_ACCOUNT_CONVENTION;
+use crate::core::event::{AccountType, BalanceAdjustment, Money, MoneyError};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use crate::core::event::EntryDirection;
+
+/// One movement of the account's balance inside the reconciled window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationLine {
+    pub event_id: String,
+    pub event_type: String,
+    pub entity_id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Side of the account the movement posted to, by the report's
+    /// `account_type`
+    pub direction: EntryDirection,
+    /// Unsigned amount; `direction` carries the sign
+    pub amount: Money,
+}
+
+/// A currency whose opening balance plus its lines does not equal its
+/// closing balance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationDiscrepancy {
+    pub currency: String,
+    pub opening: Decimal,
+    pub net_movement: Decimal,
+    pub closing: Decimal,
+    /// `closing - (opening + net_movement)`
+    pub difference: Decimal,
+}
+
+/// Statement of changes to one account between two instants.
+///
+/// The window is `(from, to]`: the opening balance includes everything at or
+/// before `from`, the closing balance everything at or before `to`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub account_id: String,
+    /// Type recorded when the account was created, which sets whether a
+    /// debit or a credit raises its balance; unknown accounts follow
+    /// `UNTYPED_ACCOUNT_CONVENTION`
+    #[serde(default)]
+    pub account_type: Option<AccountType>,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    pub opening_balances: HashMap<String, Money>,
+    pub lines: Vec<ReconciliationLine>,
+    pub closing_balances: HashMap<String, Money>,
+    /// Empty when every currency ties out
+    pub discrepancies: Vec<ReconciliationDiscrepancy>,
+}
+
+impl ReconciliationReport {
+    pub fn is_reconciled(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    /// Checks opening + lines = closing for every currency that appears in
+    /// any of the three. Fails with `MoneyError::Overflow` if a sum is
+    /// outside `Decimal`'s range.
+    pub(crate) fn tie_out(&mut self) -> Result<(), MoneyError> {
+        let convention = self.account_type.clone().unwrap_or(UNTYPED_ACCOUNT_CONVENTION);
+        let mut net: HashMap<&str, Decimal> = HashMap::new();
+        for line in &self.lines {
+            let currency = line.amount.currency_code.as_str();
+            let signed = convention.signed(line.direction, line.amount.amount);
+            let total = net.entry(currency).or_default();
+            *total = total.checked_add(signed).ok_or_else(|| MoneyError::overflow(currency))?;
+        }
+
+        let mut currencies: Vec<&str> = self
+            .opening_balances
+            .keys()
+            .chain(self.closing_balances.keys())
+            .map(String::as_str)
+            .chain(net.keys().copied())
+            .collect();
+        currencies.sort_unstable();
+        currencies.dedup();
+
+        let amount_in = |balances: &HashMap<String, Money>, currency: &str| {
+            balances.get(currency).map(|m| m.amount).unwrap_or_default()
+        };
+        let mut discrepancies = Vec::new();
+        for currency in currencies {
+            let opening = amount_in(&self.opening_balances, currency);
+            let closing = amount_in(&self.closing_balances, currency);
+            let net_movement = net.get(currency).copied().unwrap_or_default();
+            let difference = opening
+                .checked_add(net_movement)
+                .and_then(|expected| closing.checked_sub(expected))
+                .ok_or_else(|| MoneyError::overflow(currency))?;
+            if !difference.is_zero() {
+                discrepancies.push(ReconciliationDiscrepancy {
+                    currency: currency.to_string(),
+                    opening,
+                    net_movement,
+                    closing,
+                    difference,
+                });
+            }
+        }
+        self.discrepancies = discrepancies;
+        Ok(())
+    }
+}
+
+/// Change in the state of the ledger between two instants, from
+/// `DigitalLedger::diff`.
+///
+/// Like a reconciliation, the window is `(from, to]`: balances at `from`
+/// include everything at or before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerDiff {
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    /// Accounts whose `AccountCreation` falls in the window, in event order
+    pub accounts_created: Vec<CreatedAccount>,
+    /// Per account and currency, ordered by both, every balance that
+    /// differs between the two instants
+    pub balance_changes: Vec<BalanceChange>,
+    /// Balance adjustments posted in the window, in event order
+    pub adjustments: Vec<PostedAdjustment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedAccount {
+    pub event_id: String,
+    pub account_id: String,
+    pub account_type: AccountType,
+    pub initial_balance: Money,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// One account's balance in one currency at both ends of a `LedgerDiff`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub account_id: String,
+    pub currency: String,
+    /// `None` when the account had no balance in the currency at `from`,
+    /// for instance because it didn't exist yet
+    pub before: Option<Money>,
+    /// `None` when the account has no balance in the currency at `to`
+    pub after: Option<Money>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostedAdjustment {
+    pub event_id: String,
+    pub adjustment: BalanceAdjustment,
+}
@@ -0,0 +1,139 @@
+//! Versioning of stored event payloads.
+//!
+//! Migrations never rewrite stored records. A record keeps the bytes and the
+//! `schema_version` it was written with; `migrate_record` upgrades the
+//! payload to the current `LedgerEvent` shape only for the in-memory view.
+//! Hash links, integrity tags and Merkle roots are computed over event ids,
+//! not event bytes, so they keep verifying after the event type changes.
+//!
+//! An older record's migrated event no longer encodes to the bytes its id
+//! was hashed from, so storage hands those bytes up with it as
+//! `LedgerRecord::legacy_event_bytes`. Its id is recomputed from them with
+//! the record's codec, and they must migrate to the event it is read as.
+//! Marking a current record as an older version therefore doesn't skip the
+//! check, and an older record without stored bytes fails it.
+use crate::core::event::{LedgerEvent, Money};
+use thiserror::Error;
+
+/// Version of the `LedgerEvent` shape written by this build
+pub const CURRENT_SCHEMA_VERSION: u16 = 3;
+
+/// A migration step upgrades a payload by exactly one version
+type MigrationStep = fn(serde_json::Value) -> Result<serde_json::Value, String>;
+
+/// `MIGRATIONS[i]` upgrades a version `i + 1` payload to version `i + 2`.
+/// Bumping `CURRENT_SCHEMA_VERSION` means appending a step here.
+const MIGRATIONS: &[MigrationStep] = &[snake_case_enums, canonical_amounts];
+
+const _: () = assert!(MIGRATIONS.len() + 1 == CURRENT_SCHEMA_VERSION as usize);
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("Unsupported schema version {0}; this build reads up to {CURRENT_SCHEMA_VERSION}")]
+    UnsupportedVersion(u16),
+    #[error("Migrating from schema version {from} failed: {reason}")]
+    StepFailed { from: u16, reason: String },
+    #[error("Migrated payload does not deserialize: {0}")]
+    Deserialize(#[from] serde_json::Error),
+}
+
+/// A cloned `Deserialize` error keeps its message but not its position
+impl Clone for MigrationError {
+    fn clone(&self) -> Self {
+        match self {
+            MigrationError::UnsupportedVersion(version) => MigrationError::UnsupportedVersion(*version),
+            MigrationError::StepFailed { from, reason } => MigrationError::StepFailed {
+                from: *from,
+                reason: reason.clone(),
+            },
+            MigrationError::Deserialize(e) => MigrationError::Deserialize(serde::de::Error::custom(e)),
+        }
+    }
+}
+
+/// Version 2 serializes `AlertSeverity`, `AccountType`, `ComplianceLevel`
+/// and `AdjustmentReason` in snake_case instead of by variant name
+fn snake_case_enums(mut payload: serde_json::Value) -> Result<serde_json::Value, String> {
+    let fields = match payload.get("event_type").and_then(|t| t.as_str()) {
+        Some("compliance_alert") => &["severity"][..],
+        Some("account_creation") => &["account_type", "compliance_level"][..],
+        Some("balance_adjustment") => &["reason"][..],
+        _ => return Ok(payload),
+    };
+    for name in fields {
+        let Some(value) = payload.get_mut(*name) else {
+            continue;
+        };
+        let variant = value
+            .as_str()
+            .ok_or_else(|| format!("{} is not a string", name))?;
+        *value = serde_json::Value::String(to_snake_case(variant));
+    }
+    Ok(payload)
+}
+
+/// Version 3 writes `Money.amount` as its canonical string and always
+/// fills in `precision`. Earlier payloads wrote amounts however
+/// `rust_decimal` did, so every `Money` object in them, at any depth, is
+/// rewritten in the version 3 form. Their ids were hashed from the old
+/// form, which is why the version changed.
+fn canonical_amounts(mut payload: serde_json::Value) -> Result<serde_json::Value, String> {
+    canonicalize_money(&mut payload)?;
+    Ok(payload)
+}
+
+fn canonicalize_money(value: &mut serde_json::Value) -> Result<(), String> {
+    match value {
+        serde_json::Value::Object(fields) if fields.contains_key("amount") && fields.contains_key("currency_code") => {
+            let money: Money = serde_json::from_value(value.clone()).map_err(|e| format!("invalid money: {}", e))?;
+            *value = serde_json::to_value(money).map_err(|e| e.to_string())?;
+        }
+        serde_json::Value::Object(fields) => {
+            for field in fields.values_mut() {
+                canonicalize_money(field)?;
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                canonicalize_money(item)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn to_snake_case(variant: &str) -> String {
+    let mut snake = String::with_capacity(variant.len() + 4);
+    for (i, c) in variant.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Records written before `schema_version` existed are version 1
+pub fn default_schema_version() -> u16 {
+    1
+}
+
+/// Upgrades a stored event payload written at `schema_version` to the
+/// current shape and deserializes it
+pub fn migrate_record(
+    schema_version: u16,
+    mut payload: serde_json::Value,
+) -> Result<LedgerEvent, MigrationError> {
+    if schema_version == 0 || schema_version > CURRENT_SCHEMA_VERSION {
+        return Err(MigrationError::UnsupportedVersion(schema_version));
+    }
+    for (step, from) in MIGRATIONS[schema_version as usize - 1..].iter().zip(schema_version..) {
+        payload = step(payload).map_err(|reason| MigrationError::StepFailed { from, reason })?;
+    }
+    Ok(serde_json::from_value(payload)?)
+}
@@ -0,0 +1,190 @@
+use crate::compliance::validator::{RedactionPolicy, RuleSeverity};
+use crate::core::event::{AlertSeverity, ComplianceAlert, LedgerEvent};
+use crate::core::{DigitalLedger, LedgerObserver, LedgerRecord};
+use ring::hmac;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Header carrying `sha256=<hex HMAC of the body>`
+pub const SIGNATURE_HEADER: &str = "X-Ledger-Signature";
+
+/// Rule id of the alerts recorded when a delivery finally fails
+pub const DELIVERY_FAILURE_RULE_ID: &str = "WEBHOOK_DELIVERY_FAILED";
+
+/// Which appended records are delivered. A record matches if any configured
+/// criterion matches; an empty trigger matches nothing.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookTrigger {
+    pub event_types: Vec<String>,
+    pub rule_ids: Vec<String>,
+    /// Deliver records carrying a violation at or above this severity
+    pub min_severity: Option<RuleSeverity>,
+}
+
+impl WebhookTrigger {
+    pub fn matches(&self, record: &LedgerRecord) -> bool {
+        self.event_types.iter().any(|t| t == record.event.event_type_name())
+            || record.violations.iter().any(|v| {
+                self.rule_ids.contains(&v.rule_id)
+                    || self.min_severity.as_ref().is_some_and(|min| v.severity >= *min)
+            })
+    }
+}
+
+pub struct WebhookConfig {
+    pub url: String,
+    pub secret: Vec<u8>,
+    pub trigger: WebhookTrigger,
+    pub queue_capacity: usize,
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    /// Record a `ComplianceAlert` on this ledger when a delivery exhausts its retries
+    pub failure_ledger: Option<Weak<DigitalLedger>>,
+    /// Applied to the delivered record's violation evidence
+    pub redaction: Option<RedactionPolicy>,
+}
+
+impl WebhookConfig {
+    pub fn new(url: impl Into<String>, secret: impl Into<Vec<u8>>, trigger: WebhookTrigger) -> Self {
+        Self {
+            url: url.into(),
+            secret: secret.into(),
+            trigger,
+            queue_capacity: 1024,
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            failure_ledger: None,
+            redaction: None,
+        }
+    }
+}
+
+/// Observer that POSTs matching records to a webhook.
+///
+/// Records are queued on a bounded channel and delivered by a background
+/// task, so a slow endpoint never blocks appends; when the queue is full the
+/// record is dropped and a warning logged. Each request body is signed with
+/// HMAC-SHA256 over the shared secret.
+pub struct WebhookObserver {
+    trigger: WebhookTrigger,
+    queue: mpsc::Sender<LedgerRecord>,
+}
+
+impl WebhookObserver {
+    /// Spawns the delivery task on the current tokio runtime
+    pub fn spawn(config: WebhookConfig) -> Arc<Self> {
+        let (queue, receiver) = mpsc::channel(config.queue_capacity);
+        let trigger = config.trigger.clone();
+        tokio::spawn(deliver_loop(config, receiver));
+        Arc::new(Self { trigger, queue })
+    }
+}
+
+impl LedgerObserver for WebhookObserver {
+    fn on_appended(&self, record: &LedgerRecord) -> anyhow::Result<()> {
+        if !self.trigger.matches(record) {
+            return Ok(());
+        }
+        if let Err(mpsc::error::TrySendError::Full(record)) = self.queue.try_send(record.clone()) {
+            warn!("Webhook queue full, dropping delivery of {}", record.event_id);
+        }
+        Ok(())
+    }
+}
+
+async fn deliver_loop(config: WebhookConfig, mut receiver: mpsc::Receiver<LedgerRecord>) {
+    let client = reqwest::Client::new();
+    let key = hmac::Key::new(hmac::HMAC_SHA256, &config.secret);
+
+    while let Some(record) = receiver.recv().await {
+        let mut payload = record.clone();
+        if let Some(policy) = &config.redaction {
+            payload.violations = payload.violations.iter().map(|v| v.redact(policy)).collect();
+        }
+        let body = match serde_json::to_vec(&serde_json::json!({
+            "chain_id": record.chain_id,
+            "event_id": record.event_id,
+            "event_type": record.event.event_type_name(),
+            "record": payload,
+        })) {
+            Ok(body) => body,
+            Err(e) => {
+                error!("Failed to serialize webhook payload for {}: {}", record.event_id, e);
+                continue;
+            }
+        };
+        let signature = format!("sha256={}", hex::encode(hmac::sign(&key, &body).as_ref()));
+
+        if let Err(e) = deliver(&client, &config, &body, &signature).await {
+            error!(
+                "Webhook delivery of {} to {} failed after {} retries: {}",
+                record.event_id, config.url, config.max_retries, e
+            );
+            record_failure(&config, &record, &e).await;
+        }
+    }
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    config: &WebhookConfig,
+    body: &[u8],
+    signature: &str,
+) -> Result<(), String> {
+    let mut backoff = config.initial_backoff;
+    let mut attempt = 0;
+
+    loop {
+        let result = client
+            .post(&config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(body.to_vec())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        match result {
+            Ok(_) => return Ok(()),
+            Err(e) if attempt >= config.max_retries => return Err(e.to_string()),
+            Err(e) => {
+                warn!("Webhook delivery attempt {} failed: {}", attempt + 1, e);
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+async fn record_failure(config: &WebhookConfig, record: &LedgerRecord, reason: &str) {
+    // Never alert about a failed alert, or an unreachable endpoint would loop
+    if let LedgerEvent::ComplianceAlert(alert) = &record.event {
+        if alert.rule_id == DELIVERY_FAILURE_RULE_ID {
+            return;
+        }
+    }
+    let Some(ledger) = config.failure_ledger.as_ref().and_then(Weak::upgrade) else {
+        return;
+    };
+
+    let alert = LedgerEvent::ComplianceAlert(ComplianceAlert {
+        alert_id: uuid::Uuid::new_v4().to_string(),
+        rule_id: DELIVERY_FAILURE_RULE_ID.to_string(),
+        severity: AlertSeverity::High,
+        description: format!("Webhook delivery to {} failed: {}", config.url, reason),
+        affected_entities: vec![record.event_id.clone()],
+        evidence: serde_json::json!({
+            "url": config.url,
+            "event_id": record.event_id,
+            "error": reason,
+        }),
+        timestamp: chrono::Utc::now(),
+    });
+
+    if let Err(e) = ledger.append_event(alert, None).await {
+        error!("Failed to record webhook delivery failure for {}: {}", record.event_id, e);
+    }
+}
@@ -1,7 +1,8 @@
 use gitdigital_ledger_core::{
     api,
-    core::DigitalLedger,
+    core::{DigitalLedger, LedgerConfig},
     storage::{append_only::PostgresStorage, AppendOnlyStorage},
+    compliance::context::StorageContextProvider,
     compliance::validator::{ComplianceValidator, AmountLimitRule, SanctionedCountriesRule},
 };
 use std::sync::Arc;
@@ -16,13 +17,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = load_config().await?;
     
     // Initialize storage
-    let storage = PostgresStorage::new(&config.database_url, "ledger_events")
-        .await
-        .map_err(|e| format!("Failed to initialize storage: {}", e))?;
+    let storage: Arc<dyn AppendOnlyStorage> = Arc::new(
+        PostgresStorage::new(&config.database_url, "ledger_events")
+            .await
+            .map_err(|e| format!("Failed to initialize storage: {}", e))?,
+    );
+    
+    let chain_id = "main_ledger";
     
     // Initialize compliance validator
     let mut validator = ComplianceValidator::new();
     
+    // Give history-dependent rules the ledger's recent activity
+    validator.set_context_provider(Arc::new(StorageContextProvider::new(
+        storage.clone(),
+        chain_id,
+        chrono::Duration::days(1),
+    )));
+    
     // Add compliance rules
     validator.add_rule(Box::new(AmountLimitRule::new(
         rust_decimal::Decimal::new(1000000, 0), // 1,000,000
@@ -35,9 +47,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Create ledger
     let ledger = DigitalLedger::new(
-        Arc::new(storage),
+        storage,
         Arc::new(validator),
-        "main_ledger".to_string(),
+        LedgerConfig::new(chain_id),
     )
     .await
     .map_err(|e| format!("Failed to create ledger: {}", e))?;
@@ -1,21 +1,295 @@
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use sqlx::Row;
 use thiserror::Error;
+use crate::core::ledger::{link_target, links_to_genesis, TAG_INDEX};
+use crate::core::schema::{migrate_record, CURRENT_SCHEMA_VERSION};
 use crate::core::LedgerRecord;
+use crate::storage::codec::{codec_for_id, JSON_CODEC_ID};
+use crate::storage::merkle_tree::IncrementalMerkleTree;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
+/// A ledger's backing store.
+///
+/// One storage may hold several chains. Every read takes the `chain_id` it
+/// is about and sees only that chain's records, in chain order. Event ids
+/// and idempotency keys are unique within a chain only, so the same event
+/// may be stored in several chains.
+///
+/// Implemented through `async_trait`, so every method returns a boxed
+/// `Send` future and `Arc<dyn AppendOnlyStorage>` can be held across
+/// `.await` points in spawned tasks. Implementations in other crates should
+/// use `#[async_trait::async_trait]` as well, not the `?Send` form.
 #[async_trait]
 pub trait AppendOnlyStorage: Send + Sync {
     async fn append(&self, record: LedgerRecord) -> Result<(), StorageError>;
-    async fn get(&self, event_id: &str) -> Result<Option<LedgerRecord>, StorageError>;
+    /// Stores the record and its secondary index entries in one atomic
+    /// commit, so a crash never leaves index entries for a missing record or
+    /// a record missing from its indexes.
+    async fn append_atomic(
+        &self,
+        record: LedgerRecord,
+        index_entries: &[IndexEntry],
+    ) -> Result<(), StorageError>;
+    /// Records of `chain_id` with an entry under `index` for `key`, in chain
+    /// order
+    async fn records_by_index(
+        &self,
+        chain_id: &str,
+        index: &str,
+        key: &str,
+    ) -> Result<Vec<LedgerRecord>, StorageError>;
+    /// The record `event_id` of `chain_id`, if that chain holds it
+    async fn get(&self, chain_id: &str, event_id: &str) -> Result<Option<LedgerRecord>, StorageError>;
+    /// The record of `chain_id` appended with this idempotency key, if any.
+    /// Backends store the key durably alongside the record when `append` is
+    /// called.
+    async fn get_by_idempotency_key(&self, chain_id: &str, key: &str) -> Result<Option<LedgerRecord>, StorageError>;
+    /// Records of `chain_id` matching every filter given, in chain order
     async fn query_records(
         &self,
+        chain_id: &str,
         entity_id: Option<&str>,
         start_time: Option<chrono::DateTime<chrono::Utc>>,
         end_time: Option<chrono::DateTime<chrono::Utc>>,
+        event_types: Option<&[String]>,
+        tags: Option<&TagFilter>,
+        // Only records with no visibility or sharing a scope with these;
+        // every record when `None`
+        caller_scopes: Option<&[String]>,
     ) -> Result<Vec<LedgerRecord>, StorageError>;
-    async fn verify_chain(&self) -> Result<bool, StorageError>;
-    async fn get_latest_hash(&self) -> Result<Option<String>, StorageError>;
-    async fn get_merkle_root(&self) -> Result<String, StorageError>;
+    /// The record `event_id` and every record after it in `chain_id`, in
+    /// chain order; empty if the chain doesn't hold it. This fallback loads
+    /// the whole chain.
+    async fn records_from(&self, chain_id: &str, event_id: &str) -> Result<Vec<LedgerRecord>, StorageError> {
+        let records = self.query_records(chain_id, None, None, None, None, None, None).await?;
+        Ok(records
+            .into_iter()
+            .skip_while(|record| record.event_id != event_id)
+            .collect())
+    }
+    /// Whether `chain_id`'s records link from its genesis seed through each
+    /// `previous_hash`, in chain order.
+    ///
+    /// A pre-check on links only: event ids are not recomputed from the
+    /// stored events, which a backend wrapped in an `EncryptionLayer` only
+    /// holds sealed, so a rewritten payload passes. `true` therefore does
+    /// not mean the chain is intact; `DigitalLedger::verify_integrity` runs
+    /// this first and then checks every record's id itself.
+    async fn verify_chain(&self, chain_id: &str) -> Result<bool, StorageError>;
+    /// Event id of the head of `chain_id`, `None` while it has no records
+    async fn get_latest_hash(&self, chain_id: &str) -> Result<Option<String>, StorageError>;
+    /// Reads the chain head, builds the record that follows it with `build`,
+    /// and stores it with its index entries, with no other append to
+    /// `chain_id` able to read the same head in between. If `build` fails,
+    /// nothing is stored.
+    ///
+    /// This default relies on the caller's in-process append lock. Backends
+    /// shared by several processes override it with a lock they all see.
+    async fn append_linked(
+        &self,
+        chain_id: &str,
+        build: &(dyn Fn(Option<&LedgerRecord>) -> Result<LedgerRecord, StorageError> + Send + Sync),
+        index_entries: &[IndexEntry],
+    ) -> Result<LedgerRecord, StorageError> {
+        let head = match self.get_latest_hash(chain_id).await? {
+            Some(event_id) => Some(self.get(chain_id, &event_id).await?.ok_or(StorageError::NotFound)?),
+            None => None,
+        };
+        let record = build(head.as_ref())?;
+        if record.chain_id != chain_id {
+            return Err(StorageError::ChainVerification(format!(
+                "record {} is for chain {}, not {}",
+                record.event_id, record.chain_id, chain_id
+            )));
+        }
+        self.append_atomic(record.clone(), index_entries).await?;
+        Ok(record)
+    }
+    /// `append_linked` for one record per entry of `index_entries`, in
+    /// order. `build` gets the record's position in the batch, the record
+    /// before it and the Merkle root over the chain up to it. Either every
+    /// record is stored or none is.
+    ///
+    /// This default appends them one `append_linked` at a time, so a
+    /// failure part-way leaves the records before it stored. Backends with
+    /// transactions override it with a single commit.
+    async fn append_linked_batch(
+        &self,
+        chain_id: &str,
+        build: &(dyn Fn(usize, Option<&LedgerRecord>, &str) -> Result<LedgerRecord, StorageError> + Send + Sync),
+        index_entries: &[Vec<IndexEntry>],
+    ) -> Result<Vec<LedgerRecord>, StorageError> {
+        let mut records = Vec::with_capacity(index_entries.len());
+        for (position, entries) in index_entries.iter().enumerate() {
+            let merkle_root = self.get_merkle_root(chain_id).await?;
+            let build_one = |head: Option<&LedgerRecord>| build(position, head, &merkle_root);
+            records.push(self.append_linked(chain_id, &build_one, entries).await?);
+        }
+        Ok(records)
+    }
+    /// Root of the Merkle tree over `chain_id`'s event ids in chain order
+    async fn get_merkle_root(&self, chain_id: &str) -> Result<String, StorageError>;
+    /// Recomputes `chain_id`'s Merkle tree from the stored records,
+    /// replacing any cached tree, and returns the new root.
+    async fn rebuild_merkle_tree(&self, chain_id: &str) -> Result<String, StorageError>;
+    /// Atomically deletes the given records of the tombstone's chain and
+    /// stores `tombstone` in their place. This is the only operation that
+    /// removes records, and is used
+    /// solely by retention after the records have been archived.
+    async fn replace_with_tombstone(
+        &self,
+        event_ids: &[String],
+        tombstone: LedgerRecord,
+    ) -> Result<(), StorageError>;
+    /// Record counts and time range of `chain_id`. Backends should serve
+    /// this from counters kept up to date on append; this fallback loads and
+    /// scans every record of the chain, so its cost grows with the chain.
+    async fn stats(&self, chain_id: &str) -> Result<StorageStats, StorageError> {
+        let records = self.query_records(chain_id, None, None, None, None, None, None).await?;
+        let mut stats = StorageStats::default();
+        records.iter().for_each(|record| stats.record(record));
+        Ok(stats)
+    }
+    /// Every chain the storage holds records for, ordered by chain id. This
+    /// is the only read across chains, so it has no fallback built on the
+    /// per-chain reads.
+    async fn list_chains(&self) -> Result<Vec<ChainSummary>, StorageError>;
+    /// Sets how appends are made durable from now on. Backends with no
+    /// durable commit path of their own have nothing to configure and
+    /// accept every level.
+    fn set_durability(&self, _level: DurabilityLevel) -> Result<(), StorageError> {
+        Ok(())
+    }
+    /// Called once at the end of every batch of appends. Under
+    /// `DurabilityLevel::FsyncBatch` it returns once everything appended
+    /// so far is on disk; under the other levels it has nothing to do.
+    async fn flush_batch(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+// Fails to compile if a change to the trait stops shared storage from
+// moving between threads, or makes its futures !Send
+const _: () = {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    fn assert_send<T: Send>(_: &T) {}
+    fn _storage_is_shareable(storage: Arc<dyn AppendOnlyStorage>) {
+        assert_send_sync::<Arc<dyn AppendOnlyStorage>>();
+        assert_send(&storage.get("chain", "event"));
+        assert_send(&storage.query_records("chain", None, None, None, None, None, None));
+        assert_send(&storage.append_linked("chain", &|_| Err(StorageError::NotFound), &[]));
+    }
+};
+
+/// How a `TagFilter` combines its tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TagMatch {
+    /// Records carrying at least one of the tags
+    #[default]
+    Any,
+    /// Records carrying every one of the tags
+    All,
+}
+
+/// Restricts a query to transactions by their tags. Only
+/// `FinancialTransaction`s carry tags, so every other event is excluded, as
+/// is everything when no tags are given. Matching goes through the
+/// `TAG_INDEX` secondary index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagFilter {
+    pub tags: Vec<String>,
+    pub mode: TagMatch,
+}
+
+impl TagFilter {
+    pub fn any(tags: Vec<String>) -> Self {
+        Self { tags, mode: TagMatch::Any }
+    }
+
+    pub fn all(tags: Vec<String>) -> Self {
+        Self { tags, mode: TagMatch::All }
+    }
+}
+
+/// Secondary index entry pointing a key at the record it is stored with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub index: String,
+    pub key: String,
+}
+
+impl IndexEntry {
+    pub fn new(index: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            index: index.into(),
+            key: key.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageStats {
+    pub record_count: usize,
+    pub event_type_counts: HashMap<String, usize>,
+    pub earliest: Option<chrono::DateTime<chrono::Utc>>,
+    pub latest: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl StorageStats {
+    /// Counts one more stored record
+    pub fn record(&mut self, record: &LedgerRecord) {
+        self.record_count += 1;
+        *self
+            .event_type_counts
+            .entry(record.event.event_type_name().to_string())
+            .or_default() += 1;
+        self.earliest = Some(self.earliest.map_or(record.timestamp, |t| t.min(record.timestamp)));
+        self.latest = Some(self.latest.map_or(record.timestamp, |t| t.max(record.timestamp)));
+    }
+}
+
+/// One chain in a storage that may hold several
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainSummary {
+    pub chain_id: String,
+    pub record_count: usize,
+    /// Event id of the chain's first record
+    pub first_hash: Option<String>,
+    /// Event id of the chain's head
+    pub latest_hash: Option<String>,
+    pub earliest: Option<chrono::DateTime<chrono::Utc>>,
+    pub latest: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// When an append reaches disk, traded off against append throughput.
+///
+/// Every level commits a record and its index entries atomically, so a
+/// crash never leaves a partial record or a chain that links to a missing
+/// one. They differ in how many of the most recently acknowledged appends
+/// a crash can lose; whatever survives is always a prefix of the chain and
+/// still verifies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DurabilityLevel {
+    /// Each append is flushed to disk before it returns. A crash loses
+    /// nothing that was acknowledged.
+    #[default]
+    Fsync,
+    /// Appends return once committed, without waiting for the flush, and
+    /// are flushed together on the backend's timer. Each batch of appends
+    /// (`append_batch`, `append_batch_lenient`, `import_ndjson`) is flushed
+    /// once before it returns, so a crash can lose single appends
+    /// acknowledged within the last flush interval but never part of an
+    /// acknowledged batch.
+    FsyncBatch,
+    /// Flushing is left to the backend's timer and the operating system,
+    /// batches included. A crash can lose any appends acknowledged within
+    /// the last flush interval, and other readers may already have seen
+    /// them. Only for chains that can be rebuilt from another source.
+    Async,
 }
 
 #[derive(Debug, Error)]
@@ -28,14 +302,62 @@ pub enum StorageError {
     Database(String),
     #[error("Chain verification failed: {0}")]
     ChainVerification(String),
+    #[error("Codec error: {0}")]
+    Codec(String),
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    #[error("Schema migration error: {0}")]
+    Migration(#[from] crate::core::schema::MigrationError),
     #[error("Record not found")]
     NotFound,
 }
 
-// Example PostgreSQL implementation
+/// So errors can be handed to every observer. Cloned I/O and serialization
+/// errors keep their kind and message but not their source.
+impl Clone for StorageError {
+    fn clone(&self) -> Self {
+        match self {
+            StorageError::Io(e) => StorageError::Io(std::io::Error::new(e.kind(), e.to_string())),
+            StorageError::Serialization(e) => StorageError::Serialization(serde::de::Error::custom(e)),
+            StorageError::Database(message) => StorageError::Database(message.clone()),
+            StorageError::ChainVerification(message) => StorageError::ChainVerification(message.clone()),
+            StorageError::Codec(message) => StorageError::Codec(message.clone()),
+            StorageError::Encryption(message) => StorageError::Encryption(message.clone()),
+            StorageError::Migration(e) => StorageError::Migration(e.clone()),
+            StorageError::NotFound => StorageError::NotFound,
+        }
+    }
+}
+
+/// PostgreSQL backend, safe to share between processes appending to the
+/// same chains.
+///
+/// A chain's rows are kept in insertion order by `(chain_id, seq)`: `seq`
+/// is assigned on insert, and a retention tombstone takes the `seq` of the
+/// first record it replaces. `append_linked` serializes appenders of a
+/// chain with a transaction-scoped advisory lock keyed on the chain id, so
+/// two processes can never link records to the same head and fork the
+/// chain. Chain order never depends on record timestamps, so appending
+/// processes' clocks may disagree.
+///
+/// `DurabilityLevel` maps onto `synchronous_commit` for the storage's own
+/// write transactions. `Fsync` keeps it on. `FsyncBatch` and `Async` turn
+/// it off, so commits are flushed by the WAL writer every
+/// `wal_writer_delay` (a crash can lose up to three times that), and
+/// `FsyncBatch` ends each batch with a synchronous commit, which also
+/// flushes all WAL before it. Retention always commits synchronously.
 pub struct PostgresStorage {
     pool: sqlx::PgPool,
     table_name: String,
+    chains: tokio::sync::Mutex<HashMap<String, ChainCache>>,
+    durability: Mutex<DurabilityLevel>,
+}
+
+/// Merkle tree and stats of one chain, loaded on first use and kept up to
+/// date by this process's appends
+struct ChainCache {
+    merkle_tree: IncrementalMerkleTree,
+    stats: StorageStats,
 }
 
 impl PostgresStorage {
@@ -44,19 +366,27 @@ impl PostgresStorage {
             .await
             .map_err(|e| StorageError::Database(e.to_string()))?;
         
+        Self::with_pool(pool, table_name).await
+    }
+    
+    /// Uses an existing pool, so several storages (one table per ledger) and
+    /// the rest of the service can share connections and pool limits
+    pub async fn with_pool(pool: sqlx::PgPool, table_name: &str) -> Result<Self, StorageError> {
         // Create table if not exists
         let create_table_query = format!(
             r#"
             CREATE TABLE IF NOT EXISTS {} (
-                event_id VARCHAR(255) PRIMARY KEY,
+                event_id VARCHAR(255) NOT NULL,
                 event_data JSONB NOT NULL,
                 metadata JSONB,
                 timestamp TIMESTAMPTZ NOT NULL,
                 previous_hash VARCHAR(255),
                 chain_id VARCHAR(100) NOT NULL,
                 signature TEXT,
+                violations JSONB,
                 created_at TIMESTAMPTZ DEFAULT NOW(),
-                merkle_path TEXT[]
+                merkle_path TEXT[],
+                PRIMARY KEY (chain_id, event_id)
             )
             "#,
             table_name
@@ -67,20 +397,182 @@ impl PostgresStorage {
             .await
             .map_err(|e| StorageError::Database(e.to_string()))?;
         
+        // Tables created by earlier versions lack the newer columns
+        let migrate_table_queries = [
+            format!("ALTER TABLE {} ADD COLUMN IF NOT EXISTS violations JSONB", table_name),
+            format!(
+                "ALTER TABLE {} ADD COLUMN IF NOT EXISTS codec VARCHAR(16) NOT NULL DEFAULT '{}'",
+                table_name, JSON_CODEC_ID
+            ),
+            format!("ALTER TABLE {} ADD COLUMN IF NOT EXISTS event_bytes BYTEA", table_name),
+            format!("ALTER TABLE {} ADD COLUMN IF NOT EXISTS idempotency_key VARCHAR(255)", table_name),
+            format!(
+                "ALTER TABLE {} ADD COLUMN IF NOT EXISTS schema_version SMALLINT NOT NULL DEFAULT 1",
+                table_name
+            ),
+            format!("ALTER TABLE {} ADD COLUMN IF NOT EXISTS sequence BIGINT", table_name),
+            format!("ALTER TABLE {} ADD COLUMN IF NOT EXISTS nonce VARCHAR(64)", table_name),
+            format!("ALTER TABLE {} ADD COLUMN IF NOT EXISTS merkle_root_at_append VARCHAR(64)", table_name),
+            format!(
+                "ALTER TABLE {} ADD COLUMN IF NOT EXISTS visibility TEXT[] NOT NULL DEFAULT '{{}}'",
+                table_name
+            ),
+            // Event ids and idempotency keys were once unique across chains
+            format!(
+                r#"
+                DO $$
+                DECLARE pkey TEXT;
+                BEGIN
+                    SELECT conname INTO pkey FROM pg_constraint
+                    WHERE conrelid = '{0}'::regclass AND contype = 'p' AND array_length(conkey, 1) = 1;
+                    IF pkey IS NOT NULL THEN
+                        EXECUTE format('ALTER TABLE {0} DROP CONSTRAINT %I CASCADE', pkey);
+                        ALTER TABLE {0} ADD PRIMARY KEY (chain_id, event_id);
+                    END IF;
+                END $$
+                "#,
+                table_name
+            ),
+            format!("DROP INDEX IF EXISTS {}_idempotency_key_idx", table_name),
+            format!(
+                "CREATE UNIQUE INDEX IF NOT EXISTS {0}_chain_idempotency_key_idx ON {0} (chain_id, idempotency_key)",
+                table_name
+            ),
+            // Insertion order, which is chain order
+            format!("ALTER TABLE {} ADD COLUMN IF NOT EXISTS seq BIGSERIAL", table_name),
+            format!("CREATE INDEX IF NOT EXISTS {0}_chain_seq_idx ON {0} (chain_id, seq)", table_name),
+            format!(
+                "CREATE INDEX IF NOT EXISTS {0}_chain_timestamp_idx ON {0} (chain_id, timestamp, seq)",
+                table_name
+            ),
+            // Covered by the primary key
+            format!("DROP INDEX IF EXISTS {}_chain_event_idx", table_name),
+            format!("CREATE INDEX IF NOT EXISTS {0}_timestamp_idx ON {0} (timestamp, seq)", table_name),
+            // Index entries go with their record when retention deletes it
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {0}_index (
+                    index_name VARCHAR(64) NOT NULL,
+                    key VARCHAR(255) NOT NULL,
+                    chain_id VARCHAR(100) NOT NULL,
+                    event_id VARCHAR(255) NOT NULL,
+                    PRIMARY KEY (index_name, key, chain_id, event_id),
+                    FOREIGN KEY (chain_id, event_id) REFERENCES {0} (chain_id, event_id) ON DELETE CASCADE
+                )
+                "#,
+                table_name
+            ),
+            // Index tables from before chain-scoped ids lost their foreign
+            // key with the old primary key above
+            format!(
+                r#"
+                DO $$
+                BEGIN
+                    IF NOT EXISTS (
+                        SELECT 1 FROM pg_attribute
+                        WHERE attrelid = '{0}_index'::regclass AND attname = 'chain_id'
+                    ) THEN
+                        ALTER TABLE {0}_index ADD COLUMN chain_id VARCHAR(100);
+                        UPDATE {0}_index i SET chain_id = r.chain_id FROM {0} r WHERE r.event_id = i.event_id;
+                        ALTER TABLE {0}_index ALTER COLUMN chain_id SET NOT NULL;
+                        ALTER TABLE {0}_index DROP CONSTRAINT {0}_index_pkey;
+                        ALTER TABLE {0}_index ADD PRIMARY KEY (index_name, key, chain_id, event_id);
+                        ALTER TABLE {0}_index ADD FOREIGN KEY (chain_id, event_id)
+                            REFERENCES {0} (chain_id, event_id) ON DELETE CASCADE;
+                    END IF;
+                END $$
+                "#,
+                table_name
+            ),
+        ];
+        
+        for migrate_query in &migrate_table_queries {
+            sqlx::query(migrate_query)
+                .execute(&pool)
+                .await
+                .map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+        
         Ok(Self {
             pool,
             table_name: table_name.to_string(),
+            chains: tokio::sync::Mutex::new(HashMap::new()),
+            durability: Mutex::new(DurabilityLevel::default()),
         })
     }
-}
-
-#[async_trait]
-impl AppendOnlyStorage for PostgresStorage {
-    async fn append(&self, record: LedgerRecord) -> Result<(), StorageError> {
+    
+    pub fn with_durability(self, level: DurabilityLevel) -> Self {
+        *self.durability.lock().unwrap() = level;
+        self
+    }
+    
+    /// Starts an append transaction, committing asynchronously unless the
+    /// durability level is `Fsync`
+    async fn begin_append(&self) -> Result<sqlx::Transaction<'_, sqlx::Postgres>, StorageError> {
+        let mut tx = self.pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        if *self.durability.lock().unwrap() != DurabilityLevel::Fsync {
+            sqlx::query("SET LOCAL synchronous_commit = off")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+        
+        Ok(tx)
+    }
+    
+    /// Commits an append of `records`, then counts them in their chain's
+    /// cache if that is loaded. The cache lock is held across the commit, so
+    /// a concurrent `chain_cache` load sees the records either in the table
+    /// or through this update, never both or neither.
+    async fn commit_append(
+        &self,
+        tx: sqlx::Transaction<'_, sqlx::Postgres>,
+        records: &[LedgerRecord],
+    ) -> Result<(), StorageError> {
+        let mut chains = self.chains.lock().await;
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        for record in records {
+            if let Some(cache) = chains.get_mut(&record.chain_id) {
+                cache.merkle_tree.push(&record.event_id);
+                cache.stats.record(record);
+            }
+        }
+        Ok(())
+    }
+    
+    /// The cache of `chain_id`, loaded from the table on first use. The
+    /// caller holds the cache lock.
+    async fn chain_cache<'c>(
+        &self,
+        chains: &'c mut HashMap<String, ChainCache>,
+        chain_id: &str,
+    ) -> Result<&'c mut ChainCache, StorageError> {
+        if !chains.contains_key(chain_id) {
+            let event_ids = self.load_event_ids(chain_id).await?;
+            let cache = ChainCache {
+                merkle_tree: IncrementalMerkleTree::from_leaves(event_ids.iter().map(String::as_str)),
+                stats: self.load_stats(chain_id).await?,
+            };
+            chains.insert(chain_id.to_string(), cache);
+        }
+        chains.get_mut(chain_id).ok_or(StorageError::NotFound)
+    }
+    
+    async fn insert_record<'e, E>(&self, executor: E, record: &LedgerRecord) -> Result<(), StorageError>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
         let query = format!(
             r#"
-            INSERT INTO {} (event_id, event_data, metadata, timestamp, previous_hash, chain_id, signature)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO {} (event_id, event_data, metadata, timestamp, previous_hash, chain_id, signature, violations, codec, event_bytes, idempotency_key, schema_version, sequence, nonce, merkle_root_at_append, visibility)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             "#,
             self.table_name
         );
@@ -93,74 +585,343 @@ impl AppendOnlyStorage for PostgresStorage {
             .bind(&record.previous_hash)
             .bind(&record.chain_id)
             .bind(&record.signature)
-            .execute(&self.pool)
+            .bind(serde_json::to_value(&record.violations)?)
+            .bind(&record.codec)
+            .bind(codec_for_id(&record.codec)?.encode_event(&record.event)?)
+            .bind(record.idempotency_key())
+            .bind(record.schema_version as i16)
+            .bind(record.sequence.map(|s| s as i64))
+            .bind(&record.nonce)
+            .bind(&record.merkle_root_at_append)
+            .bind(&record.visibility)
+            .execute(executor)
             .await
             .map_err(|e| StorageError::Database(e.to_string()))?;
         
         Ok(())
     }
     
-    async fn get(&self, event_id: &str) -> Result<Option<LedgerRecord>, StorageError> {
+    async fn insert_index_entries(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        record: &LedgerRecord,
+        index_entries: &[IndexEntry],
+    ) -> Result<(), StorageError> {
+        let query = format!(
+            "INSERT INTO {}_index (index_name, key, chain_id, event_id) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+            self.table_name
+        );
+        for entry in index_entries {
+            sqlx::query(&query)
+                .bind(&entry.index)
+                .bind(&entry.key)
+                .bind(&record.chain_id)
+                .bind(&record.event_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+    
+    /// Computes a chain's stats with one aggregate query
+    async fn load_stats(&self, chain_id: &str) -> Result<StorageStats, StorageError> {
+        let query = format!(
+            r#"
+            SELECT event_data->>'event_type' AS event_type, COUNT(*) AS count,
+                   MIN(timestamp) AS earliest, MAX(timestamp) AS latest
+            FROM {}
+            WHERE chain_id = $1
+            GROUP BY event_data->>'event_type'
+            "#,
+            self.table_name
+        );
+        
+        let rows = sqlx::query(&query)
+            .bind(chain_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        let mut stats = StorageStats::default();
+        for row in &rows {
+            let count = row.get::<i64, _>("count") as usize;
+            let earliest: chrono::DateTime<chrono::Utc> = row.get("earliest");
+            let latest: chrono::DateTime<chrono::Utc> = row.get("latest");
+            stats.record_count += count;
+            stats.event_type_counts.insert(row.get("event_type"), count);
+            stats.earliest = Some(stats.earliest.map_or(earliest, |t| t.min(earliest)));
+            stats.latest = Some(stats.latest.map_or(latest, |t| t.max(latest)));
+        }
+        Ok(stats)
+    }
+    
+    async fn load_event_ids(&self, chain_id: &str) -> Result<Vec<String>, StorageError> {
+        let query = format!(
+            "SELECT event_id FROM {} WHERE chain_id = $1 ORDER BY seq ASC",
+            self.table_name
+        );
+        
+        let rows = sqlx::query(&query)
+            .bind(chain_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        Ok(rows.iter().map(|r| r.get("event_id")).collect())
+    }
+}
+
+#[async_trait]
+impl AppendOnlyStorage for PostgresStorage {
+    async fn append(&self, record: LedgerRecord) -> Result<(), StorageError> {
+        let mut tx = self.begin_append().await?;
+        self.insert_record(&mut *tx, &record).await?;
+        self.commit_append(tx, std::slice::from_ref(&record)).await
+    }
+    
+    async fn append_atomic(
+        &self,
+        record: LedgerRecord,
+        index_entries: &[IndexEntry],
+    ) -> Result<(), StorageError> {
+        let mut tx = self.begin_append().await?;
+        
+        self.insert_record(&mut *tx, &record).await?;
+        self.insert_index_entries(&mut tx, &record, index_entries).await?;
+        self.commit_append(tx, std::slice::from_ref(&record)).await
+    }
+    
+    async fn append_linked(
+        &self,
+        chain_id: &str,
+        build: &(dyn Fn(Option<&LedgerRecord>) -> Result<LedgerRecord, StorageError> + Send + Sync),
+        index_entries: &[IndexEntry],
+    ) -> Result<LedgerRecord, StorageError> {
+        let mut tx = self.begin_append().await?;
+        
+        // Held until commit or rollback; every process appending to this
+        // chain queues here before reading the head
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(chain_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        let head_query = format!(
+            "SELECT * FROM {} WHERE chain_id = $1 ORDER BY seq DESC LIMIT 1",
+            self.table_name
+        );
+        let head = sqlx::query(&head_query)
+            .bind(chain_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?
+            .map(|row| record_from_row(&row))
+            .transpose()?;
+        
+        let record = build(head.as_ref())?;
+        if record.chain_id != chain_id {
+            return Err(StorageError::ChainVerification(format!(
+                "record {} is for chain {}, not {}",
+                record.event_id, record.chain_id, chain_id
+            )));
+        }
+        self.insert_record(&mut *tx, &record).await?;
+        self.insert_index_entries(&mut tx, &record, index_entries).await?;
+        self.commit_append(tx, std::slice::from_ref(&record)).await?;
+        Ok(record)
+    }
+    
+    async fn append_linked_batch(
+        &self,
+        chain_id: &str,
+        build: &(dyn Fn(usize, Option<&LedgerRecord>, &str) -> Result<LedgerRecord, StorageError> + Send + Sync),
+        index_entries: &[Vec<IndexEntry>],
+    ) -> Result<Vec<LedgerRecord>, StorageError> {
+        let mut tx = self.begin_append().await?;
+        
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(chain_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        let head_query = format!(
+            "SELECT * FROM {} WHERE chain_id = $1 ORDER BY seq DESC LIMIT 1",
+            self.table_name
+        );
+        let mut head = sqlx::query(&head_query)
+            .bind(chain_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?
+            .map(|row| record_from_row(&row))
+            .transpose()?;
+        // A copy, so the cached tree only moves once the batch commits
+        let mut merkle_tree = {
+            let mut chains = self.chains.lock().await;
+            self.chain_cache(&mut chains, chain_id).await?.merkle_tree.clone()
+        };
+        
+        let mut records = Vec::with_capacity(index_entries.len());
+        for (position, entries) in index_entries.iter().enumerate() {
+            let record = build(position, head.as_ref(), &merkle_tree.root_hex())?;
+            if record.chain_id != chain_id {
+                return Err(StorageError::ChainVerification(format!(
+                    "record {} is for chain {}, not {}",
+                    record.event_id, record.chain_id, chain_id
+                )));
+            }
+            self.insert_record(&mut *tx, &record).await?;
+            self.insert_index_entries(&mut tx, &record, entries).await?;
+            merkle_tree.push(&record.event_id);
+            head = Some(record.clone());
+            records.push(record);
+        }
+        self.commit_append(tx, &records).await?;
+        Ok(records)
+    }
+    
+    async fn records_by_index(
+        &self,
+        chain_id: &str,
+        index: &str,
+        key: &str,
+    ) -> Result<Vec<LedgerRecord>, StorageError> {
         let query = format!(
-            "SELECT * FROM {} WHERE event_id = $1",
+            r#"
+            SELECT r.* FROM {0} r
+            JOIN {0}_index i ON i.chain_id = r.chain_id AND i.event_id = r.event_id
+            WHERE r.chain_id = $1 AND i.index_name = $2 AND i.key = $3
+            ORDER BY r.seq ASC
+            "#,
+            self.table_name
+        );
+        
+        let rows = sqlx::query(&query)
+            .bind(chain_id)
+            .bind(index)
+            .bind(key)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        rows.iter().map(record_from_row).collect()
+    }
+    
+    async fn get(&self, chain_id: &str, event_id: &str) -> Result<Option<LedgerRecord>, StorageError> {
+        let query = format!(
+            "SELECT * FROM {} WHERE chain_id = $1 AND event_id = $2",
             self.table_name
         );
         
         let row = sqlx::query(&query)
+            .bind(chain_id)
             .bind(event_id)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| StorageError::Database(e.to_string()))?;
         
-        match row {
-            Some(row) => {
-                let record = LedgerRecord {
-                    event_id: row.get("event_id"),
-                    event: serde_json::from_value(row.get("event_data"))?,
-                    metadata: row.get("metadata"),
-                    timestamp: row.get("timestamp"),
-                    previous_hash: row.get("previous_hash"),
-                    chain_id: row.get("chain_id"),
-                    signature: row.get("signature"),
-                };
-                Ok(Some(record))
-            }
-            None => Ok(None),
-        }
+        row.map(|row| record_from_row(&row)).transpose()
+    }
+    
+    async fn get_by_idempotency_key(&self, chain_id: &str, key: &str) -> Result<Option<LedgerRecord>, StorageError> {
+        let query = format!(
+            "SELECT * FROM {} WHERE chain_id = $1 AND idempotency_key = $2",
+            self.table_name
+        );
+        
+        let row = sqlx::query(&query)
+            .bind(chain_id)
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        row.map(|row| record_from_row(&row)).transpose()
     }
     
     async fn query_records(
         &self,
+        chain_id: &str,
         entity_id: Option<&str>,
         start_time: Option<chrono::DateTime<chrono::Utc>>,
         end_time: Option<chrono::DateTime<chrono::Utc>>,
+        event_types: Option<&[String]>,
+        tags: Option<&TagFilter>,
+        caller_scopes: Option<&[String]>,
     ) -> Result<Vec<LedgerRecord>, StorageError> {
-        let mut query = format!("SELECT * FROM {} WHERE 1=1", self.table_name);
-        let mut params = vec![];
-        let mut param_counter = 1;
+        let mut query = format!("SELECT * FROM {} WHERE chain_id = $1", self.table_name);
+        let mut param_counter = 2;
         
-        if let Some(entity) = entity_id {
-            query.push_str(&format!(" AND event_data->>'entity_id' = ${}", param_counter));
-            params.push(entity.to_string());
+        if entity_id.is_some() {
+            query.push_str(&format!(" AND {} = ${}", ENTITY_ID_SQL, param_counter));
             param_counter += 1;
         }
         
-        if let Some(start) = start_time {
+        if start_time.is_some() {
             query.push_str(&format!(" AND timestamp >= ${}", param_counter));
-            params.push(start);
             param_counter += 1;
         }
         
-        if let Some(end) = end_time {
+        if end_time.is_some() {
             query.push_str(&format!(" AND timestamp <= ${}", param_counter));
-            params.push(end);
+            param_counter += 1;
+        }
+        
+        if event_types.is_some() {
+            query.push_str(&format!(" AND event_data->>'event_type' = ANY(${})", param_counter));
+            param_counter += 1;
+        }
+        
+        if let Some(filter) = tags {
+            query.push_str(&format!(
+                " AND event_id IN (SELECT event_id FROM {}_index WHERE chain_id = $1 AND index_name = '{}' AND key = ANY(${}) GROUP BY event_id",
+                self.table_name, TAG_INDEX, param_counter
+            ));
+            if filter.mode == TagMatch::All {
+                query.push_str(&format!(" HAVING COUNT(DISTINCT key) = ${}", param_counter + 1));
+            }
+            query.push(')');
+            param_counter += match filter.mode {
+                TagMatch::All => 2,
+                TagMatch::Any => 1,
+            };
+        }
+        
+        if caller_scopes.is_some() {
+            query.push_str(&format!(
+                " AND (cardinality(visibility) = 0 OR visibility && ${})",
+                param_counter
+            ));
         }
         
-        query.push_str(" ORDER BY timestamp ASC");
+        query.push_str(" ORDER BY seq ASC");
         
-        let mut query_builder = sqlx::query(&query);
-        for param in params {
-            query_builder = query_builder.bind(param);
+        // Parameters are bound in the same order their placeholders were added
+        let mut query_builder = sqlx::query(&query).bind(chain_id);
+        if let Some(entity) = entity_id {
+            query_builder = query_builder.bind(entity.to_string());
+        }
+        if let Some(start) = start_time {
+            query_builder = query_builder.bind(start);
+        }
+        if let Some(end) = end_time {
+            query_builder = query_builder.bind(end);
+        }
+        if let Some(types) = event_types {
+            query_builder = query_builder.bind(types.to_vec());
+        }
+        if let Some(filter) = tags {
+            query_builder = query_builder.bind(filter.tags.clone());
+            if filter.mode == TagMatch::All {
+                let distinct: HashSet<&String> = filter.tags.iter().collect();
+                query_builder = query_builder.bind(distinct.len() as i64);
+            }
+        }
+        if let Some(scopes) = caller_scopes {
+            query_builder = query_builder.bind(scopes.to_vec());
         }
         
         let rows = query_builder
@@ -168,34 +929,69 @@ impl AppendOnlyStorage for PostgresStorage {
             .await
             .map_err(|e| StorageError::Database(e.to_string()))?;
         
-        let mut records = Vec::new();
-        for row in rows {
-            records.push(LedgerRecord {
-                event_id: row.get("event_id"),
-                event: serde_json::from_value(row.get("event_data"))?,
-                metadata: row.get("metadata"),
-                timestamp: row.get("timestamp"),
-                previous_hash: row.get("previous_hash"),
-                chain_id: row.get("chain_id"),
-                signature: row.get("signature"),
-            });
-        }
+        rows.iter().map(record_from_row).collect()
+    }
+    
+    async fn records_from(&self, chain_id: &str, event_id: &str) -> Result<Vec<LedgerRecord>, StorageError> {
+        let query = format!(
+            r#"
+            SELECT * FROM {0}
+            WHERE chain_id = $1 AND seq >= (SELECT seq FROM {0} WHERE chain_id = $1 AND event_id = $2)
+            ORDER BY seq ASC
+            "#,
+            self.table_name
+        );
         
-        Ok(records)
+        let rows = sqlx::query(&query)
+            .bind(chain_id)
+            .bind(event_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        rows.iter().map(record_from_row).collect()
     }
     
-    async fn verify_chain(&self) -> Result<bool, StorageError> {
-        // Implementation for verifying hash chain integrity
+    /// Streams the table in chain order, checking that each record links to
+    /// the one before it and that the first links to its genesis seed.
+    /// Memory use does not grow with the chain. Links only, as the trait
+    /// documents; the ledger checks ids.
+    async fn verify_chain(&self, chain_id: &str) -> Result<bool, StorageError> {
+        let query = format!("SELECT * FROM {} WHERE chain_id = $1 ORDER BY seq ASC", self.table_name);
+        let mut rows = sqlx::query(&query).bind(chain_id).fetch(&self.pool);
+        
+        let mut expected_previous: Option<String> = None;
+        while let Some(row) = rows
+            .try_next()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?
+        {
+            let record = record_from_row(&row)?;
+            let linked = match &expected_previous {
+                Some(_) => record.previous_hash == expected_previous,
+                None => links_to_genesis(&record, &record.chain_id),
+            };
+            if !linked {
+                tracing::error!(
+                    "Record {} links to {:?}, expected {:?}",
+                    record.event_id, record.previous_hash, expected_previous
+                );
+                return Ok(false);
+            }
+            expected_previous = Some(link_target(&record));
+        }
+        
         Ok(true)
     }
     
-    async fn get_latest_hash(&self) -> Result<Option<String>, StorageError> {
+    async fn get_latest_hash(&self, chain_id: &str) -> Result<Option<String>, StorageError> {
         let query = format!(
-            "SELECT event_id FROM {} ORDER BY timestamp DESC LIMIT 1",
+            "SELECT event_id FROM {} WHERE chain_id = $1 ORDER BY seq DESC LIMIT 1",
             self.table_name
         );
         
         let row = sqlx::query(&query)
+            .bind(chain_id)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| StorageError::Database(e.to_string()))?;
@@ -203,8 +999,196 @@ impl AppendOnlyStorage for PostgresStorage {
         Ok(row.map(|r| r.get("event_id")))
     }
     
-    async fn get_merkle_root(&self) -> Result<String, StorageError> {
-        // Implementation for calculating Merkle root
-        Ok("merkle_root_placeholder".to_string())
+    async fn get_merkle_root(&self, chain_id: &str) -> Result<String, StorageError> {
+        let mut chains = self.chains.lock().await;
+        Ok(self.chain_cache(&mut chains, chain_id).await?.merkle_tree.root_hex())
+    }
+    
+    async fn rebuild_merkle_tree(&self, chain_id: &str) -> Result<String, StorageError> {
+        let mut chains = self.chains.lock().await;
+        chains.remove(chain_id);
+        Ok(self.chain_cache(&mut chains, chain_id).await?.merkle_tree.root_hex())
+    }
+    
+    async fn replace_with_tombstone(
+        &self,
+        event_ids: &[String],
+        tombstone: LedgerRecord,
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        // The tombstone takes the range's place in chain order
+        let first_seq: Option<i64> = sqlx::query(&format!(
+            "SELECT MIN(seq) AS seq FROM {} WHERE chain_id = $1 AND event_id = ANY($2)",
+            self.table_name
+        ))
+        .bind(&tombstone.chain_id)
+        .bind(event_ids)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?
+        .get("seq");
+        
+        sqlx::query(&format!("DELETE FROM {} WHERE chain_id = $1 AND event_id = ANY($2)", self.table_name))
+            .bind(&tombstone.chain_id)
+            .bind(event_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        self.insert_record(&mut *tx, &tombstone).await?;
+        if let Some(seq) = first_seq {
+            sqlx::query(&format!(
+                "UPDATE {} SET seq = $1 WHERE chain_id = $2 AND event_id = $3",
+                self.table_name
+            ))
+            .bind(seq)
+            .bind(&tombstone.chain_id)
+            .bind(&tombstone.event_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+        
+        // Reloaded on next use, from the table as it now stands
+        let mut chains = self.chains.lock().await;
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        chains.remove(&tombstone.chain_id);
+        Ok(())
+    }
+    
+    async fn stats(&self, chain_id: &str) -> Result<StorageStats, StorageError> {
+        let mut chains = self.chains.lock().await;
+        Ok(self.chain_cache(&mut chains, chain_id).await?.stats.clone())
+    }
+    
+    async fn list_chains(&self) -> Result<Vec<ChainSummary>, StorageError> {
+        let query = format!(
+            r#"
+            SELECT c.chain_id, COUNT(*) AS count, MIN(c.timestamp) AS earliest, MAX(c.timestamp) AS latest,
+                   (SELECT f.event_id FROM {0} f WHERE f.chain_id = c.chain_id
+                    ORDER BY f.seq ASC LIMIT 1) AS first_hash,
+                   (SELECT h.event_id FROM {0} h WHERE h.chain_id = c.chain_id
+                    ORDER BY h.seq DESC LIMIT 1) AS latest_hash
+            FROM {0} c
+            GROUP BY c.chain_id
+            ORDER BY c.chain_id
+            "#,
+            self.table_name
+        );
+        
+        let rows = sqlx::query(&query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        
+        Ok(rows
+            .iter()
+            .map(|row| ChainSummary {
+                chain_id: row.get("chain_id"),
+                record_count: row.get::<i64, _>("count") as usize,
+                first_hash: row.get("first_hash"),
+                latest_hash: row.get("latest_hash"),
+                earliest: Some(row.get("earliest")),
+                latest: Some(row.get("latest")),
+            })
+            .collect())
+    }
+    
+    fn set_durability(&self, level: DurabilityLevel) -> Result<(), StorageError> {
+        *self.durability.lock().unwrap() = level;
+        Ok(())
+    }
+    
+    async fn flush_batch(&self) -> Result<(), StorageError> {
+        if *self.durability.lock().unwrap() != DurabilityLevel::FsyncBatch {
+            return Ok(());
+        }
+        // Taking a transaction id makes the commit write a WAL record, and a
+        // synchronous commit waits for all WAL up to it to be flushed
+        let mut tx = self.pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        sqlx::query("SELECT pg_current_xact_id()")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))
+    }
+}
+
+// `LedgerEvent::get_entity_id` over the event_data projection, where each
+// event type keeps its id under its own field
+const ENTITY_ID_SQL: &str = r#"
+    CASE event_data->>'event_type'
+        WHEN 'financial_transaction' THEN event_data->>'transaction_id'
+        WHEN 'compliance_alert' THEN event_data->>'alert_id'
+        WHEN 'account_creation' THEN event_data->>'account_id'
+        WHEN 'balance_adjustment' THEN event_data->>'adjustment_id'
+        WHEN 'audit_log' THEN event_data->>'log_id'
+        WHEN 'transaction_reversal' THEN event_data->>'reversal_id'
+        WHEN 'journal_entry' THEN event_data->>'entry_id'
+    END"#;
+
+fn record_from_row(row: &sqlx::postgres::PgRow) -> Result<LedgerRecord, StorageError> {
+    let schema_version = row.get::<i16, _>("schema_version") as u16;
+    let bytes = row.get::<Option<Vec<u8>>, _>("event_bytes");
+    Ok(LedgerRecord {
+        event_id: row.get("event_id"),
+        event: read_event(row, schema_version, bytes.as_deref())?,
+        metadata: row.get("metadata"),
+        timestamp: row.get("timestamp"),
+        previous_hash: row.get("previous_hash"),
+        chain_id: row.get("chain_id"),
+        signature: row.get("signature"),
+        violations: read_violations(row)?,
+        codec: row.get("codec"),
+        schema_version,
+        // The id of a migrated event is checked against what was stored
+        legacy_event_bytes: bytes.filter(|_| schema_version != CURRENT_SCHEMA_VERSION).map(hex::encode),
+        sequence: row.get::<Option<i64>, _>("sequence").map(|s| s as u64),
+        nonce: row.get("nonce"),
+        merkle_root_at_append: row.get("merkle_root_at_append"),
+        visibility: row.get("visibility"),
+    })
+}
+
+// event_data is kept as a JSON projection for queries; event_bytes holds the
+// codec-encoded form the event id was hashed from and is authoritative.
+// Records written before event_bytes existed only have event_data. Payloads
+// from an older schema version are migrated here, leaving the row untouched.
+fn read_event(
+    row: &sqlx::postgres::PgRow,
+    schema_version: u16,
+    bytes: Option<&[u8]>,
+) -> Result<crate::core::event::LedgerEvent, StorageError> {
+    if schema_version == CURRENT_SCHEMA_VERSION {
+        return match bytes {
+            Some(bytes) => codec_for_id(row.get("codec"))?.decode_event(bytes),
+            None => Ok(serde_json::from_value(row.get("event_data"))?),
+        };
+    }
+    let payload = match bytes {
+        Some(bytes) => codec_for_id(row.get("codec"))?.decode_payload(bytes)?,
+        None => row.get("event_data"),
+    };
+    Ok(migrate_record(schema_version, payload)?)
+}
+
+// Records written before the violations column existed have it as NULL
+fn read_violations(
+    row: &sqlx::postgres::PgRow,
+) -> Result<Vec<crate::compliance::validator::Violation>, StorageError> {
+    match row.get::<Option<serde_json::Value>, _>("violations") {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(Vec::new()),
     }
 }
@@ -0,0 +1,597 @@
+//! Encryption at rest for any `AppendOnlyStorage` backend.
+//!
+//! `EncryptionLayer` seals each record's event and metadata with AES-256-GCM
+//! before handing it to the wrapped backend. The stored record keeps its
+//! event id, previous hash, timestamp, chain id, sequence, nonce and
+//! signature in the clear, so the backend can still order, link and verify
+//! the chain. Event ids are hashed from the plaintext event by the ledger,
+//! and every read decrypts, so `DigitalLedger::verify_integrity` sees the
+//! same records with or without encryption.
+//!
+//! Records whose metadata names a subject under `SUBJECT_ID_METADATA` can
+//! instead be sealed with a key of that subject's own (see `SubjectKeys`).
+//! Destroying the subject's keys erases their records' payloads for good
+//! while the chain, which only links event ids, still verifies: the
+//! erasure record commits to each erased record's ciphertext hash, which
+//! its placeholder is checked against in place of its event.
+
+use async_trait::async_trait;
+use crate::core::event::{AuditLog, LedgerEvent};
+use crate::core::ledger::{IDEMPOTENCY_KEY_METADATA, RETENTION_TOMBSTONE_ACTION, SUBJECT_ID_METADATA};
+use crate::core::schema::{migrate_record, CURRENT_SCHEMA_VERSION};
+use crate::core::LedgerRecord;
+use crate::storage::append_only::{AppendOnlyStorage, ChainSummary, DurabilityLevel, IndexEntry, StorageError, TagFilter};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// `AuditLog` action of the envelope an encrypted record is stored as
+pub const ENCRYPTED_RECORD_ACTION: &str = "encrypted_record";
+
+/// `AuditLog` action of the placeholder read back for a record whose
+/// subject's keys were destroyed
+pub const ERASED_RECORD_ACTION: &str = "lawfully_erased";
+
+/// Envelope version whose associated data also covers the key id, subject
+/// id and schema version, so none of them can be changed, or a record
+/// moved under a subject key, without the seal failing. Envelopes without
+/// a version were written before and are opened as they were sealed.
+const ENVELOPE_VERSION: u64 = 2;
+
+/// Whether `record` reads as the placeholder of a crypto-shredded record.
+/// Its event no longer hashes to its id, so id checks compare its
+/// ciphertext hash with the one its erasure recorded instead; its links
+/// and Merkle leaf are unchanged. Anyone can store an event shaped like a
+/// placeholder, so this alone proves nothing.
+pub fn is_erased(record: &LedgerRecord) -> bool {
+    matches!(&record.event, LedgerEvent::AuditLog(log) if log.action == ERASED_RECORD_ACTION)
+}
+
+/// SHA-256 of the ciphertext still stored for an erased record, `None` for
+/// any other record
+pub fn erased_ciphertext_sha256(record: &LedgerRecord) -> Option<&str> {
+    match &record.event {
+        LedgerEvent::AuditLog(log) if log.action == ERASED_RECORD_ACTION => {
+            log.changes.get("ciphertext_sha256").and_then(|v| v.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// AES-256 key, wiped from memory on drop
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    pub fn from_slice(key: &[u8]) -> Result<Self, StorageError> {
+        let key: [u8; 32] = key
+            .try_into()
+            .map_err(|_| StorageError::Encryption(format!("key must be 32 bytes, got {}", key.len())))?;
+        Ok(Self(key))
+    }
+
+    fn aead_key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.0).expect("AES-256 key is 32 bytes"))
+    }
+}
+
+/// Encryption keys by id. New records are sealed with the active key and
+/// record its id, so after a rotation older records still open with the
+/// key they were written with for as long as it is kept here.
+#[derive(Clone)]
+pub struct EncryptionKeys {
+    active_id: String,
+    keys: HashMap<String, EncryptionKey>,
+}
+
+impl EncryptionKeys {
+    pub fn new(key_id: impl Into<String>, key: EncryptionKey) -> Self {
+        let active_id = key_id.into();
+        Self {
+            keys: HashMap::from([(active_id.clone(), key)]),
+            active_id,
+        }
+    }
+
+    /// Adds a retired key, used only to decrypt records sealed with it
+    pub fn with_key(mut self, key_id: impl Into<String>, key: EncryptionKey) -> Self {
+        self.keys.insert(key_id.into(), key);
+        self
+    }
+
+    /// Makes `key` the one new records are sealed with, keeping the
+    /// previous keys for reads
+    pub fn rotate(&mut self, key_id: impl Into<String>, key: EncryptionKey) {
+        let key_id = key_id.into();
+        self.keys.insert(key_id.clone(), key);
+        self.active_id = key_id;
+    }
+
+    pub fn active_id(&self) -> &str {
+        &self.active_id
+    }
+}
+
+/// Keys for crypto-shredding, one or more per data subject.
+///
+/// Synchronous because records are sealed while the storage holds the
+/// chain head; implementations over a remote key service should cache.
+/// Destroyed keys must be unrecoverable, including from backups, for
+/// erasure to hold.
+pub trait SubjectKeys: Send + Sync {
+    /// Id and key of the key new records of `subject_id` are sealed with,
+    /// creating one if the subject has none
+    fn active_key(&self, subject_id: &str) -> Result<(String, EncryptionKey), StorageError>;
+    /// A key by id, `None` once destroyed
+    fn key(&self, key_id: &str) -> Result<Option<EncryptionKey>, StorageError>;
+    /// Whether `key_id` was issued and has since been destroyed. A key id
+    /// that was never issued is not destroyed, so a record naming one is
+    /// not taken for erased.
+    fn destroyed(&self, key_id: &str) -> Result<bool, StorageError>;
+    /// Destroys every key of `subject_id`, returning how many there were.
+    /// Records the subject appends afterwards get a fresh key.
+    fn destroy(&self, subject_id: &str) -> Result<usize, StorageError>;
+}
+
+/// `SubjectKeys` held in process memory. Every key is lost on restart,
+/// which erases every subject, so this suits tests and ephemeral ledgers.
+pub struct InMemorySubjectKeys {
+    rng: SystemRandom,
+    table: Mutex<SubjectKeyTable>,
+}
+
+#[derive(Default)]
+struct SubjectKeyTable {
+    // key id -> (subject id, key)
+    keys: HashMap<String, (String, EncryptionKey)>,
+    // subject id -> id of the key new records are sealed with
+    active: HashMap<String, String>,
+    destroyed: HashSet<String>,
+}
+
+impl InMemorySubjectKeys {
+    pub fn new() -> Self {
+        Self {
+            rng: SystemRandom::new(),
+            table: Mutex::new(SubjectKeyTable::default()),
+        }
+    }
+}
+
+impl Default for InMemorySubjectKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubjectKeys for InMemorySubjectKeys {
+    fn active_key(&self, subject_id: &str) -> Result<(String, EncryptionKey), StorageError> {
+        let mut table = self.table.lock().unwrap();
+        if let Some(key_id) = table.active.get(subject_id) {
+            if let Some((_, key)) = table.keys.get(key_id) {
+                return Ok((key_id.clone(), key.clone()));
+            }
+        }
+
+        let mut bytes = [0u8; 32];
+        self.rng
+            .fill(&mut bytes)
+            .map_err(|_| StorageError::Encryption("system random number generator failed".to_string()))?;
+        let key = EncryptionKey::new(bytes);
+        bytes.zeroize();
+        let key_id = uuid::Uuid::new_v4().to_string();
+        table.keys.insert(key_id.clone(), (subject_id.to_string(), key.clone()));
+        table.active.insert(subject_id.to_string(), key_id.clone());
+        Ok((key_id, key))
+    }
+
+    fn key(&self, key_id: &str) -> Result<Option<EncryptionKey>, StorageError> {
+        Ok(self.table.lock().unwrap().keys.get(key_id).map(|(_, key)| key.clone()))
+    }
+
+    fn destroyed(&self, key_id: &str) -> Result<bool, StorageError> {
+        Ok(self.table.lock().unwrap().destroyed.contains(key_id))
+    }
+
+    fn destroy(&self, subject_id: &str) -> Result<usize, StorageError> {
+        let mut table = self.table.lock().unwrap();
+        table.active.remove(subject_id);
+        let key_ids: Vec<String> = table
+            .keys
+            .iter()
+            .filter(|(_, (subject, _))| subject == subject_id)
+            .map(|(key_id, _)| key_id.clone())
+            .collect();
+        for key_id in &key_ids {
+            table.keys.remove(key_id);
+        }
+        table.destroyed.extend(key_ids.iter().cloned());
+        Ok(key_ids.len())
+    }
+}
+
+/// Wraps a backend so record payloads are stored encrypted.
+///
+/// Retention tombstones are stored as-is: they carry only ids and Merkle
+/// data, and the backend must read their bridge hash to verify links.
+/// Records stored before encryption was enabled are returned unchanged, so
+/// a chain can be switched over without rewriting it. Index keys and
+/// idempotency keys stay in the clear so lookups still work.
+///
+/// Filtering by entity or event type happens after decryption, as does
+/// `stats`, so those cost a scan of the matching time range.
+///
+/// With `with_subject_keys`, records naming a subject are sealed with the
+/// subject's key, and once those keys are destroyed they read back as an
+/// `ERASED_RECORD_ACTION` placeholder with the original ids and links.
+pub struct EncryptionLayer<S> {
+    inner: S,
+    keys: RwLock<EncryptionKeys>,
+    subject_keys: Option<Arc<dyn SubjectKeys>>,
+    rng: SystemRandom,
+}
+
+impl<S: AppendOnlyStorage> EncryptionLayer<S> {
+    pub fn new(inner: S, keys: EncryptionKeys) -> Self {
+        Self {
+            inner,
+            keys: RwLock::new(keys),
+            subject_keys: None,
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Seals records naming a subject with that subject's key. Share the
+    /// same `SubjectKeys` with `LedgerConfig::with_subject_keys` so
+    /// `DigitalLedger::erase_subject` destroys them.
+    pub fn with_subject_keys(mut self, subject_keys: Arc<dyn SubjectKeys>) -> Self {
+        self.subject_keys = Some(subject_keys);
+        self
+    }
+
+    /// Seals records appended from now on with `key`
+    pub fn rotate_key(&self, key_id: impl Into<String>, key: EncryptionKey) {
+        self.keys.write().unwrap().rotate(key_id, key);
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn encrypt(&self, mut record: LedgerRecord) -> Result<LedgerRecord, StorageError> {
+        if is_tombstone(&record) {
+            return Ok(record);
+        }
+
+        let mut payload = serde_json::to_vec(&serde_json::json!({
+            "event": record.event,
+            "metadata": record.metadata,
+        }))?;
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| StorageError::Encryption("system random number generator failed".to_string()))?;
+
+        let subject_id = record
+            .metadata
+            .get(SUBJECT_ID_METADATA)
+            .and_then(|s| s.as_str())
+            .map(str::to_string);
+        let (key_id, key) = match (&subject_id, &self.subject_keys) {
+            (Some(subject_id), Some(subject_keys)) => subject_keys.active_key(subject_id)?,
+            _ => {
+                let keys = self.keys.read().unwrap();
+                let key = keys
+                    .keys
+                    .get(&keys.active_id)
+                    .ok_or_else(|| StorageError::Encryption(format!("no key {}", keys.active_id)))?;
+                (keys.active_id.clone(), key.clone())
+            }
+        };
+        let subject_key = subject_id.clone().filter(|_| self.subject_keys.is_some());
+        let mut envelope = serde_json::json!({
+            "envelope_version": ENVELOPE_VERSION,
+            "key_id": key_id,
+            "subject_id": subject_key,
+            "nonce": hex::encode(nonce),
+            "schema_version": record.schema_version,
+        });
+        key.aead_key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(associated_data(&record, &envelope)),
+                &mut payload,
+            )
+            .map_err(|_| StorageError::Encryption(format!("failed to seal record {}", record.event_id)))?;
+        envelope["ciphertext"] = hex::encode(&payload).into();
+
+        // The idempotency key is needed in the clear for lookups, and the
+        // subject id to find a subject's records
+        let idempotency_key = record.idempotency_key().map(str::to_string);
+        let mut clear = serde_json::Map::new();
+        if let Some(key) = idempotency_key {
+            clear.insert(IDEMPOTENCY_KEY_METADATA.to_string(), key.into());
+        }
+        if let Some(subject_id) = &subject_id {
+            clear.insert(SUBJECT_ID_METADATA.to_string(), subject_id.clone().into());
+        }
+        record.metadata = if clear.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::Value::Object(clear)
+        };
+        record.event = LedgerEvent::AuditLog(AuditLog {
+            log_id: record.event_id.clone(),
+            action: ENCRYPTED_RECORD_ACTION.to_string(),
+            actor: "ledger".to_string(),
+            resource: record.chain_id.clone(),
+            changes: envelope,
+            ip_address: None,
+            user_agent: None,
+            timestamp: record.timestamp,
+        });
+        record.schema_version = CURRENT_SCHEMA_VERSION;
+        Ok(record)
+    }
+
+    fn decrypt(&self, mut record: LedgerRecord) -> Result<LedgerRecord, StorageError> {
+        let changes = match &record.event {
+            LedgerEvent::AuditLog(log) if log.action == ENCRYPTED_RECORD_ACTION => &log.changes,
+            _ => return Ok(record),
+        };
+        let field = |name: &str| {
+            changes.get(name).and_then(|v| v.as_str()).ok_or_else(|| {
+                StorageError::Encryption(format!("record {} has no {}", record.event_id, name))
+            })
+        };
+        let key_id = field("key_id")?;
+        let nonce: [u8; NONCE_LEN] = hex::decode(field("nonce")?)
+            .ok()
+            .and_then(|nonce| nonce.try_into().ok())
+            .ok_or_else(|| StorageError::Encryption(format!("record {} has a malformed nonce", record.event_id)))?;
+        let mut payload = hex::decode(field("ciphertext")?)
+            .map_err(|e| StorageError::Encryption(format!("record {} ciphertext: {}", record.event_id, e)))?;
+        let schema_version = changes
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .map_or(CURRENT_SCHEMA_VERSION, |v| v as u16);
+        let subject_id = changes.get("subject_id").and_then(|v| v.as_str());
+
+        let key = match subject_id {
+            Some(subject_id) => {
+                let subject_keys = self.subject_keys.as_ref().ok_or_else(|| {
+                    StorageError::Encryption(format!(
+                        "record {} is sealed with a subject key, but no subject keys are configured",
+                        record.event_id
+                    ))
+                })?;
+                match subject_keys.key(key_id)? {
+                    Some(key) => key,
+                    // Only a key that existed and was destroyed erases the
+                    // record; an envelope naming an unknown key is an error
+                    None if subject_keys.destroyed(key_id)? => {
+                        let subject_id = subject_id.to_string();
+                        let key_id = key_id.to_string();
+                        let ciphertext_sha256 = hex::encode(Sha256::digest(&payload));
+                        return Ok(erased(record, subject_id, key_id, ciphertext_sha256));
+                    }
+                    None => {
+                        return Err(StorageError::Encryption(format!(
+                            "no subject key {} for record {}",
+                            key_id, record.event_id
+                        )))
+                    }
+                }
+            }
+            None => {
+                let keys = self.keys.read().unwrap();
+                keys.keys.get(key_id).cloned().ok_or_else(|| {
+                    StorageError::Encryption(format!("no key {} for record {}", key_id, record.event_id))
+                })?
+            }
+        };
+        let plaintext = key
+            .aead_key()
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(associated_data(&record, changes)),
+                &mut payload,
+            )
+            .map_err(|_| StorageError::Encryption(format!("failed to open record {}", record.event_id)))?;
+
+        let mut payload: serde_json::Value = serde_json::from_slice(plaintext)?;
+        let event = payload["event"].take();
+        record.event = if schema_version == CURRENT_SCHEMA_VERSION {
+            serde_json::from_value(event)?
+        } else {
+            migrate_record(schema_version, event)?
+        };
+        record.metadata = payload["metadata"].take();
+        record.schema_version = schema_version;
+        Ok(record)
+    }
+
+    fn decrypt_all(&self, records: Vec<LedgerRecord>) -> Result<Vec<LedgerRecord>, StorageError> {
+        records.into_iter().map(|record| self.decrypt(record)).collect()
+    }
+}
+
+/// The placeholder for a record whose subject key was destroyed. It keeps
+/// the record's ids, links and clear metadata, and the hash of the
+/// ciphertext that is still stored, which is what its erasure recorded.
+fn erased(mut record: LedgerRecord, subject_id: String, key_id: String, ciphertext_sha256: String) -> LedgerRecord {
+    record.event = LedgerEvent::AuditLog(AuditLog {
+        log_id: record.event_id.clone(),
+        action: ERASED_RECORD_ACTION.to_string(),
+        actor: "ledger".to_string(),
+        resource: record.chain_id.clone(),
+        changes: serde_json::json!({
+            "subject_id": subject_id,
+            "key_id": key_id,
+            "ciphertext_sha256": ciphertext_sha256,
+        }),
+        ip_address: None,
+        user_agent: None,
+        timestamp: record.timestamp,
+    });
+    record
+}
+
+/// Binds the ciphertext to its record, so it can't be moved to another
+/// one, and from `ENVELOPE_VERSION` on to the envelope fields saying which
+/// key sealed it
+fn associated_data(record: &LedgerRecord, envelope: &serde_json::Value) -> Vec<u8> {
+    let mut data = format!("{}\n{}", record.chain_id, record.event_id);
+    if envelope.get("envelope_version").and_then(|v| v.as_u64()) >= Some(ENVELOPE_VERSION) {
+        let field = |name: &str| envelope.get(name).map_or_else(String::new, |v| v.to_string());
+        data.push_str(&format!(
+            "\n{}\n{}\n{}",
+            field("key_id"),
+            field("subject_id"),
+            field("schema_version")
+        ));
+    }
+    data.into_bytes()
+}
+
+fn is_tombstone(record: &LedgerRecord) -> bool {
+    matches!(&record.event, LedgerEvent::AuditLog(log) if log.action == RETENTION_TOMBSTONE_ACTION)
+}
+
+#[async_trait]
+impl<S: AppendOnlyStorage> AppendOnlyStorage for EncryptionLayer<S> {
+    async fn append(&self, record: LedgerRecord) -> Result<(), StorageError> {
+        self.inner.append(self.encrypt(record)?).await
+    }
+
+    async fn append_atomic(
+        &self,
+        record: LedgerRecord,
+        index_entries: &[IndexEntry],
+    ) -> Result<(), StorageError> {
+        self.inner.append_atomic(self.encrypt(record)?, index_entries).await
+    }
+
+    async fn records_by_index(
+        &self,
+        chain_id: &str,
+        index: &str,
+        key: &str,
+    ) -> Result<Vec<LedgerRecord>, StorageError> {
+        self.decrypt_all(self.inner.records_by_index(chain_id, index, key).await?)
+    }
+
+    async fn get(&self, chain_id: &str, event_id: &str) -> Result<Option<LedgerRecord>, StorageError> {
+        self.inner.get(chain_id, event_id).await?.map(|record| self.decrypt(record)).transpose()
+    }
+
+    async fn get_by_idempotency_key(&self, chain_id: &str, key: &str) -> Result<Option<LedgerRecord>, StorageError> {
+        self.inner
+            .get_by_idempotency_key(chain_id, key)
+            .await?
+            .map(|record| self.decrypt(record))
+            .transpose()
+    }
+
+    async fn query_records(
+        &self,
+        chain_id: &str,
+        entity_id: Option<&str>,
+        start_time: Option<chrono::DateTime<chrono::Utc>>,
+        end_time: Option<chrono::DateTime<chrono::Utc>>,
+        event_types: Option<&[String]>,
+        tags: Option<&TagFilter>,
+        caller_scopes: Option<&[String]>,
+    ) -> Result<Vec<LedgerRecord>, StorageError> {
+        // Time range, tags and visibility can be filtered on the stored
+        // form; tags are matched through the index, whose keys stay in the
+        // clear, and visibility is never encrypted
+        let records = self.decrypt_all(
+            self.inner
+                .query_records(chain_id, None, start_time, end_time, None, tags, caller_scopes)
+                .await?,
+        )?;
+        Ok(records
+            .into_iter()
+            .filter(|record| entity_id.map_or(true, |id| record.event.get_entity_id() == id))
+            .filter(|record| {
+                event_types.map_or(true, |types| types.iter().any(|t| t == record.event.event_type_name()))
+            })
+            .collect())
+    }
+
+    async fn records_from(&self, chain_id: &str, event_id: &str) -> Result<Vec<LedgerRecord>, StorageError> {
+        self.decrypt_all(self.inner.records_from(chain_id, event_id).await?)
+    }
+
+    async fn verify_chain(&self, chain_id: &str) -> Result<bool, StorageError> {
+        self.inner.verify_chain(chain_id).await
+    }
+
+    async fn list_chains(&self) -> Result<Vec<ChainSummary>, StorageError> {
+        self.inner.list_chains().await
+    }
+
+    fn set_durability(&self, level: DurabilityLevel) -> Result<(), StorageError> {
+        self.inner.set_durability(level)
+    }
+
+    async fn flush_batch(&self) -> Result<(), StorageError> {
+        self.inner.flush_batch().await
+    }
+
+    async fn get_latest_hash(&self, chain_id: &str) -> Result<Option<String>, StorageError> {
+        self.inner.get_latest_hash(chain_id).await
+    }
+
+    async fn append_linked(
+        &self,
+        chain_id: &str,
+        build: &(dyn Fn(Option<&LedgerRecord>) -> Result<LedgerRecord, StorageError> + Send + Sync),
+        index_entries: &[IndexEntry],
+    ) -> Result<LedgerRecord, StorageError> {
+        let seal = |head: Option<&LedgerRecord>| {
+            let head = head.map(|head| self.decrypt(head.clone())).transpose()?;
+            self.encrypt(build(head.as_ref())?)
+        };
+        let record = self.inner.append_linked(chain_id, &seal, index_entries).await?;
+        self.decrypt(record)
+    }
+
+    async fn append_linked_batch(
+        &self,
+        chain_id: &str,
+        build: &(dyn Fn(usize, Option<&LedgerRecord>, &str) -> Result<LedgerRecord, StorageError> + Send + Sync),
+        index_entries: &[Vec<IndexEntry>],
+    ) -> Result<Vec<LedgerRecord>, StorageError> {
+        let seal = |position: usize, head: Option<&LedgerRecord>, merkle_root: &str| {
+            let head = head.map(|head| self.decrypt(head.clone())).transpose()?;
+            self.encrypt(build(position, head.as_ref(), merkle_root)?)
+        };
+        let records = self.inner.append_linked_batch(chain_id, &seal, index_entries).await?;
+        self.decrypt_all(records)
+    }
+
+    async fn get_merkle_root(&self, chain_id: &str) -> Result<String, StorageError> {
+        self.inner.get_merkle_root(chain_id).await
+    }
+
+    async fn rebuild_merkle_tree(&self, chain_id: &str) -> Result<String, StorageError> {
+        self.inner.rebuild_merkle_tree(chain_id).await
+    }
+
+    async fn replace_with_tombstone(
+        &self,
+        event_ids: &[String],
+        tombstone: LedgerRecord,
+    ) -> Result<(), StorageError> {
+        self.inner.replace_with_tombstone(event_ids, self.encrypt(tombstone)?).await
+    }
+}
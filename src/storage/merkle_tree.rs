@@ -0,0 +1,283 @@
+use sha2::{Digest, Sha256};
+
+pub type MerkleHash = [u8; 32];
+
+/// Version of the tree hashing format. Version 1 hashed leaves and interior
+/// nodes identically; version 2 prefixes leaves with `LEAF_PREFIX` and nodes
+/// with `NODE_PREFIX` (RFC 6962 domain separation), so a node can never be
+/// passed off as a leaf. Every root changes between versions: roots kept
+/// outside this process (tombstone subroots, sealed bundles, exported
+/// proofs) must be compared with the version they were produced under.
+pub const MERKLE_TREE_VERSION: u8 = 2;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Append-only Merkle tree kept as the roots of its perfect subtrees.
+///
+/// The tree shape follows RFC 6962: the root over `n` leaves splits at the
+/// largest power of two below `n`. Appending merges equal-sized subtrees,
+/// touching O(log n) nodes, and the root is cached so reading it is O(1).
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalMerkleTree {
+    leaf_count: usize,
+    // (subtree size, subtree root), largest first
+    subtrees: Vec<(usize, MerkleHash)>,
+    root: Option<MerkleHash>,
+}
+
+impl IncrementalMerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_leaves<'a>(leaves: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut tree = Self::new();
+        for leaf in leaves {
+            tree.push(leaf);
+        }
+        tree
+    }
+
+    pub fn push(&mut self, leaf: &str) {
+        let mut node = (1, hash_leaf(leaf.as_bytes()));
+        while let Some(&(size, left)) = self.subtrees.last() {
+            if size != node.0 {
+                break;
+            }
+            self.subtrees.pop();
+            node = (size * 2, hash_node(&left, &node.1));
+        }
+        self.subtrees.push(node);
+        self.leaf_count += 1;
+        self.root = Some(self.fold_root());
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaf_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaf_count == 0
+    }
+
+    pub fn root(&self) -> MerkleHash {
+        self.root.unwrap_or_else(empty_root)
+    }
+
+    pub fn root_hex(&self) -> String {
+        hex::encode(self.root())
+    }
+
+    fn fold_root(&self) -> MerkleHash {
+        let mut iter = self.subtrees.iter().rev();
+        let Some(&(_, mut root)) = iter.next() else {
+            return empty_root();
+        };
+        for (_, left) in iter {
+            root = hash_node(left, &root);
+        }
+        root
+    }
+}
+
+/// Computes the root over all leaves from scratch, for checking the cached root.
+pub fn compute_root(leaves: &[&str]) -> MerkleHash {
+    match leaves.len() {
+        0 => empty_root(),
+        1 => hash_leaf(leaves[0].as_bytes()),
+        n => {
+            let split = n.next_power_of_two() / 2;
+            hash_node(&compute_root(&leaves[..split]), &compute_root(&leaves[split..]))
+        }
+    }
+}
+
+/// Audit path proving that `leaves[index]` is included under
+/// `compute_root(leaves)`, ordered from the leaf's sibling up to the root.
+/// Returns `None` when `index` is out of range.
+pub fn inclusion_proof(leaves: &[&str], index: usize) -> Option<Vec<MerkleHash>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let (mut leaves, mut index) = (leaves, index);
+    let mut siblings = Vec::new();
+    while leaves.len() > 1 {
+        let split = leaves.len().next_power_of_two() / 2;
+        if index < split {
+            siblings.push(compute_root(&leaves[split..]));
+            leaves = &leaves[..split];
+        } else {
+            siblings.push(compute_root(&leaves[..split]));
+            leaves = &leaves[split..];
+            index -= split;
+        }
+    }
+    // Collected root-down; the path runs leaf-up
+    siblings.reverse();
+    Some(siblings)
+}
+
+/// Checks an audit path from `inclusion_proof` against a root, following the
+/// verification algorithm of RFC 9162 section 2.1.3.2.
+pub fn verify_inclusion(
+    leaf: &str,
+    index: usize,
+    tree_size: usize,
+    proof: &[MerkleHash],
+    root: &MerkleHash,
+) -> bool {
+    if index >= tree_size {
+        return false;
+    }
+    let (mut fn_, mut sn) = (index, tree_size - 1);
+    let mut hash = hash_leaf(leaf.as_bytes());
+    for sibling in proof {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            hash = hash_node(sibling, &hash);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            hash = hash_node(&hash, sibling);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && &hash == root
+}
+
+/// Proof that the tree over the first `from_size` leaves is a prefix of the
+/// tree over all of `leaves`, following RFC 6962 section 2.1.2. Returns
+/// `None` when `from_size` is out of range. The proof from an empty tree is
+/// empty.
+pub fn consistency_proof(leaves: &[&str], from_size: usize) -> Option<Vec<MerkleHash>> {
+    if from_size > leaves.len() {
+        return None;
+    }
+    if from_size == 0 {
+        return Some(Vec::new());
+    }
+    let mut proof = Vec::new();
+    subproof(from_size, leaves, true, &mut proof);
+    Some(proof)
+}
+
+// SUBPROOF(m, D[n], b) of RFC 6962; `complete` is b, whether the subtree
+// over the first m leaves is the old tree itself
+fn subproof(m: usize, leaves: &[&str], complete: bool, proof: &mut Vec<MerkleHash>) {
+    let n = leaves.len();
+    if m == n {
+        if !complete {
+            proof.push(compute_root(leaves));
+        }
+        return;
+    }
+    let split = n.next_power_of_two() / 2;
+    if m <= split {
+        subproof(m, &leaves[..split], complete, proof);
+        proof.push(compute_root(&leaves[split..]));
+    } else {
+        subproof(m - split, &leaves[split..], false, proof);
+        proof.push(compute_root(&leaves[..split]));
+    }
+}
+
+/// Checks a proof from `consistency_proof` between two roots, following the
+/// verification algorithm of RFC 9162 section 2.1.4.2.
+pub fn verify_consistency(
+    from_size: usize,
+    to_size: usize,
+    proof: &[MerkleHash],
+    from_root: &MerkleHash,
+    to_root: &MerkleHash,
+) -> bool {
+    if from_size > to_size {
+        return false;
+    }
+    if from_size == 0 {
+        return proof.is_empty();
+    }
+    if from_size == to_size {
+        return proof.is_empty() && from_root == to_root;
+    }
+    if proof.is_empty() {
+        return false;
+    }
+
+    // An old tree of perfect size is a node of the new one and left out of
+    // the proof
+    let mut path = Vec::with_capacity(proof.len() + 1);
+    if from_size.is_power_of_two() {
+        path.push(*from_root);
+    }
+    path.extend_from_slice(proof);
+
+    let (mut fn_, mut sn) = (from_size - 1, to_size - 1);
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    let (mut fr, mut sr) = (path[0], path[0]);
+    for c in &path[1..] {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            fr = hash_node(c, &fr);
+            sr = hash_node(c, &sr);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            sr = hash_node(&sr, c);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && &fr == from_root && &sr == to_root
+}
+
+/// Consistency proof between two tree sizes, hex-encoded for transport
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConsistencyProof {
+    pub from_size: usize,
+    pub to_size: usize,
+    pub from_root: String,
+    pub to_root: String,
+    pub path: Vec<String>,
+}
+
+/// Inclusion proof for one record, hex-encoded for transport
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MerkleProof {
+    pub event_id: String,
+    pub leaf_index: usize,
+    pub tree_size: usize,
+    pub path: Vec<String>,
+    pub root: String,
+}
+
+pub fn hash_leaf(data: &[u8]) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+pub fn hash_node(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn empty_root() -> MerkleHash {
+    Sha256::digest([]).into()
+}
@@ -0,0 +1,97 @@
+//! Metric names and recording helpers for ledger operations.
+//!
+//! Recording is a no-op unless the `metrics` feature is enabled, in which case
+//! values go to whatever recorder is installed for the `metrics` crate (for
+//! example a Prometheus exporter). Names and labels are stable so dashboards
+//! can rely on them.
+
+use crate::compliance::validator::Violation;
+use crate::core::LedgerError;
+use std::time::Duration;
+
+/// Counter of records appended
+pub const APPENDS_TOTAL: &str = "ledger_appends_total";
+/// Counter of rejected appends, labelled by `reason`
+pub const APPENDS_REJECTED_TOTAL: &str = "ledger_appends_rejected_total";
+/// Counter of compliance violations, labelled by `severity` and `rule_id`
+pub const COMPLIANCE_VIOLATIONS_TOTAL: &str = "ledger_compliance_violations_total";
+/// Histogram of `append_event` latency in seconds
+pub const APPEND_DURATION_SECONDS: &str = "ledger_append_duration_seconds";
+/// Histogram of `verify_integrity` duration in seconds
+pub const VERIFY_INTEGRITY_DURATION_SECONDS: &str = "ledger_verify_integrity_duration_seconds";
+/// Counter of observer notifications dropped on a full queue, labelled by `observer`
+pub const OBSERVER_DROPPED_TOTAL: &str = "ledger_observer_dropped_total";
+
+pub const LABEL_REASON: &str = "reason";
+pub const LABEL_SEVERITY: &str = "severity";
+pub const LABEL_RULE_ID: &str = "rule_id";
+pub const LABEL_OBSERVER: &str = "observer";
+
+pub fn rejection_reason(error: &LedgerError) -> &'static str {
+    match error {
+        LedgerError::ComplianceViolation { .. } => "compliance_violation",
+        LedgerError::StorageError(_) => "storage_error",
+        LedgerError::ValidationError { .. } => "validation_error",
+        LedgerError::LedgerSealed => "ledger_sealed",
+        LedgerError::ImportRejected(_) => "import_rejected",
+        LedgerError::IdempotencyConflict { .. } => "idempotency_conflict",
+        LedgerError::NotSealed => "not_sealed",
+        LedgerError::DuplicateEvent { .. } => "duplicate_event",
+        LedgerError::AppendQueueClosed => "append_queue_closed",
+        LedgerError::EnrichmentFailed(_) => "enrichment_failed",
+        LedgerError::TemporarilyReadOnly => "temporarily_read_only",
+        LedgerError::TimestampOutOfBounds { .. } => "timestamp_out_of_bounds",
+        LedgerError::EventTypeNotAllowed { .. } => "event_type_not_allowed",
+        LedgerError::NotQuarantined { .. } => "not_quarantined",
+        LedgerError::ChainNotFound { .. } => "chain_not_found",
+        LedgerError::MoneyError(_) => "money_error",
+        LedgerError::ReplicationGap { .. } => "replication_gap",
+        LedgerError::ReplicationFork { .. } => "replication_fork",
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub fn record_append(elapsed: Duration) {
+    metrics::counter!(APPENDS_TOTAL).increment(1);
+    metrics::histogram!(APPEND_DURATION_SECONDS).record(elapsed.as_secs_f64());
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn record_append(_elapsed: Duration) {}
+
+#[cfg(feature = "metrics")]
+pub fn record_rejection(error: &LedgerError) {
+    metrics::counter!(APPENDS_REJECTED_TOTAL, LABEL_REASON => rejection_reason(error)).increment(1);
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn record_rejection(_error: &LedgerError) {}
+
+#[cfg(feature = "metrics")]
+pub fn record_violation(violation: &Violation) {
+    metrics::counter!(
+        COMPLIANCE_VIOLATIONS_TOTAL,
+        LABEL_SEVERITY => format!("{:?}", violation.severity),
+        LABEL_RULE_ID => violation.rule_id.clone()
+    )
+    .increment(1);
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn record_violation(_violation: &Violation) {}
+
+#[cfg(feature = "metrics")]
+pub fn record_observer_drop(observer: &str) {
+    metrics::counter!(OBSERVER_DROPPED_TOTAL, LABEL_OBSERVER => observer.to_string()).increment(1);
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn record_observer_drop(_observer: &str) {}
+
+#[cfg(feature = "metrics")]
+pub fn record_verify_integrity(elapsed: Duration) {
+    metrics::histogram!(VERIFY_INTEGRITY_DURATION_SECONDS).record(elapsed.as_secs_f64());
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn record_verify_integrity(_elapsed: Duration) {}
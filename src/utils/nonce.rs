@@ -0,0 +1,55 @@
+use ring::rand::{SecureRandom, SystemRandom};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of per-record nonces, injectable like `Clock` so tests get
+/// reproducible records.
+pub trait NonceSource: Send + Sync {
+    /// A fresh nonce, hex encoded
+    fn nonce(&self) -> String;
+}
+
+/// 128 random bits per nonce from the operating system
+pub struct SystemNonceSource {
+    rng: SystemRandom,
+}
+
+impl SystemNonceSource {
+    pub fn new() -> Self {
+        Self { rng: SystemRandom::new() }
+    }
+}
+
+impl Default for SystemNonceSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NonceSource for SystemNonceSource {
+    fn nonce(&self) -> String {
+        let mut bytes = [0u8; 16];
+        self.rng
+            .fill(&mut bytes)
+            .expect("system random number generator failed");
+        hex::encode(bytes)
+    }
+}
+
+/// Nonces that count up from a seed, for deterministic tests.
+pub struct MockNonceSource {
+    next: AtomicU64,
+}
+
+impl MockNonceSource {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            next: AtomicU64::new(seed),
+        }
+    }
+}
+
+impl NonceSource for MockNonceSource {
+    fn nonce(&self) -> String {
+        format!("{:032x}", self.next.fetch_add(1, Ordering::Relaxed))
+    }
+}
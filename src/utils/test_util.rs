@@ -0,0 +1,167 @@
+//! Proptest strategies for ledger events, for property tests here and in
+//! downstream crates. Only compiled with the `test-util` feature.
+#![cfg(feature = "test-util")]
+
+use crate::core::event::{
+    currency_minor_units, AccountCreation, AccountType, AdjustmentReason, AuditLog,
+    BalanceAdjustment, ComplianceLevel, FinancialTransaction, LedgerEvent, Money,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use proptest::prelude::*;
+use rust_decimal::Decimal;
+
+const CURRENCIES: &[&str] = &["USD", "EUR", "GBP", "JPY", "KWD"];
+
+pub fn arb_timestamp() -> impl Strategy<Value = DateTime<Utc>> {
+    // 2000-01-01 through 2100-01-01, whole seconds so JSON round-trips exactly
+    (946_684_800i64..4_102_444_800i64).prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+pub fn arb_currency() -> impl Strategy<Value = String> {
+    prop::sample::select(CURRENCIES).prop_map(str::to_string)
+}
+
+pub fn arb_id() -> impl Strategy<Value = String> {
+    "[a-z0-9]{1,16}"
+}
+
+/// Non-negative amounts with a valid three-letter code and that currency's
+/// precision
+pub fn arb_money() -> impl Strategy<Value = Money> {
+    (0i64..1_000_000_000_000, arb_currency()).prop_map(|(units, currency_code)| {
+        let scale = currency_minor_units(&currency_code) as u32;
+        Money::with_currency_defaults(Decimal::new(units, scale), currency_code)
+    })
+}
+
+/// Money that breaks at least one field constraint: a negative amount or a
+/// currency code that isn't three characters
+pub fn arb_invalid_money() -> impl Strategy<Value = Money> {
+    prop_oneof![
+        (1i64..1_000_000_000, arb_currency()).prop_map(|(units, currency_code)| Money {
+            amount: Decimal::new(-units, 2),
+            currency_code,
+            precision: 2,
+        }),
+        (0i64..1_000_000_000, "[A-Z]{0,2}|[A-Z]{4,6}").prop_map(|(units, currency_code)| Money {
+            amount: Decimal::new(units, 2),
+            currency_code,
+            precision: 2,
+        }),
+    ]
+}
+
+fn financial_transaction_with(
+    money: impl Strategy<Value = Money>,
+) -> impl Strategy<Value = FinancialTransaction> {
+    (arb_id(), arb_id(), arb_id(), money, ".{0,32}", arb_timestamp(), prop::collection::vec(arb_id(), 0..3))
+        .prop_map(|(transaction_id, from_account, to_account, amount, description, timestamp, tags)| {
+            FinancialTransaction {
+                transaction_id,
+                from_account,
+                to_account,
+                currency: amount.currency_code.clone(),
+                amount,
+                description,
+                metadata: serde_json::json!({}),
+                timestamp,
+                tags,
+                settlement_amount: None,
+                exchange_rate: None,
+            }
+        })
+}
+
+pub fn arb_financial_transaction() -> impl Strategy<Value = FinancialTransaction> {
+    financial_transaction_with(arb_money())
+}
+
+pub fn arb_invalid_financial_transaction() -> impl Strategy<Value = FinancialTransaction> {
+    financial_transaction_with(arb_invalid_money())
+}
+
+pub fn arb_account_type() -> impl Strategy<Value = AccountType> {
+    prop_oneof![
+        Just(AccountType::Asset),
+        Just(AccountType::Liability),
+        Just(AccountType::Equity),
+        Just(AccountType::Revenue),
+        Just(AccountType::Expense),
+    ]
+}
+
+pub fn arb_account_creation() -> impl Strategy<Value = AccountCreation> {
+    (arb_id(), arb_account_type(), arb_id(), arb_money(), arb_timestamp()).prop_map(
+        |(account_id, account_type, owner_id, initial_balance, created_at)| AccountCreation {
+            account_id,
+            account_type,
+            owner_id,
+            initial_balance,
+            compliance_level: ComplianceLevel::LowRisk,
+            created_at,
+            metadata: serde_json::json!({}),
+        },
+    )
+}
+
+pub fn arb_balance_adjustment() -> impl Strategy<Value = BalanceAdjustment> {
+    let reason = prop_oneof![
+        Just(AdjustmentReason::Correction),
+        Just(AdjustmentReason::WriteOff),
+        Just(AdjustmentReason::Revaluation),
+        Just(AdjustmentReason::Regulatory),
+    ];
+    (arb_id(), arb_id(), reason, arb_money(), arb_id(), arb_id(), arb_timestamp()).prop_map(
+        |(adjustment_id, account_id, reason, amount, reference, authorized_by, timestamp)| {
+            BalanceAdjustment {
+                adjustment_id,
+                account_id,
+                reason,
+                amount,
+                reference,
+                authorized_by,
+                timestamp,
+            }
+        },
+    )
+}
+
+pub fn arb_audit_log() -> impl Strategy<Value = AuditLog> {
+    (arb_id(), arb_id(), arb_id(), arb_id(), arb_timestamp()).prop_map(
+        |(log_id, action, actor, resource, timestamp)| AuditLog {
+            log_id,
+            action,
+            actor,
+            resource,
+            changes: serde_json::json!({}),
+            ip_address: None,
+            user_agent: None,
+            timestamp,
+        },
+    )
+}
+
+/// Events that pass `LedgerEvent::validate`
+pub fn arb_ledger_event() -> impl Strategy<Value = LedgerEvent> {
+    prop_oneof![
+        arb_financial_transaction().prop_map(LedgerEvent::FinancialTransaction),
+        arb_account_creation().prop_map(LedgerEvent::AccountCreation),
+        arb_balance_adjustment().prop_map(LedgerEvent::BalanceAdjustment),
+        arb_audit_log().prop_map(LedgerEvent::AuditLog),
+    ]
+}
+
+/// Events that `LedgerEvent::validate` must reject
+pub fn arb_invalid_ledger_event() -> impl Strategy<Value = LedgerEvent> {
+    prop_oneof![
+        arb_invalid_financial_transaction().prop_map(LedgerEvent::FinancialTransaction),
+        (arb_account_creation(), arb_invalid_money()).prop_map(|(mut acct, money)| {
+            acct.initial_balance = money;
+            LedgerEvent::AccountCreation(acct)
+        }),
+        arb_account_creation().prop_map(|mut acct| {
+            acct.account_id.clear();
+            LedgerEvent::AccountCreation(acct)
+        }),
+    ]
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use gitdigital_ledger_core::compliance::validator::ComplianceValidator;
+use gitdigital_ledger_core::core::ledger::IDEMPOTENCY_KEY_METADATA;
+use gitdigital_ledger_core::core::{DigitalLedger, LedgerConfig};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn chains_sharing_a_storage_are_read_separately() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let payments = common::ledger(storage.clone(), "payments").await;
+    payments
+        .append_event(common::transfer_event("tx-1", "alice", "bob", common::usd(1_000)), None)
+        .await
+        .unwrap();
+
+    // The second chain still gets its own genesis record
+    let treasury = common::ledger(storage.clone(), "treasury").await;
+    treasury
+        .append_event(common::transfer_event("tx-2", "carol", "dave", common::usd(2_000)), None)
+        .await
+        .unwrap();
+    payments
+        .append_event(common::transfer_event("tx-3", "bob", "alice", common::usd(500)), None)
+        .await
+        .unwrap();
+
+    assert!(payments.verify_integrity().await.unwrap());
+    assert!(treasury.verify_integrity().await.unwrap());
+    assert_eq!(payments.stats().await.unwrap().record_count, 3);
+    assert_eq!(treasury.stats().await.unwrap().record_count, 2);
+    assert_ne!(
+        payments.get_merkle_root().await.unwrap(),
+        treasury.get_merkle_root().await.unwrap()
+    );
+
+    let reopened = DigitalLedger::open_existing(
+        storage,
+        Arc::new(ComplianceValidator::new()),
+        LedgerConfig::new("treasury"),
+    )
+    .await
+    .unwrap();
+    assert!(reopened.verify_integrity().await.unwrap());
+    assert_eq!(reopened.stats().await.unwrap().record_count, 2);
+}
+
+#[tokio::test]
+async fn chains_sharing_a_storage_keep_ids_and_keys_apart() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let payments = common::ledger(storage.clone(), "payments").await;
+    let treasury = common::ledger(storage, "treasury").await;
+    let event = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+    let metadata = serde_json::json!({ IDEMPOTENCY_KEY_METADATA: "key-1" });
+
+    // The same event under the same key is new to each chain
+    let in_payments = payments.append_event(event.clone(), Some(metadata.clone())).await.unwrap();
+    let in_treasury = treasury.append_event(event, Some(metadata)).await.unwrap();
+    assert_eq!(in_payments, in_treasury);
+
+    let record = payments.get_record(&in_payments).await.unwrap().unwrap();
+    assert_eq!(record.chain_id, "payments");
+    let genesis = treasury.get_audit_trail(None, None, None, None, None, None).await.unwrap()[0].event_id.clone();
+    assert!(payments.get_record(&genesis).await.unwrap().is_none());
+    assert!(payments.verify_integrity().await.unwrap());
+    assert!(treasury.verify_integrity().await.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_appends_chain_linearly() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    // Two ledgers on one chain, so appends race both within a ledger and
+    // across them
+    let first = Arc::new(common::ledger(storage.clone(), "stress").await);
+    let second = Arc::new(
+        DigitalLedger::open_existing(storage, Arc::new(ComplianceValidator::new()), LedgerConfig::new("stress"))
+            .await
+            .unwrap(),
+    );
+
+    let appends: Vec<_> = (0..64)
+        .map(|i| {
+            let ledger = if i % 2 == 0 { first.clone() } else { second.clone() };
+            tokio::spawn(async move {
+                let event = common::transfer_event(&format!("tx-{}", i), "alice", "bob", common::usd(100 + i));
+                ledger.append_event(event, None).await.unwrap()
+            })
+        })
+        .collect();
+    for append in appends {
+        append.await.unwrap();
+    }
+
+    let records = first.get_audit_trail(None, None, None, None, None, None).await.unwrap();
+    assert_eq!(records.len(), 65);
+    let sequences: Vec<Option<u64>> = records.iter().map(|r| r.sequence).collect();
+    assert_eq!(sequences, (0..65).map(Some).collect::<Vec<_>>());
+    let previous: std::collections::HashSet<_> = records.iter().map(|r| r.previous_hash.clone()).collect();
+    assert_eq!(previous.len(), records.len(), "two records link to the same predecessor");
+    assert!(first.verify_integrity().await.unwrap());
+    assert!(second.verify_integrity().await.unwrap());
+}
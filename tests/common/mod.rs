@@ -0,0 +1,59 @@
+//! Shared setup for integration tests that need a real Postgres, so queries
+//! run with their SQL semantics rather than an in-memory stand-in.
+//!
+//! Tests using `postgres_storage` are skipped unless
+//! `LEDGER_TEST_DATABASE_URL` points at a database they may create tables in.
+#![allow(dead_code)]
+
+use gitdigital_ledger_core::compliance::validator::ComplianceValidator;
+use gitdigital_ledger_core::core::event::{FinancialTransaction, LedgerEvent, Money};
+use gitdigital_ledger_core::core::{DigitalLedger, LedgerConfig};
+use gitdigital_ledger_core::storage::append_only::PostgresStorage;
+use std::sync::Arc;
+
+pub const DATABASE_URL_VAR: &str = "LEDGER_TEST_DATABASE_URL";
+
+/// The test database URL and a table name of its own, or `None` when no
+/// test database is set
+pub fn postgres_table() -> Option<(String, String)> {
+    let Ok(url) = std::env::var(DATABASE_URL_VAR) else {
+        eprintln!("{} not set, skipping", DATABASE_URL_VAR);
+        return None;
+    };
+    Some((url, format!("ledger_test_{}", uuid::Uuid::new_v4().simple())))
+}
+
+/// A storage on a table of its own, or `None` when no test database is set
+pub async fn postgres_storage() -> Option<Arc<PostgresStorage>> {
+    let (url, table) = postgres_table()?;
+    Some(open_storage(&url, &table).await)
+}
+
+pub async fn open_storage(url: &str, table: &str) -> Arc<PostgresStorage> {
+    Arc::new(PostgresStorage::new(url, table).await.expect("test database unavailable"))
+}
+
+pub async fn ledger(storage: Arc<PostgresStorage>, chain_id: &str) -> DigitalLedger {
+    DigitalLedger::new(storage, Arc::new(ComplianceValidator::new()), LedgerConfig::new(chain_id))
+        .await
+        .expect("ledger opens")
+}
+
+pub fn usd(amount: i64) -> Money {
+    Money::with_currency_defaults(rust_decimal::Decimal::new(amount, 2), "USD")
+}
+
+pub fn transfer(transaction_id: &str, from: &str, to: &str, amount: Money) -> FinancialTransaction {
+    FinancialTransaction::builder()
+        .transaction_id(transaction_id)
+        .from_account(from)
+        .to_account(to)
+        .amount(amount)
+        .description("test transfer")
+        .build()
+        .expect("valid transfer")
+}
+
+pub fn transfer_event(transaction_id: &str, from: &str, to: &str, amount: Money) -> LedgerEvent {
+    LedgerEvent::FinancialTransaction(transfer(transaction_id, from, to, amount))
+}
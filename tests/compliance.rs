@@ -0,0 +1,139 @@
+mod common;
+
+use async_trait::async_trait;
+use gitdigital_ledger_core::compliance::validator::{
+    ComplianceOutcome, ComplianceValidator, ContextProvider, Rule, RuleSetConfig, RuleSeverity, ValidationContext,
+    Violation,
+};
+use gitdigital_ledger_core::core::event::LedgerEvent;
+use std::sync::Arc;
+
+const SOURCE_KEY: &str = "source";
+
+/// Supplies the name of the data source it stands for, as a KYC lookup or a
+/// transaction history query would supply their data
+struct SourceProvider(&'static str);
+
+#[async_trait]
+impl ContextProvider for SourceProvider {
+    async fn populate(&self, _event: &LedgerEvent, context: &mut ValidationContext) -> anyhow::Result<()> {
+        context.additional_data.insert(SOURCE_KEY.to_string(), serde_json::json!(self.0));
+        Ok(())
+    }
+}
+
+/// Reports which source its context was filled from
+struct ContextSourceRule;
+
+#[async_trait]
+impl Rule for ContextSourceRule {
+    async fn evaluate(&self, _event: &LedgerEvent, context: &ValidationContext) -> anyhow::Result<Vec<Violation>> {
+        Ok(vec![Violation {
+            rule_id: self.get_rule_id().to_string(),
+            severity: self.get_severity(),
+            message: "context source".to_string(),
+            evidence: serde_json::json!({ "source": context.additional_data.get(SOURCE_KEY) }),
+        }])
+    }
+
+    fn get_rule_id(&self) -> &str {
+        "CONTEXT_SOURCE"
+    }
+
+    fn get_severity(&self) -> RuleSeverity {
+        RuleSeverity::Error
+    }
+}
+
+fn evidence_sources(outcome: &ComplianceOutcome) -> Vec<serde_json::Value> {
+    outcome.violations().iter().map(|v| v.evidence["source"].clone()).collect()
+}
+
+#[tokio::test]
+async fn rule_sets_fill_their_own_context_and_judge_by_their_own_policy() {
+    let mut validator = ComplianceValidator::new();
+    validator.add_rule(Box::new(ContextSourceRule));
+    validator.set_context_provider(Arc::new(SourceProvider("default")));
+    validator.create_rule_set_with_config(
+        "onboarding",
+        vec!["CONTEXT_SOURCE"],
+        RuleSetConfig::default()
+            .with_context_provider(Arc::new(SourceProvider("kyc")))
+            .with_blocking_severity(RuleSeverity::Error),
+    );
+    validator.create_rule_set_with_config(
+        "monitoring",
+        vec!["CONTEXT_SOURCE"],
+        RuleSetConfig::default().with_context_provider(Arc::new(SourceProvider("history"))),
+    );
+    validator.create_rule_set("plain", vec!["CONTEXT_SOURCE"]);
+    let event = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+
+    let onboarding = validator.validate_with_rule_set(&event, "onboarding").await.unwrap();
+    assert_eq!(evidence_sources(&onboarding), vec![serde_json::json!("kyc")]);
+    assert!(onboarding.is_blocked());
+
+    let monitoring = validator.validate_with_rule_set(&event, "monitoring").await.unwrap();
+    assert_eq!(evidence_sources(&monitoring), vec![serde_json::json!("history")]);
+    assert!(!monitoring.is_blocked());
+
+    // A set without a provider of its own uses the validator's
+    let plain = validator.validate_with_rule_set(&event, "plain").await.unwrap();
+    assert_eq!(evidence_sources(&plain), vec![serde_json::json!("default")]);
+}
+
+/// Raises the same finding as other instances, at its own severity
+struct SharedFindingRule {
+    rule_id: &'static str,
+    severity: RuleSeverity,
+    evidence: serde_json::Value,
+}
+
+#[async_trait]
+impl Rule for SharedFindingRule {
+    async fn evaluate(&self, _event: &LedgerEvent, _context: &ValidationContext) -> anyhow::Result<Vec<Violation>> {
+        Ok(vec![Violation {
+            rule_id: "SHARED_FINDING".to_string(),
+            severity: self.severity.clone(),
+            message: format!("raised by {}", self.rule_id),
+            evidence: self.evidence.clone(),
+        }])
+    }
+
+    fn get_rule_id(&self) -> &str {
+        self.rule_id
+    }
+
+    fn get_severity(&self) -> RuleSeverity {
+        self.severity.clone()
+    }
+}
+
+fn shared_finding_validator(dedup: bool) -> ComplianceValidator {
+    let mut validator = ComplianceValidator::new().with_dedup(dedup);
+    // Equal evidence with keys in another order, then different evidence
+    let rules = [
+        ("RULE_A", RuleSeverity::Warning, serde_json::json!({ "account": "alice", "amount": "10.00" })),
+        ("RULE_B", RuleSeverity::Critical, serde_json::json!({ "amount": "10.00", "account": "alice" })),
+        ("RULE_C", RuleSeverity::Warning, serde_json::json!({ "account": "bob", "amount": "10.00" })),
+    ];
+    for (rule_id, severity, evidence) in rules {
+        validator.add_rule(Box::new(SharedFindingRule { rule_id, severity, evidence }));
+    }
+    validator
+}
+
+#[tokio::test]
+async fn dedup_collapses_the_same_violation_from_two_rules() {
+    let event = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+
+    let raw = shared_finding_validator(false).validate(&event).await.unwrap();
+    assert_eq!(raw.len(), 3);
+
+    let deduped = shared_finding_validator(true).validate(&event).await.unwrap();
+    assert_eq!(deduped.len(), 2);
+    let alice: Vec<&Violation> = deduped.iter().filter(|v| v.evidence["account"] == "alice").collect();
+    assert_eq!(alice.len(), 1);
+    assert_eq!(alice[0].severity, RuleSeverity::Critical);
+    assert!(deduped.iter().any(|v| v.evidence["account"] == "bob"));
+}
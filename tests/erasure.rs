@@ -0,0 +1,90 @@
+mod common;
+
+use gitdigital_ledger_core::compliance::validator::ComplianceValidator;
+use gitdigital_ledger_core::core::event::{AuditLog, LedgerEvent};
+use gitdigital_ledger_core::core::ledger::SUBJECT_ID_METADATA;
+use gitdigital_ledger_core::core::{DigitalLedger, LedgerConfig};
+use gitdigital_ledger_core::storage::append_only::PostgresStorage;
+use gitdigital_ledger_core::storage::encryption::{
+    EncryptionKey, EncryptionKeys, EncryptionLayer, InMemorySubjectKeys, ERASED_RECORD_ACTION,
+};
+use std::sync::Arc;
+
+/// An encrypted ledger with one record of subject `s-1` and one of nobody,
+/// its URL, table and the two event ids
+async fn subject_ledger() -> Option<(DigitalLedger, String, String, [String; 2])> {
+    let (url, table) = common::postgres_table()?;
+    let subject_keys = Arc::new(InMemorySubjectKeys::new());
+    let storage = EncryptionLayer::new(
+        PostgresStorage::new(&url, &table).await.expect("test database unavailable"),
+        EncryptionKeys::new("k-1", EncryptionKey::new([7; 32])),
+    )
+    .with_subject_keys(subject_keys.clone());
+    let ledger = DigitalLedger::new(
+        Arc::new(storage),
+        Arc::new(ComplianceValidator::new()),
+        LedgerConfig::new("erasure").with_subject_keys(subject_keys),
+    )
+    .await
+    .unwrap();
+
+    let subjects = ledger
+        .append_event(
+            common::transfer_event("tx-1", "alice", "bob", common::usd(1_000)),
+            Some(serde_json::json!({ SUBJECT_ID_METADATA: "s-1" })),
+        )
+        .await
+        .unwrap();
+    let nobodys = ledger
+        .append_event(common::transfer_event("tx-2", "bob", "carol", common::usd(500)), None)
+        .await
+        .unwrap();
+    Some((ledger, url, table, [subjects, nobodys]))
+}
+
+#[tokio::test]
+async fn erased_records_still_verify() {
+    let Some((ledger, _, _, [subjects, _])) = subject_ledger().await else {
+        return;
+    };
+    ledger.erase_subject("s-1").await.unwrap();
+
+    let record = ledger.get_record(&subjects).await.unwrap().unwrap();
+    assert!(matches!(&record.event, LedgerEvent::AuditLog(log) if log.action == ERASED_RECORD_ACTION));
+    let report = ledger.verify_integrity_parallel(16).await.unwrap();
+    assert!(report.first_break.is_none(), "{:?}", report.first_break);
+}
+
+#[tokio::test]
+async fn forged_placeholder_is_a_break() {
+    let Some((ledger, url, table, [_, nobodys])) = subject_ledger().await else {
+        return;
+    };
+    ledger.erase_subject("s-1").await.unwrap();
+
+    // Store a placeholder in the clear over a record nobody erased
+    let forged = LedgerEvent::AuditLog(AuditLog {
+        log_id: nobodys.clone(),
+        action: ERASED_RECORD_ACTION.to_string(),
+        actor: "ledger".to_string(),
+        resource: "erasure".to_string(),
+        changes: serde_json::json!({ "subject_id": "s-1", "ciphertext_sha256": "00" }),
+        ip_address: None,
+        user_agent: None,
+        timestamp: chrono::Utc::now(),
+    });
+    let pool = sqlx::PgPool::connect(&url).await.unwrap();
+    sqlx::query(&format!(
+        "UPDATE {} SET event_data = $1, event_bytes = $2 WHERE event_id = $3",
+        table
+    ))
+    .bind(serde_json::to_value(&forged).unwrap())
+    .bind(serde_json::to_vec(&forged).unwrap())
+    .bind(&nobodys)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let report = ledger.verify_integrity_parallel(16).await.unwrap();
+    assert_eq!(report.first_break.map(|b| b.event_id), Some(nobodys));
+}
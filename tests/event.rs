@@ -0,0 +1,137 @@
+use gitdigital_ledger_core::core::event::{
+    AccountType, AdjustmentReason, AlertSeverity, ComplianceLevel, EntryDirection, FinancialTransaction, JournalLine,
+    LedgerEvent, Money,
+};
+use gitdigital_ledger_core::core::schema::migrate_record;
+use gitdigital_ledger_core::storage::codec::{CborCodec, JsonCodec, RecordCodec};
+use rust_decimal::Decimal;
+
+/// Serializes `value`, checks the JSON string and that `Display` and
+/// `FromStr` agree with it, returning the parsed value's wire name
+fn wire_name<T>(value: &T, expected: &str) -> String
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + std::fmt::Display + std::str::FromStr,
+    T::Err: std::fmt::Debug,
+{
+    assert_eq!(serde_json::to_value(value).unwrap(), serde_json::json!(expected));
+    assert_eq!(value.to_string(), expected);
+    let parsed: T = expected.parse().unwrap();
+    let deserialized: T = serde_json::from_value(serde_json::json!(expected)).unwrap();
+    assert_eq!(deserialized.to_string(), expected);
+    parsed.to_string()
+}
+
+#[test]
+fn enums_use_snake_case_wire_names() {
+    let severities = ["low", "medium", "high", "critical"];
+    for (value, expected) in AlertSeverity::ALL.iter().zip(severities) {
+        assert_eq!(wire_name(value, expected), expected);
+    }
+    let account_types = ["asset", "liability", "equity", "revenue", "expense"];
+    for (value, expected) in AccountType::ALL.iter().zip(account_types) {
+        assert_eq!(wire_name(value, expected), expected);
+    }
+    let levels = ["low_risk", "medium_risk", "high_risk", "sanctioned"];
+    for (value, expected) in ComplianceLevel::ALL.iter().zip(levels) {
+        assert_eq!(wire_name(value, expected), expected);
+    }
+    let reasons = ["correction", "write_off", "revaluation", "regulatory"];
+    for (value, expected) in AdjustmentReason::ALL.iter().zip(reasons) {
+        assert_eq!(wire_name(value, expected), expected);
+    }
+}
+
+#[test]
+fn rust_variant_names_are_not_wire_names() {
+    assert!("HighRisk".parse::<ComplianceLevel>().is_err());
+    assert!("Low".parse::<AlertSeverity>().is_err());
+    assert!(serde_json::from_value::<AdjustmentReason>(serde_json::json!("WriteOff")).is_err());
+}
+
+/// A transfer of 100 USD as JSON
+fn transfer_payload() -> serde_json::Value {
+    let transfer = FinancialTransaction::builder()
+        .transaction_id("tx-1")
+        .from_account("alice")
+        .to_account("bob")
+        .amount(Money::with_currency_defaults(Decimal::new(100, 0), "USD"))
+        .description("test transfer")
+        .build()
+        .unwrap();
+    serde_json::to_value(LedgerEvent::FinancialTransaction(transfer)).unwrap()
+}
+
+/// `payload` with its amount written as `amount`
+fn with_amount(payload: &serde_json::Value, amount: serde_json::Value) -> serde_json::Value {
+    let mut payload = payload.clone();
+    payload["amount"]["amount"] = amount;
+    payload
+}
+
+#[test]
+fn equal_amounts_hash_identically_however_written() {
+    let payload = transfer_payload();
+    let written = [serde_json::json!(100), serde_json::json!("100.00"), serde_json::json!("1E2")];
+    let events: Vec<LedgerEvent> = written
+        .iter()
+        .map(|amount| serde_json::from_value(with_amount(&payload, amount.clone())).unwrap())
+        .collect();
+
+    for codec in [&JsonCodec as &dyn RecordCodec, &CborCodec] {
+        let hashes: Vec<String> = events.iter().map(|event| codec.hash_event(event).unwrap()).collect();
+        assert!(hashes.iter().all(|hash| *hash == hashes[0]), "{} hashes differ", codec.codec_id());
+    }
+    for event in &events {
+        assert_eq!(serde_json::to_value(event).unwrap()["amount"]["amount"], "100.00");
+    }
+}
+
+#[test]
+fn legacy_amounts_migrate_to_the_canonical_form() {
+    let payload = transfer_payload();
+    let canonical: LedgerEvent = serde_json::from_value(with_amount(&payload, serde_json::json!("100.00"))).unwrap();
+    for amount in [serde_json::json!(100), serde_json::json!("100"), serde_json::json!("1E2")] {
+        let mut payload = with_amount(&payload, amount);
+        payload["amount"].as_object_mut().unwrap().remove("precision");
+
+        let migrated = migrate_record(2, payload).unwrap();
+        let value = serde_json::to_value(&migrated).unwrap();
+        assert_eq!(value["amount"]["amount"], "100.00");
+        assert_eq!(value["amount"]["precision"], 2);
+        assert_eq!(JsonCodec.hash_event(&migrated).unwrap(), JsonCodec.hash_event(&canonical).unwrap());
+    }
+}
+
+#[test]
+fn account_types_apply_debits_and_credits_by_normal_balance() {
+    // (type, normal balance, balance of 100 after a 30 debit, after a 30 credit)
+    let cases = [
+        (AccountType::Asset, EntryDirection::Debit, 130, 70),
+        (AccountType::Expense, EntryDirection::Debit, 130, 70),
+        (AccountType::Liability, EntryDirection::Credit, 70, 130),
+        (AccountType::Equity, EntryDirection::Credit, 70, 130),
+        (AccountType::Revenue, EntryDirection::Credit, 70, 130),
+    ];
+    assert_eq!(cases.len(), AccountType::ALL.len());
+    let line = |direction| JournalLine {
+        account: "acct-1".to_string(),
+        direction,
+        amount: Money::with_currency_defaults(Decimal::new(30, 0), "USD"),
+    };
+    for (account_type, normal_balance, after_debit, after_credit) in cases {
+        assert_eq!(account_type.normal_balance(), normal_balance, "{}", account_type);
+        let current = Decimal::new(100, 0);
+        assert_eq!(
+            account_type.apply(current, &line(EntryDirection::Debit)).unwrap(),
+            Decimal::new(after_debit, 0),
+            "debit to {}",
+            account_type
+        );
+        assert_eq!(
+            account_type.apply(current, &line(EntryDirection::Credit)).unwrap(),
+            Decimal::new(after_credit, 0),
+            "credit to {}",
+            account_type
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! Checks events against `event_schema()` the way a partner would before
+//! submitting them. Needs the `schema` feature.
+#![cfg(feature = "schema")]
+
+use gitdigital_ledger_core::core::event::{event_schema, FinancialTransaction, LedgerEvent, Money};
+use jsonschema::{Draft, JSONSchema};
+
+fn compiled_schema() -> JSONSchema {
+    JSONSchema::options()
+        .with_draft(Draft::Draft201909)
+        .compile(&event_schema())
+        .expect("event schema compiles")
+}
+
+fn transfer_payload() -> serde_json::Value {
+    let transfer = FinancialTransaction::builder()
+        .transaction_id("tx-1")
+        .from_account("alice")
+        .to_account("bob")
+        .amount(Money::with_currency_defaults(rust_decimal::Decimal::new(1_000, 2), "USD"))
+        .description("test transfer")
+        .build()
+        .unwrap();
+    serde_json::to_value(LedgerEvent::FinancialTransaction(transfer)).unwrap()
+}
+
+#[test]
+fn schema_round_trips_through_json() {
+    let schema = event_schema();
+    let text = serde_json::to_string(&schema).unwrap();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&text).unwrap(), schema);
+    assert!(schema["$schema"].as_str().unwrap().contains("2019-09"));
+}
+
+#[test]
+fn valid_event_passes_and_deserializes() {
+    let schema = compiled_schema();
+    let payload = transfer_payload();
+
+    assert!(schema.is_valid(&payload));
+    let event: LedgerEvent = serde_json::from_value(payload.clone()).unwrap();
+    assert_eq!(serde_json::to_value(event).unwrap(), payload);
+}
+
+#[test]
+fn invalid_events_fail() {
+    let schema = compiled_schema();
+    let valid = transfer_payload();
+
+    let mut unknown_type = valid.clone();
+    unknown_type["event_type"] = serde_json::json!("wire_transfer");
+    let mut missing_field = valid.clone();
+    missing_field.as_object_mut().unwrap().remove("to_account");
+    let mut empty_id = valid.clone();
+    empty_id["transaction_id"] = serde_json::json!("");
+    let mut bad_currency = valid.clone();
+    bad_currency["amount"]["currency_code"] = serde_json::json!("DOLLARS");
+
+    for (case, payload) in [
+        ("unknown event type", unknown_type),
+        ("missing field", missing_field),
+        ("empty id", empty_id),
+        ("bad currency", bad_currency),
+    ] {
+        assert!(!schema.is_valid(&payload), "{} passed schema validation", case);
+    }
+}
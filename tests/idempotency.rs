@@ -0,0 +1,127 @@
+mod common;
+
+use gitdigital_ledger_core::compliance::validator::ComplianceValidator;
+use gitdigital_ledger_core::core::ledger::IDEMPOTENCY_KEY_METADATA;
+use gitdigital_ledger_core::core::{DigitalLedger, LedgerConfig, LedgerError};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn batch_reusing_key_of_unscoped_record_returns_existing_id() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let ledger = common::ledger(storage, "idempotency").await;
+    let event = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+    let metadata = serde_json::json!({ IDEMPOTENCY_KEY_METADATA: "key-1" });
+
+    let first = ledger.append_event(event.clone(), Some(metadata.clone())).await.unwrap();
+    let batch = ledger.append_batch(vec![(event, Some(metadata))]).await.unwrap();
+
+    assert_eq!(batch, vec![first]);
+}
+
+#[tokio::test]
+async fn batch_reusing_key_of_scoped_record_conflicts() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let ledger = common::ledger(storage, "idempotency").await;
+    let event = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+    let metadata = serde_json::json!({ IDEMPOTENCY_KEY_METADATA: "key-1" });
+
+    let scoped = ledger
+        .append_event_with_visibility(event.clone(), Some(metadata.clone()), vec!["treasury".to_string()])
+        .await
+        .unwrap();
+
+    // Batched records are unscoped, so the same event is a different record
+    match ledger.append_batch(vec![(event.clone(), Some(metadata.clone()))]).await {
+        Err(LedgerError::IdempotencyConflict { existing_event_id, .. }) => assert_eq!(existing_event_id, scoped),
+        other => panic!("expected an idempotency conflict, got {:?}", other),
+    }
+    let lenient = ledger.append_batch_lenient(vec![(event, Some(metadata))]).await.unwrap();
+    assert!(lenient.appended.is_empty());
+    assert!(matches!(lenient.rejected.as_slice(), [(0, LedgerError::IdempotencyConflict { .. })]));
+}
+
+#[tokio::test]
+async fn concurrent_appends_under_one_key_do_not_fail_in_storage() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let ledger = common::ledger(storage, "idempotency").await;
+    let metadata = serde_json::json!({ IDEMPOTENCY_KEY_METADATA: "key-1" });
+    let first = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+    let second = common::transfer_event("tx-2", "alice", "bob", common::usd(2_000));
+
+    let (a, b) = tokio::join!(
+        ledger.append_event(first, Some(metadata.clone())),
+        ledger.append_event(second, Some(metadata)),
+    );
+
+    // One wins; the other sees the key taken, not a unique-index error
+    let (winner, loser) = if a.is_ok() { (a, b) } else { (b, a) };
+    match loser {
+        Err(LedgerError::IdempotencyConflict { existing_event_id, .. }) => {
+            assert_eq!(existing_event_id, winner.unwrap())
+        }
+        other => panic!("expected an idempotency conflict, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn batch_repeating_a_key_returns_one_record_or_conflicts() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let ledger = common::ledger(storage, "idempotency").await;
+    let metadata = serde_json::json!({ IDEMPOTENCY_KEY_METADATA: "key-1" });
+    let event = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+    let other = common::transfer_event("tx-2", "alice", "bob", common::usd(2_000));
+
+    let conflicting = vec![(event.clone(), Some(metadata.clone())), (other, Some(metadata.clone()))];
+    assert!(matches!(
+        ledger.append_batch(conflicting).await,
+        Err(LedgerError::IdempotencyConflict { .. })
+    ));
+    assert_eq!(ledger.stats().await.unwrap().record_count, 1);
+
+    let repeated = vec![(event.clone(), Some(metadata.clone())), (event, Some(metadata))];
+    let event_ids = ledger.append_batch(repeated).await.unwrap();
+    assert_eq!(event_ids[0], event_ids[1]);
+    assert_eq!(ledger.stats().await.unwrap().record_count, 2);
+}
+
+#[tokio::test]
+async fn appending_the_same_event_twice_is_a_duplicate() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let ledger = DigitalLedger::new(
+        storage.clone(),
+        Arc::new(ComplianceValidator::new()),
+        LedgerConfig::new("idempotency").with_genesis(false),
+    )
+    .await
+    .unwrap();
+    let event = common::transfer_event("tx-1", "alice", "bob", common::usd(1_000));
+
+    let event_id = ledger.append_event(event.clone(), None).await.unwrap();
+    match ledger.append_event(event.clone(), None).await {
+        Err(LedgerError::DuplicateEvent { event_id: duplicate }) => assert_eq!(duplicate, event_id),
+        other => panic!("expected a duplicate event, got {:?}", other),
+    }
+    assert_eq!(ledger.stats().await.unwrap().record_count, 1);
+    assert!(ledger.verify_integrity().await.unwrap());
+
+    // Opting in returns the existing id instead, still without appending
+    let idempotent = DigitalLedger::open_existing(
+        storage,
+        Arc::new(ComplianceValidator::new()),
+        LedgerConfig::new("idempotency").with_idempotent_duplicates(true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(idempotent.append_event(event, None).await.unwrap(), event_id);
+    assert_eq!(idempotent.stats().await.unwrap().record_count, 1);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use async_trait::async_trait;
+use gitdigital_ledger_core::compliance::validator::ComplianceValidator;
+use gitdigital_ledger_core::core::ledger::{ArchiveSink, RetentionPolicy};
+use gitdigital_ledger_core::core::{DigitalLedger, LedgerConfig, LedgerError, LedgerRecord};
+use gitdigital_ledger_core::storage::append_only::StorageError;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct MemorySink(Mutex<Vec<LedgerRecord>>);
+
+#[async_trait]
+impl ArchiveSink for MemorySink {
+    async fn archive(&self, records: &[LedgerRecord]) -> Result<(), StorageError> {
+        self.0.lock().unwrap().extend_from_slice(records);
+        Ok(())
+    }
+}
+
+/// A ledger on a fresh table, with no genesis so a dump can start it
+async fn empty_ledger(chain_id: &str) -> Option<DigitalLedger> {
+    let storage = common::postgres_storage().await?;
+    let ledger = DigitalLedger::new(
+        storage,
+        Arc::new(ComplianceValidator::new()),
+        LedgerConfig::new(chain_id).with_genesis(false),
+    )
+    .await
+    .unwrap();
+    Some(ledger)
+}
+
+async fn export(ledger: &DigitalLedger) -> Vec<String> {
+    let mut dump = Vec::new();
+    ledger.export_ndjson(&mut dump).await.unwrap();
+    String::from_utf8(dump).unwrap().lines().map(str::to_string).collect()
+}
+
+async fn populated(chain_id: &str) -> Option<DigitalLedger> {
+    let storage = common::postgres_storage().await?;
+    let ledger = common::ledger(storage, chain_id).await;
+    for id in ["tx-1", "tx-2", "tx-3"] {
+        ledger
+            .append_event(common::transfer_event(id, "alice", "bob", common::usd(1_000)), None)
+            .await
+            .unwrap();
+    }
+    Some(ledger)
+}
+
+#[tokio::test]
+async fn import_follows_retention_tombstones() {
+    let Some(source) = populated("import").await else {
+        return;
+    };
+    let policy = RetentionPolicy {
+        max_age: chrono::Duration::zero(),
+        archive_sink: Arc::new(MemorySink::default()),
+        allow_when_sealed: false,
+    };
+    assert!(source.apply_retention(&policy).await.unwrap() > 0);
+    let lines = export(&source).await;
+
+    let Some(target) = empty_ledger("import").await else {
+        return;
+    };
+    assert_eq!(target.import_ndjson(lines.join("\n").as_bytes()).await.unwrap(), lines.len());
+    assert!(target.verify_integrity().await.unwrap());
+}
+
+#[tokio::test]
+async fn import_rejects_sequence_gaps() {
+    let Some(source) = populated("import").await else {
+        return;
+    };
+    let mut lines = export(&source).await;
+    lines.remove(2);
+
+    let Some(target) = empty_ledger("import").await else {
+        return;
+    };
+    let result = target.import_ndjson(lines.join("\n").as_bytes()).await;
+    assert!(matches!(result, Err(LedgerError::ImportRejected(_))));
+    assert_eq!(export(&target).await.len(), 0);
+}
+
+#[tokio::test]
+async fn import_rejects_another_chain() {
+    let Some(source) = populated("import").await else {
+        return;
+    };
+    let lines = export(&source).await;
+
+    let Some(target) = empty_ledger("elsewhere").await else {
+        return;
+    };
+    let result = target.import_ndjson(lines.join("\n").as_bytes()).await;
+    assert!(matches!(result, Err(LedgerError::ImportRejected(_))));
+}
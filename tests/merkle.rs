@@ -0,0 +1,51 @@
+use gitdigital_ledger_core::storage::merkle_tree::{
+    compute_root, hash_leaf, hash_node, inclusion_proof, verify_inclusion, IncrementalMerkleTree,
+};
+
+#[test]
+fn incremental_root_matches_a_from_scratch_root() {
+    let leaves: Vec<String> = (0..5_000).map(|i| format!("event-{}", i)).collect();
+    let leaves: Vec<&str> = leaves.iter().map(String::as_str).collect();
+
+    let mut tree = IncrementalMerkleTree::new();
+    assert_eq!(tree.root(), compute_root(&[]));
+    for (appended, leaf) in leaves.iter().enumerate() {
+        tree.push(leaf);
+        let size = appended + 1;
+        // Every size around a power of two, where the tree shape changes,
+        // and a spread of the rest
+        if size % 97 == 0 || (size - 1).is_power_of_two() || size.is_power_of_two() || (size + 1).is_power_of_two() {
+            assert_eq!(tree.root(), compute_root(&leaves[..size]), "roots differ at {} leaves", size);
+        }
+    }
+    assert_eq!(tree.len(), leaves.len());
+    assert_eq!(tree.root(), compute_root(&leaves));
+    assert_eq!(IncrementalMerkleTree::from_leaves(leaves.iter().copied()).root(), tree.root());
+}
+
+/// The second-preimage attack: the interior node over leaves 0 and 1 of a
+/// four-leaf tree, presented as leaf 0 of a two-leaf tree with the right
+/// subtree as its sibling. Without domain separation the node's children,
+/// concatenated, hash to the node as a leaf and the proof checks out.
+#[test]
+fn interior_node_presented_as_a_leaf_is_rejected() {
+    let leaves = ["tx-1", "tx-2", "tx-3", "tx-4"];
+    let root = compute_root(&leaves);
+    let (left, right) = (hash_leaf(leaves[0].as_bytes()), hash_leaf(leaves[1].as_bytes()));
+    let node = hash_node(&left, &right);
+    let forged_leaf = [left, right].concat();
+    let forged_proof = [compute_root(&leaves[2..])];
+
+    // The forgery relied on these being equal
+    assert_ne!(hash_leaf(&forged_leaf), node);
+    assert_eq!(hash_node(&node, &forged_proof[0]), root);
+
+    // The crafted proof, with the forged leaf in the only form the API takes
+    let forged_leaf = hex::encode(&forged_leaf);
+    assert!(!verify_inclusion(&forged_leaf, 0, 2, &forged_proof, &root));
+    // The genuine proofs still verify
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof = inclusion_proof(&leaves, index).unwrap();
+        assert!(verify_inclusion(leaf, index, leaves.len(), &proof, &root));
+    }
+}
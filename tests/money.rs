@@ -0,0 +1,58 @@
+use gitdigital_ledger_core::core::event::{EntryDirection, JournalEntry, JournalLine, Money, MoneyError, RoundingMode};
+use rust_decimal::Decimal;
+
+fn usd(amount: &str) -> Money {
+    Money::with_currency_defaults(amount.parse().unwrap(), "USD")
+}
+
+#[test]
+fn half_cents_round_per_mode() {
+    // (mode, 2.675, 2.665, -2.675) rounded to USD's two places
+    let cases = [
+        (RoundingMode::HalfEven, "2.68", "2.66", "-2.68"),
+        (RoundingMode::HalfUp, "2.68", "2.67", "-2.68"),
+        (RoundingMode::HalfDown, "2.67", "2.66", "-2.67"),
+        (RoundingMode::Up, "2.68", "2.67", "-2.68"),
+        (RoundingMode::Down, "2.67", "2.66", "-2.67"),
+        (RoundingMode::Ceiling, "2.68", "2.67", "-2.67"),
+        (RoundingMode::Floor, "2.67", "2.66", "-2.68"),
+    ];
+    for (mode, above, below, negative) in cases {
+        for (amount, expected) in [("2.675", above), ("2.665", below), ("-2.675", negative)] {
+            let rounded = usd(amount).round_to_precision(mode);
+            assert_eq!(rounded.amount, expected.parse::<Decimal>().unwrap(), "{} under {:?}", amount, mode);
+            assert_eq!(rounded.currency_code, "USD");
+            assert_eq!(rounded.precision, 2);
+        }
+    }
+}
+
+#[test]
+fn rounding_defaults_to_half_even() {
+    assert_eq!(RoundingMode::default(), RoundingMode::HalfEven);
+    // JPY has no minor units
+    let yen = Money::with_currency_defaults("2.5".parse().unwrap(), "JPY");
+    assert_eq!(yen.round_to_precision(RoundingMode::default()).amount, Decimal::from(2));
+}
+
+#[test]
+fn overflowing_arithmetic_is_an_error_not_a_panic() {
+    let max = Money::with_currency_defaults(Decimal::MAX, "USD");
+    assert_eq!(max.checked_add(Decimal::ONE).unwrap_err(), MoneyError::overflow("USD"));
+    assert_eq!(max.checked_mul(Decimal::TWO).unwrap_err(), MoneyError::overflow("USD"));
+    assert!(max.checked_add(Decimal::ZERO).is_ok());
+    assert!(max.checked_mul(Decimal::ONE).is_ok());
+
+    let line = |direction| JournalLine {
+        account: "acct-1".to_string(),
+        direction,
+        amount: max.clone(),
+    };
+    let entry = JournalEntry {
+        entry_id: "entry-1".to_string(),
+        description: "overflowing debits".to_string(),
+        lines: vec![line(EntryDirection::Debit), line(EntryDirection::Debit), line(EntryDirection::Credit)],
+        timestamp: chrono::Utc::now(),
+    };
+    assert_eq!(entry.totals().unwrap_err(), MoneyError::overflow("USD"));
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use gitdigital_ledger_core::core::event::LedgerEvent;
+
+#[tokio::test]
+async fn reversal_finds_the_original_transaction() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let ledger = common::ledger(storage, "reversal").await;
+    ledger
+        .append_event(common::transfer_event("tx-1", "alice", "bob", common::usd(1_000)), None)
+        .await
+        .unwrap();
+
+    let reversal_id = ledger.reverse_transaction("tx-1", "duplicate", "ops").await.unwrap();
+
+    let record = ledger.get_record(&reversal_id).await.unwrap().unwrap();
+    match record.event {
+        LedgerEvent::TransactionReversal(reversal) => {
+            assert_eq!(reversal.original_transaction_id, "tx-1");
+            assert_eq!(reversal.reversed_amount.amount, common::usd(1_000).amount);
+        }
+        other => panic!("expected a reversal, got {:?}", other),
+    }
+    assert!(ledger.reverse_transaction("tx-2", "unknown", "ops").await.is_err());
+}
+
+#[tokio::test]
+async fn audit_trail_filters_by_entity_id() {
+    let Some(storage) = common::postgres_storage().await else {
+        return;
+    };
+    let ledger = common::ledger(storage, "reversal").await;
+    for id in ["tx-1", "tx-2"] {
+        ledger
+            .append_event(common::transfer_event(id, "alice", "bob", common::usd(1_000)), None)
+            .await
+            .unwrap();
+    }
+
+    let trail = ledger.get_audit_trail(Some("tx-2"), None, None, None, None, None).await.unwrap();
+
+    assert_eq!(trail.len(), 1);
+    assert_eq!(trail[0].event.get_entity_id(), "tx-2");
+}
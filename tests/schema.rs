@@ -0,0 +1,101 @@
+mod common;
+
+use gitdigital_ledger_core::compliance::validator::ComplianceValidator;
+use gitdigital_ledger_core::core::event::LedgerEvent;
+use gitdigital_ledger_core::core::{DigitalLedger, LedgerConfig};
+use gitdigital_ledger_core::storage::codec::{JsonCodec, RecordCodec};
+use std::sync::Arc;
+
+/// A ledger with two transfers on a table of its own, its URL and table
+async fn two_transfers() -> Option<(DigitalLedger, String, String, [String; 2])> {
+    let (url, table) = common::postgres_table()?;
+    let ledger = common::ledger(common::open_storage(&url, &table).await, "schema").await;
+    let first = ledger
+        .append_event(common::transfer_event("tx-1", "alice", "bob", common::usd(1_000)), None)
+        .await
+        .unwrap();
+    let second = ledger
+        .append_event(common::transfer_event("tx-2", "bob", "alice", common::usd(500)), None)
+        .await
+        .unwrap();
+    Some((ledger, url, table, [first, second]))
+}
+
+/// The transfer `event_id` as version 2 wrote it, amount as a number
+async fn version_2_payload(ledger: &DigitalLedger, event_id: &str) -> serde_json::Value {
+    let record = ledger.get_record(event_id).await.unwrap().unwrap();
+    let mut payload = serde_json::to_value(&record.event).unwrap();
+    payload["amount"]["amount"] = serde_json::json!(10);
+    payload
+}
+
+async fn reopen(url: &str, table: &str) -> DigitalLedger {
+    DigitalLedger::open_existing(
+        common::open_storage(url, table).await,
+        Arc::new(ComplianceValidator::new()),
+        LedgerConfig::new("schema"),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn rewriting_a_record_as_version_2_is_detected() {
+    let Some((ledger, url, table, [event_id, _])) = two_transfers().await else {
+        return;
+    };
+    let payload = version_2_payload(&ledger, &event_id).await;
+    let pool = sqlx::PgPool::connect(&url).await.unwrap();
+    sqlx::query(&format!(
+        "UPDATE {} SET schema_version = 2, event_data = $1, event_bytes = $2 WHERE event_id = $3",
+        table
+    ))
+    .bind(&payload)
+    .bind(serde_json::to_vec(&payload).unwrap())
+    .bind(&event_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let report = reopen(&url, &table).await.verify_integrity_parallel(16).await.unwrap();
+    assert_eq!(report.first_break.map(|b| b.event_id), Some(event_id));
+}
+
+#[tokio::test]
+async fn version_2_record_verifies_against_its_stored_bytes() {
+    let Some((ledger, url, table, [_, head_id])) = two_transfers().await else {
+        return;
+    };
+    // Store the head as version 2 wrote it, under the id hashed from those bytes
+    let payload = version_2_payload(&ledger, &head_id).await;
+    let bytes = serde_json::to_vec(&payload).unwrap();
+    let legacy_id = JsonCodec.hash_bytes(&bytes);
+    let pool = sqlx::PgPool::connect(&url).await.unwrap();
+    sqlx::query(&format!("DELETE FROM {}_index WHERE event_id = $1", table))
+        .bind(&head_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(&format!(
+        "UPDATE {} SET event_id = $1, schema_version = 2, event_data = $2, event_bytes = $3 WHERE event_id = $4",
+        table
+    ))
+    .bind(&legacy_id)
+    .bind(&payload)
+    .bind(&bytes)
+    .bind(&head_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let reopened = reopen(&url, &table).await;
+    let report = reopened.verify_integrity_parallel(16).await.unwrap();
+    assert!(report.first_break.is_none(), "{:?}", report.first_break);
+
+    let migrated = reopened.get_record(&legacy_id).await.unwrap().unwrap();
+    assert_eq!(migrated.schema_version, 2);
+    match migrated.event {
+        LedgerEvent::FinancialTransaction(tx) => assert_eq!(tx.amount.canonical_amount(), "10.00"),
+        other => panic!("expected a transfer, got {:?}", other),
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use gitdigital_ledger_core::core::DigitalLedger;
+
+/// A ledger with three transfers on a table of its own, its URL, table and
+/// the transfers' event ids
+async fn three_transfers() -> Option<(DigitalLedger, String, String, Vec<String>)> {
+    let (url, table) = common::postgres_table()?;
+    let ledger = common::ledger(common::open_storage(&url, &table).await, "verification").await;
+    let mut event_ids = Vec::new();
+    for (id, amount) in [("tx-1", 1_000), ("tx-2", 2_000), ("tx-3", 3_000)] {
+        let event = common::transfer_event(id, "alice", "bob", common::usd(amount));
+        event_ids.push(ledger.append_event(event, None).await.unwrap());
+    }
+    Some((ledger, url, table, event_ids))
+}
+
+#[tokio::test]
+async fn sequential_and_parallel_checks_agree_on_an_intact_chain() {
+    let Some((ledger, ..)) = three_transfers().await else {
+        return;
+    };
+
+    assert!(ledger.verify_integrity().await.unwrap());
+    for segment_size in [1, 2, 16] {
+        assert!(ledger.verify_integrity_parallel(segment_size).await.unwrap().is_valid());
+    }
+}
+
+#[tokio::test]
+async fn sequential_and_parallel_checks_agree_on_a_tampered_chain() {
+    let Some((ledger, url, table, event_ids)) = three_transfers().await else {
+        return;
+    };
+    // Rewrite the middle transfer's amount, leaving its id and links alone
+    let record = ledger.get_record(&event_ids[1]).await.unwrap().unwrap();
+    let mut payload = serde_json::to_value(&record.event).unwrap();
+    payload["amount"]["amount"] = serde_json::json!("99999.00");
+    let pool = sqlx::PgPool::connect(&url).await.unwrap();
+    sqlx::query(&format!(
+        "UPDATE {} SET event_data = $1, event_bytes = $2 WHERE event_id = $3",
+        table
+    ))
+    .bind(&payload)
+    .bind(serde_json::to_vec(&payload).unwrap())
+    .bind(&event_ids[1])
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert!(!ledger.verify_integrity().await.unwrap());
+    assert!(!ledger.verify_since_checkpoint().await.unwrap());
+    for segment_size in [1, 2, 16] {
+        let report = ledger.verify_integrity_parallel(segment_size).await.unwrap();
+        assert_eq!(report.first_break.map(|b| b.event_id), Some(event_ids[1].clone()));
+    }
+}
+
+#[tokio::test]
+async fn unsigned_chain_detects_a_rewritten_nonce_or_sequence() {
+    for column in ["nonce", "sequence"] {
+        let Some((ledger, url, table, event_ids)) = three_transfers().await else {
+            return;
+        };
+        let pool = sqlx::PgPool::connect(&url).await.unwrap();
+        let value = if column == "nonce" { "'00'" } else { "sequence + 10" };
+        sqlx::query(&format!("UPDATE {} SET {} = {} WHERE event_id = $1", table, column, value))
+            .bind(&event_ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(!ledger.verify_integrity().await.unwrap(), "{} rewrite went unnoticed", column);
+        let report = ledger.verify_integrity_parallel(16).await.unwrap();
+        assert!(!report.is_valid(), "{} rewrite went unnoticed", column);
+    }
+}